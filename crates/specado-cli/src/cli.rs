@@ -62,6 +62,9 @@ pub enum Commands {
     /// Execute a provider request and get the normalized response
     Run(RunArgs),
     
+    /// Convert a provider-native request to another provider's format
+    Convert(ConvertArgs),
+    
     /// Manage configuration files and settings
     Config(ConfigArgs),
     
@@ -193,6 +196,64 @@ pub struct RunArgs {
     pub silent: bool,
 }
 
+/// Arguments for the convert command
+#[derive(Parser, Debug)]
+pub struct ConvertArgs {
+    /// Path to the provider-native request JSON file
+    #[arg(value_name = "REQUEST_FILE")]
+    pub request_file: PathBuf,
+
+    /// Source provider and model the request was written for (e.g. openai:gpt-5)
+    #[arg(long, value_name = "PROVIDER:MODEL")]
+    pub from: ModelRef,
+
+    /// Target provider and model to convert the request to (e.g. anthropic:claude-opus-4.1)
+    #[arg(long, value_name = "PROVIDER:MODEL")]
+    pub to: ModelRef,
+
+    /// Translation strictness mode
+    #[arg(short, long, value_enum, default_value = "warn")]
+    pub strict: StrictMode,
+
+    /// Show lossiness report
+    #[arg(long)]
+    pub show_lossiness: bool,
+
+    /// Output file path (stdout if not specified)
+    #[arg(long = "save-to")]
+    pub output_file: Option<PathBuf>,
+}
+
+/// A provider and model pair written as `PROVIDER:MODEL`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelRef {
+    /// Provider name or path to provider specification
+    pub provider: String,
+    /// Model ID or alias
+    pub model: String,
+}
+
+impl std::str::FromStr for ModelRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // Split on the last colon so provider paths with drive letters still work
+        match s.rsplit_once(':') {
+            Some((provider, model)) if !provider.is_empty() && !model.is_empty() => Ok(Self {
+                provider: provider.to_string(),
+                model: model.to_string(),
+            }),
+            _ => Err(format!("expected PROVIDER:MODEL, got '{}'", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for ModelRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.provider, self.model)
+    }
+}
+
 /// Arguments for the config command
 #[derive(Parser, Debug)]
 pub struct ConfigArgs {
@@ -438,4 +499,20 @@ mod tests {
         };
         assert_eq!(quiet_cli.verbosity_level(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_convert_model_refs() {
+        let cli = Cli::parse_from([
+            "specado", "convert", "--from", "openai:gpt-5", "--to", "anthropic:claude-opus-4.1", "request.json",
+        ]);
+        let Commands::Convert(args) = cli.command else {
+            panic!("expected convert command");
+        };
+        assert_eq!(args.from, ModelRef { provider: "openai".to_string(), model: "gpt-5".to_string() });
+        assert_eq!(args.to.provider, "anthropic");
+        assert_eq!(args.to.model, "claude-opus-4.1");
+
+        assert!("gpt-5".parse::<ModelRef>().is_err());
+        assert!("openai:".parse::<ModelRef>().is_err());
+    }
+}
@@ -8,6 +8,7 @@ pub use validate::handle_validate;
 pub use preview::handle_preview;
 pub use translate::handle_translate;
 pub use run::handle_run;
+pub use convert::handle_convert;
 pub use config::handle_config;
 pub use completions::handle_completions;
 
@@ -16,6 +17,7 @@ mod validate;
 mod preview;
 mod translate;
 mod run;
+mod convert;
 mod config;
 mod completions;
 mod utils;
\ No newline at end of file
@@ -0,0 +1,114 @@
+//! Convert command handler
+
+use crate::cli::ConvertArgs;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::logging::timing::Timer;
+use crate::output::OutputWriter;
+use std::fs;
+use tracing::{instrument, info, debug, error};
+
+use super::utils::load_model_provider_spec;
+
+/// Handle the convert command
+#[instrument(skip(config, output), fields(
+    file = %args.request_file.display(),
+    from = %args.from,
+    to = %args.to
+))]
+pub async fn handle_convert(
+    args: ConvertArgs,
+    config: &Config,
+    output: &mut OutputWriter,
+) -> Result<()> {
+    let _timer = Timer::with_details("convert_command", &format!("from: {}, to: {}", args.from, args.to));
+    info!("Starting convert operation");
+    output.info(&format!(
+        "Converting {} from {} to {}",
+        args.request_file.display(),
+        args.from,
+        args.to
+    ))?;
+
+    // Check if request file exists
+    if !args.request_file.exists() {
+        error!("Request file not found: {}", args.request_file.display());
+        return Err(Error::FileNotFound {
+            path: args.request_file.clone(),
+        });
+    }
+
+    // Load the provider-native request
+    debug!("Reading request file");
+    let content = fs::read_to_string(&args.request_file)?;
+    let request_json: serde_json::Value = serde_json::from_str(&content)?;
+
+    // Load source and target provider specs
+    let (source_spec, target_spec) = {
+        let _load_timer = Timer::new("provider_spec_loading");
+        debug!("Loading provider specifications: {} and {}", args.from, args.to);
+        (
+            load_model_provider_spec(&args.from.provider, &args.from.model, config)?,
+            load_model_provider_spec(&args.to.provider, &args.to.model, config)?,
+        )
+    };
+
+    let progress = output.spinner("Converting request...");
+
+    let conversion_result = {
+        let _conversion_timer = Timer::new("conversion_process");
+
+        let result = specado_core::convert(
+            &request_json,
+            (&source_spec, &args.from.model),
+            (&target_spec, &args.to.model),
+            args.strict.into(),
+        )?;
+
+        info!(
+            lossiness_items = result.lossiness.items.len(),
+            "Conversion completed"
+        );
+
+        result
+    };
+
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    output.success("✓ Conversion completed successfully")?;
+
+    match output.format() {
+        crate::cli::OutputFormat::Human => {
+            if args.show_lossiness && !conversion_result.lossiness.items.is_empty() {
+                output.section("Lossiness Report")?;
+                output.lossiness_report(&conversion_result.lossiness)?;
+            }
+
+            output.section("Converted Request")?;
+            output.data(&conversion_result.provider_request_json)?;
+        }
+        _ => {
+            // For machine formats, output the complete result
+            output.translation_result(&conversion_result)?;
+        }
+    }
+
+    // Save to file if requested
+    if let Some(output_file) = args.output_file {
+        let output_content = match output.format() {
+            crate::cli::OutputFormat::Yaml => {
+                serde_yaml::to_string(&conversion_result.provider_request_json)?
+            }
+            _ => {
+                serde_json::to_string_pretty(&conversion_result.provider_request_json)?
+            }
+        };
+
+        fs::write(&output_file, output_content)?;
+        output.success(&format!("✓ Output saved to {}", output_file.display()))?;
+    }
+
+    Ok(())
+}
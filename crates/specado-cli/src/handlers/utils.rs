@@ -6,6 +6,25 @@ use specado_core::ProviderSpec;
 use std::fs;
 use std::path::Path;
 
+/// Load the provider specification that declares a given model
+///
+/// Provider directories keep one spec file per model
+/// (`providers/{provider}/{model}.json`), so the model-specific file is
+/// preferred before falling back to [`load_provider_spec`].
+pub fn load_model_provider_spec(provider: &str, model: &str, config: &Config) -> Result<ProviderSpec> {
+    let model_paths = [
+        Path::new("providers").join(provider).join(format!("{}.json", model)),
+        Path::new("providers").join(provider).join(format!("{}.yaml", model)),
+        config.paths.providers_dir.join(provider).join(format!("{}.json", model)),
+        config.paths.providers_dir.join(provider).join(format!("{}.yaml", model)),
+    ];
+
+    match model_paths.into_iter().find(|p| p.exists()) {
+        Some(path) => load_provider_spec(&path.to_string_lossy(), config),
+        None => load_provider_spec(provider, config),
+    }
+}
+
 /// Load a provider specification
 pub fn load_provider_spec(provider: &str, config: &Config) -> Result<ProviderSpec> {
     // Check if provider is a file path
@@ -88,6 +88,9 @@ async fn run(cli: Cli) -> Result<()> {
         Commands::Run(args) => {
             handlers::handle_run(args, &config, &mut output).await
         }
+        Commands::Convert(args) => {
+            handlers::handle_convert(args, &config, &mut output).await
+        }
         Commands::Config(args) => {
             handlers::handle_config(args, &config, &mut output).await
         }
@@ -52,6 +52,7 @@ fn test_translation_result_formatting_human() {
         timestamp: "2025-01-17T10:30:00Z".to_string(),
         duration_ms: Some(150),
         strict_mode: StrictMode::Warn,
+        json_mode: false,
     };
 
     let translation_result = TranslationResult {
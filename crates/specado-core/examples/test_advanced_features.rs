@@ -25,9 +25,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         messages: vec![
             Message {
                 role: MessageRole::User,
-                content: "Explain quantum computing with deep reasoning".to_string(),
+                content: "Explain quantum computing with deep reasoning".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }
         ],
         tools: None,
@@ -38,6 +41,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         media: None,
         advanced: Some(advanced_params.clone()),
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     };
     println!("✅ PromptSpec with advanced parameters created successfully");
     
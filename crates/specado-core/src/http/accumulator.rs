@@ -0,0 +1,367 @@
+//! Stream accumulation into a final response
+//!
+//! This module folds normalized [`StreamEvent`]s back into the
+//! [`UniformResponse`] a sync call would have produced, so callers can
+//! stream to a UI and still keep one canonical response.
+
+use std::collections::BTreeMap;
+
+use futures_util::{Stream, StreamExt};
+use serde_json::{json, Map, Value};
+
+use super::normalizer::extract_json_content;
+use super::structured::apply_structured_output;
+use super::usage::extract_usage;
+use crate::translation::json_mode::emulates_json_mode;
+use crate::types::{
+    Candidate, FinishReason, JsonOutputConfig, ModelSpec, ReasoningBlock, ReasoningOutput, ResponseFormat,
+    StreamEvent, StructuredOutput, ToolCall, UniformResponse, UsagePaths,
+};
+use crate::{Result, StrictMode};
+
+/// Tool call being assembled from stream events
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: Option<String>,
+    name: String,
+    arguments: String,
+}
+
+/// One choice of the response being assembled
+#[derive(Debug, Default)]
+struct PartialChoice {
+    content: String,
+    tool_calls: BTreeMap<usize, PartialToolCall>,
+    finish_reason: Option<FinishReason>,
+}
+
+impl PartialChoice {
+    /// Content, finish reason and tool calls of the finished choice
+    fn finish(self) -> (String, FinishReason, Option<Vec<ToolCall>>) {
+        let tool_calls: Vec<ToolCall> = self.tool_calls.into_values()
+            .filter(|call| !call.name.is_empty())
+            .map(|call| ToolCall {
+                name: call.name,
+                arguments: parse_arguments(&call.arguments),
+                id: call.id,
+            })
+            .collect();
+        let tool_calls = if tool_calls.is_empty() { None } else { Some(tool_calls) };
+        (self.content, self.finish_reason.unwrap_or(FinishReason::Stop), tool_calls)
+    }
+}
+
+/// Accumulator that folds stream events into a UniformResponse
+#[derive(Debug)]
+pub struct StreamAccumulator {
+    model: String,
+    response_id: Option<String>,
+    choices: BTreeMap<usize, PartialChoice>,
+    reasoning: BTreeMap<usize, ReasoningBlock>,
+    usage: Option<Map<String, Value>>,
+    usage_paths: UsagePaths,
+    json_output: Option<JsonOutputConfig>,
+    json_mode: bool,
+    response_format: Option<(ResponseFormat, StrictMode)>,
+}
+
+impl StreamAccumulator {
+    /// Create a new accumulator for a model
+    pub fn new(model_id: impl Into<String>) -> Self {
+        Self {
+            model: model_id.into(),
+            response_id: None,
+            choices: BTreeMap::new(),
+            reasoning: BTreeMap::new(),
+            usage: None,
+            usage_paths: UsagePaths::conventional(),
+            json_output: None,
+            json_mode: false,
+            response_format: None,
+        }
+    }
+
+    /// Create an accumulator that reads usage with the model's stream usage paths
+    pub fn for_model(model: &ModelSpec) -> Self {
+        let mut accumulator = Self::new(model.id.clone());
+        if let Some(paths) = &model.response_normalization.stream.usage {
+            accumulator.usage_paths = paths.clone();
+        }
+        accumulator.json_output = Some(model.json_output.clone());
+        accumulator
+    }
+
+    /// Expect a JSON response; for models that emulate JSON mode through the
+    /// system prompt, the JSON document is extracted from the reply
+    pub fn with_json_mode(mut self, json_mode: bool) -> Self {
+        self.json_mode = json_mode;
+        self
+    }
+
+    /// Validate the content against the requested response format's JSON
+    /// Schema, as [`super::ResponseNormalizer::with_response_format`] does
+    pub fn with_response_format(mut self, format: ResponseFormat, strict_mode: StrictMode) -> Self {
+        self.json_mode |= !matches!(format, ResponseFormat::Text);
+        self.response_format = Some((format, strict_mode));
+        self
+    }
+
+    /// Consume a whole event stream and return the final response
+    pub async fn accumulate<S>(stream: S, model_id: impl Into<String>) -> Result<UniformResponse>
+    where
+        S: Stream<Item = Result<StreamEvent>> + Unpin,
+    {
+        Self::new(model_id).consume(stream).await
+    }
+
+    /// Consume a whole event stream from a model and return the final response
+    pub async fn accumulate_for_model<S>(stream: S, model: &ModelSpec) -> Result<UniformResponse>
+    where
+        S: Stream<Item = Result<StreamEvent>> + Unpin,
+    {
+        Self::for_model(model).consume(stream).await
+    }
+
+    /// Consume a whole event stream with this accumulator's settings
+    pub async fn consume<S>(mut self, mut stream: S) -> Result<UniformResponse>
+    where
+        S: Stream<Item = Result<StreamEvent>> + Unpin,
+    {
+        while let Some(event) = stream.next().await {
+            self.push(&event?);
+        }
+        self.finish()
+    }
+
+    /// Fold a single event into the response
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::ResponseStart { id } => {
+                self.response_id.get_or_insert_with(|| id.clone());
+            }
+            StreamEvent::TextDelta { choice, text } => self.choice(*choice).content.push_str(text),
+            StreamEvent::ReasoningDelta { index, text } => {
+                self.reasoning.entry(*index).or_default().text.get_or_insert_with(String::new).push_str(text);
+            }
+            StreamEvent::ReasoningSignature { index, signature } => {
+                self.reasoning.entry(*index).or_default().signature = Some(signature.clone());
+            }
+            StreamEvent::ReasoningRedacted { index, data } => {
+                self.reasoning.entry(*index).or_default().redacted = Some(data.clone());
+            }
+            StreamEvent::ToolCallStart { choice, index, id, name } => {
+                let call = self.choice(*choice).tool_calls.entry(*index).or_default();
+                call.id = id.clone();
+                call.name = name.clone();
+            }
+            StreamEvent::ToolCallArgsDelta { choice, index, delta } => {
+                self.choice(*choice).tool_calls.entry(*index).or_default().arguments.push_str(delta);
+            }
+            StreamEvent::ToolCallEnd { .. } => {}
+            StreamEvent::Finish { choice, finish_reason } => {
+                self.choice(*choice).finish_reason = Some(*finish_reason);
+            }
+            StreamEvent::Usage { usage } => {
+                // Providers may report usage in several events; later counts win
+                if let Some(fields) = usage.as_object() {
+                    let merged = self.usage.get_or_insert_with(Map::new);
+                    for (key, value) in fields {
+                        merged.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// The choice at `choice`, started on its first event
+    fn choice(&mut self, choice: usize) -> &mut PartialChoice {
+        self.choices.entry(choice).or_default()
+    }
+
+    /// Build the final response
+    ///
+    /// The first choice is the response; when several choices were streamed
+    /// each becomes a candidate, the first included. Fails when JSON mode
+    /// finds no JSON document, or in strict mode when the content does not
+    /// match the response format's schema.
+    pub fn finish(mut self) -> Result<UniformResponse> {
+        let (raw_metadata, usage) = match self.usage {
+            Some(usage) => {
+                let usage = Value::Object(usage);
+                let tokens = extract_usage(&usage, &self.usage_paths).ok().flatten();
+                (json!({ "usage": usage }), tokens)
+            }
+            None => (json!({}), None),
+        };
+
+        let reasoning_tokens = usage.as_ref().and_then(|u| u.reasoning_tokens);
+        let blocks: Vec<ReasoningBlock> = self.reasoning.into_values().collect();
+        let reasoning = (!blocks.is_empty() || reasoning_tokens.unwrap_or(0) > 0).then(|| ReasoningOutput {
+            text: blocks.iter()
+                .filter_map(|block| block.text.as_deref())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            blocks,
+            tokens: reasoning_tokens,
+        });
+
+        let several = self.choices.len() > 1;
+        let first = self.choices.remove(&0).unwrap_or_default();
+        let (mut content, finish_reason, tool_calls) = first.finish();
+        let mut structured_output = None;
+        if let Some(config) = self.json_output.as_ref().filter(|config| self.json_mode && emulates_json_mode(config)) {
+            let (json, parsed) = extract_json_content(&content, config)?;
+            content = json;
+            structured_output = Some(StructuredOutput { parsed: Some(parsed), errors: Vec::new() });
+        }
+        let mut candidates = Vec::new();
+        if several {
+            candidates.push(Candidate {
+                index: 0,
+                content: content.clone(),
+                finish_reason,
+                tool_calls: tool_calls.clone(),
+                logprobs: None,
+            });
+            for (index, choice) in self.choices {
+                let (content, finish_reason, tool_calls) = choice.finish();
+                candidates.push(Candidate { index, content, finish_reason, tool_calls, logprobs: None });
+            }
+        }
+
+        let mut response = UniformResponse {
+            model: self.model,
+            content,
+            finish_reason,
+            tool_calls,
+            raw_metadata,
+            usage,
+            structured_output,
+            reasoning,
+            candidates,
+            logprobs: None,
+            citations: Vec::new(),
+            response_id: self.response_id,
+            lossiness: None,
+        };
+        if let Some((format, strict_mode)) = &self.response_format {
+            apply_structured_output(&mut response, format, *strict_mode)?;
+        }
+        Ok(response)
+    }
+}
+
+/// Parse reassembled tool arguments, keeping the raw text if it is not JSON
+pub(crate) fn parse_arguments(arguments: &str) -> Value {
+    if arguments.trim().is_empty() {
+        return json!({});
+    }
+    serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulates_text_and_tools() {
+        let mut acc = StreamAccumulator::new("test-model");
+        for event in [
+            StreamEvent::TextDelta { choice: 0, text: "Hello".to_string() },
+            StreamEvent::TextDelta { choice: 0, text: " there".to_string() },
+            StreamEvent::ToolCallStart { choice: 0, index: 2, id: Some("b".to_string()), name: "second".to_string() },
+            StreamEvent::ToolCallStart { choice: 0, index: 1, id: Some("a".to_string()), name: "first".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 1, delta: "{\"x\":".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 2, delta: "not json".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 1, delta: "1}".to_string() },
+            StreamEvent::ToolCallEnd { choice: 0, index: 1 },
+            StreamEvent::ToolCallEnd { choice: 0, index: 2 },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::ToolCall },
+        ] {
+            acc.push(&event);
+        }
+
+        let response = acc.finish().unwrap();
+        assert_eq!(response.content, "Hello there");
+        assert_eq!(response.finish_reason, FinishReason::ToolCall);
+
+        let calls = response.tool_calls.unwrap();
+        assert_eq!(calls[0].name, "first");
+        assert_eq!(calls[0].arguments, json!({"x": 1}));
+        assert_eq!(calls[1].id.as_deref(), Some("b"));
+        assert_eq!(calls[1].arguments, json!("not json"));
+    }
+
+    #[test]
+    fn test_accumulates_candidates() {
+        let mut acc = StreamAccumulator::new("test-model");
+        for event in [
+            StreamEvent::ResponseStart { id: "resp_1".to_string() },
+            StreamEvent::TextDelta { choice: 0, text: "Paris.".to_string() },
+            StreamEvent::TextDelta { choice: 1, text: "It is Paris".to_string() },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::Stop },
+            StreamEvent::Finish { choice: 1, finish_reason: FinishReason::Length },
+        ] {
+            acc.push(&event);
+        }
+
+        let response = acc.finish().unwrap();
+        assert_eq!(response.response_id.as_deref(), Some("resp_1"));
+        assert_eq!(response.content, "Paris.");
+        assert_eq!(response.candidates.len(), 2);
+        assert_eq!(response.candidates[0].content, "Paris.");
+        assert_eq!(response.candidates[1].content, "It is Paris");
+        assert_eq!(response.candidates[1].finish_reason, FinishReason::Length);
+    }
+
+    #[test]
+    fn test_accumulates_reasoning_blocks() {
+        let mut acc = StreamAccumulator::new("test-model");
+        for event in [
+            StreamEvent::ReasoningDelta { index: 0, text: "First".to_string() },
+            StreamEvent::ReasoningDelta { index: 0, text: " thought".to_string() },
+            StreamEvent::ReasoningSignature { index: 0, signature: "sig".to_string() },
+            StreamEvent::ReasoningDelta { index: 2, text: "Second".to_string() },
+        ] {
+            acc.push(&event);
+        }
+
+        let reasoning = acc.finish().unwrap().reasoning.unwrap();
+        assert_eq!(reasoning.text, "First thought\n\nSecond");
+        assert_eq!(reasoning.blocks[0].signature.as_deref(), Some("sig"));
+        assert_eq!(reasoning.blocks[1].text.as_deref(), Some("Second"));
+    }
+
+    #[test]
+    fn test_extracts_emulated_json() {
+        let content = std::fs::read_to_string(format!(
+            "{}/../../providers/anthropic/claude-opus-4.1.json",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let spec: crate::ProviderSpec = serde_json::from_str(&content).unwrap();
+        let model = &spec.models[0];
+
+        let mut acc = StreamAccumulator::for_model(model).with_json_mode(true);
+        acc.push(&StreamEvent::TextDelta { choice: 0, text: "\"answer\": 42}".to_string() });
+        let response = acc.finish().unwrap();
+        assert_eq!(response.content, "{\"answer\": 42}");
+        assert_eq!(response.structured_output.unwrap().parsed, Some(json!({"answer": 42})));
+
+        let mut acc = StreamAccumulator::for_model(model).with_json_mode(true);
+        acc.push(&StreamEvent::TextDelta { choice: 0, text: "No JSON here".to_string() });
+        assert!(acc.finish().is_err());
+    }
+
+    #[test]
+    fn test_merges_usage_events() {
+        let mut acc = StreamAccumulator::new("test-model");
+        acc.push(&StreamEvent::Usage { usage: json!({"input_tokens": 25, "output_tokens": 1}) });
+        acc.push(&StreamEvent::Usage { usage: json!({"output_tokens": 32}) });
+
+        let response = acc.finish().unwrap();
+        assert_eq!(response.raw_metadata, json!({"usage": {"input_tokens": 25, "output_tokens": 32}}));
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+        assert!(response.tool_calls.is_none());
+    }
+}
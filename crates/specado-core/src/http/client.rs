@@ -741,7 +741,7 @@ mod tests {
     use crate::types::{
         ProviderInfo, Endpoints, InputModes, JsonOutputConfig, ToolingConfig,
         Constraints, ConstraintLimits, Mappings, ResponseNormalization,
-        SyncNormalization, StreamNormalization, EventSelector
+        SyncNormalization, ArgsEncoding, StreamNormalization, EventSelector
     };
     use std::collections::HashMap;
     
@@ -788,12 +788,17 @@ mod tests {
                         parallel_tool_calls_default: false,
                         can_disable_parallel_tool_calls: false,
                         disable_switch: None,
+                        rendering: None,
                     },
                     json_output: JsonOutputConfig {
                         native_param: false,
                         strategy: "none".to_string(),
+                        instruction: None,
+                        prefill: false,
                     },
                     capabilities: None,
+                    transformations: Vec::new(),
+                    message_rendering: None,
                     parameters: serde_json::json!({}),
                     constraints: Constraints {
                         system_prompt_location: "message_role".to_string(),
@@ -803,7 +808,9 @@ mod tests {
                         limits: ConstraintLimits {
                             max_tool_schema_bytes: 100000,
                             max_system_prompt_bytes: 10000,
+                            context_window: None,
                         },
+                        extensions: None,
                     },
                     mappings: Mappings {
                         paths: HashMap::new(),
@@ -814,6 +821,26 @@ mod tests {
                             content_path: "$.content".to_string(),
                             finish_reason_path: "$.finish_reason".to_string(),
                             finish_reason_map: HashMap::new(),
+                            usage_path: None,
+                            usage: None,
+                            tool_calls_path: None,
+                            tool_name_path: None,
+                            tool_args_path: None,
+                            tool_id_path: None,
+                            args_encoding: ArgsEncoding::default(),
+                            reasoning_path: None,
+                            reasoning_text_path: None,
+                            reasoning_signature_path: None,
+                            reasoning_redacted_path: None,
+                            choices_path: None,
+                            logprobs_path: None,
+                            logprob_token_path: None,
+                            logprob_value_path: None,
+                            top_logprobs_path: None,
+                            citations_path: None,
+                            citation_document_path: None,
+                            citation_text_path: None,
+                            response_id_path: None,
                         },
                         stream: StreamNormalization {
                             protocol: "sse".to_string(),
@@ -821,8 +848,10 @@ mod tests {
                                 type_path: "$.type".to_string(),
                                 routes: vec![],
                             },
+                            usage: None,
                         },
                     },
+                    extensions: None,
                 },
             ],
         }
@@ -14,6 +14,11 @@ pub mod error;
 pub mod retry;
 pub mod client;
 pub mod normalizer;
+pub mod protocol;
+pub mod accumulator;
+pub mod structured;
+pub mod usage;
+pub mod stream;
 pub mod fallback;
 pub mod diagnostics;
 pub mod timeout;
@@ -30,6 +35,11 @@ pub use error::{HttpError, ErrorClassification};
 pub use retry::{RetryPolicy, RetryDecision};
 pub use client::{HttpClient, HttpClientConfig};
 pub use normalizer::{ResponseNormalizer, normalize_response};
+pub use protocol::{StreamProtocol, StreamFrame, FrameDecoder, SseDecoder, NdjsonDecoder};
+pub use stream::{StreamNormalizer, normalize_stream};
+pub use accumulator::StreamAccumulator;
+pub use structured::{apply_structured_output, validate_structured_output};
+pub use usage::extract_usage;
 pub use fallback::{FallbackHandler, FallbackConfig, FallbackAttempt};
 pub use diagnostics::{ErrorDiagnostics, DiagnosticsBuilder};
 pub use timeout::{TimeoutConfig, RequestTimeout};
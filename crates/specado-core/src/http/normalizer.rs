@@ -2,109 +2,460 @@
 //!
 //! This module normalizes provider-specific response formats into the
 //! standard UniformResponse structure using JSONPath rules from ProviderSpec.
+//! Models that declare a `choices_path` have every choice normalized into a
+//! candidate, and per-token log probabilities are read with the model's
+//! logprob paths. Citations are normalized to the IDs of the request's RAG
+//! documents. Whatever the rules cannot carry over (unmapped finish reasons,
+//! extra choices, unknown content blocks, unparseable tool arguments) is
+//! reported in a LossinessReport, with paths pointing into the provider
+//! response.
 
 use serde_json::Value;
 use crate::types::{
-    UniformResponse, FinishReason, ToolCall,
-    ModelSpec, SyncNormalization,
+    UniformResponse, Candidate, FinishReason, ToolCall, ArgsEncoding, ReasoningBlock, ReasoningOutput,
+    TokenLogprob, TopLogprob, Citation, ModelSpec, SyncNormalization, ResponseFormat, UsagePaths,
+    JsonOutputConfig, StructuredOutput,
 };
+use super::accumulator::parse_arguments;
+use super::structured::apply_structured_output;
+use super::usage::extract_usage;
+use crate::response::TokenUsage;
+use crate::translation::json_mode;
+use crate::translation::rag::inline_citations;
 use crate::translation::jsonpath::JSONPath;
-use crate::Result;
+use crate::translation::LossinessTracker;
+use crate::{Error, LossinessReport, Result, StrictMode};
 
 /// Response normalizer that converts provider responses to UniformResponse
 #[derive(Debug)]
 pub struct ResponseNormalizer {
     /// Model specification containing normalization rules
     model_spec: ModelSpec,
+    /// Whether the request asked for JSON output
+    json_mode: bool,
+    /// Response format to validate the content against
+    response_format: Option<(ResponseFormat, StrictMode)>,
+    /// Strictness applied to response lossiness
+    strict_mode: StrictMode,
+    /// IDs of the request's RAG documents, in order
+    document_ids: Vec<String>,
 }
 
 impl ResponseNormalizer {
     /// Create a new normalizer for a specific model
     pub fn new(model_spec: ModelSpec) -> Self {
-        Self { model_spec }
+        Self {
+            model_spec,
+            json_mode: false,
+            response_format: None,
+            strict_mode: StrictMode::Warn,
+            document_ids: Vec::new(),
+        }
+    }
+
+    /// Set the strictness for response lossiness; in strict mode a response
+    /// that cannot be fully represented fails normalization
+    pub fn with_strict_mode(mut self, strict_mode: StrictMode) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+
+    /// Expect a JSON response; for models that emulate JSON mode through the
+    /// system prompt, the JSON document is extracted from the reply
+    pub fn with_json_mode(mut self, json_mode: bool) -> Self {
+        self.json_mode = json_mode;
+        self
+    }
+
+    /// Resolve citations against the request's RAG documents; citations by
+    /// index and IDs written inline in the content refer to these
+    pub fn with_documents(mut self, document_ids: Vec<String>) -> Self {
+        self.document_ids = document_ids;
+        self
+    }
+
+    /// Validate the content against the requested response format's JSON
+    /// Schema; in strict mode a mismatch fails normalization. A JSON format
+    /// also expects a JSON response, as with [`Self::with_json_mode`].
+    pub fn with_response_format(mut self, format: ResponseFormat, strict_mode: StrictMode) -> Self {
+        self.json_mode |= !matches!(format, ResponseFormat::Text);
+        self.response_format = Some((format, strict_mode));
+        self
     }
     
     /// Normalize a provider response to UniformResponse format
+    ///
+    /// Returns the response together with a report of what it could not
+    /// represent.
     pub fn normalize_response(
         &self,
         provider_response: &Value,
         model_id: &str,
-    ) -> Result<UniformResponse> {
+    ) -> Result<(UniformResponse, LossinessReport)> {
         let norm_config = &self.model_spec.response_normalization.sync;
+        let mut tracker = LossinessTracker::new(self.strict_mode);
         
         // Extract content using JSONPath
-        let content = self.extract_content(provider_response, norm_config)?;
+        let (content, json_output) = self.extract_answer(provider_response, norm_config)?;
         
         // Extract and map finish reason
-        let finish_reason = self.extract_finish_reason(provider_response, norm_config)?;
+        let finish_reason = self.extract_finish_reason(provider_response, norm_config, &mut tracker)?;
         
         // Extract tool calls if present
-        let tool_calls = self.extract_tool_calls(provider_response, norm_config)?;
+        let tool_calls = self.extract_tool_calls(provider_response, norm_config, &mut tracker)?;
+
+        // Extract token usage
+        let usage = self.extract_usage(provider_response, norm_config)?;
+        
+        // Extract reasoning blocks and summaries
+        let reasoning_tokens = usage.as_ref().and_then(|u| u.reasoning_tokens);
+        let reasoning = self.extract_reasoning(provider_response, norm_config, reasoning_tokens)?;
+        
+        // Extract per-token log probabilities
+        let logprobs = self.extract_logprobs(provider_response, norm_config)?;
         
-        Ok(UniformResponse {
+        // Extract citations of the request's documents
+        let citations = self.extract_citations(provider_response, norm_config, &content, &mut tracker)?;
+        
+        // Extract the response identifier for conversation continuation
+        let response_id = JSONPath::parse(norm_config.response_id_path.as_deref().unwrap_or("$.id"))?
+            .execute(provider_response)?
+            .first()
+            .and_then(|id| id.as_str())
+            .map(str::to_string);
+        
+        let mut response = UniformResponse {
             model: model_id.to_string(),
             content,
             finish_reason,
             tool_calls,
             raw_metadata: provider_response.clone(),
-        })
+            usage,
+            structured_output: json_output.map(|parsed| StructuredOutput { parsed: Some(parsed), errors: Vec::new() }),
+            reasoning,
+            candidates: Vec::new(),
+            logprobs,
+            citations,
+            response_id,
+            lossiness: None,
+        };
+        response.candidates = self.extract_candidates(provider_response, norm_config, &response, &mut tracker)?;
+
+        if let Some((format, strict_mode)) = &self.response_format {
+            apply_structured_output(&mut response, format, *strict_mode)?;
+        }
+
+        // Report choices and content blocks no rule selected
+        self.report_unselected(provider_response, norm_config, &mut tracker)?;
+
+        let report = tracker.build_report();
+        if self.strict_mode == StrictMode::Strict && !report.items.is_empty() {
+            let paths: Vec<&str> = report.items.iter().map(|item| item.path.as_str()).collect();
+            return Err(Error::StrictnessViolation {
+                message: format!("Response could not be fully represented: {}", paths.join(", ")),
+                mode: self.strict_mode,
+                severity: report.max_severity,
+            });
+        }
+
+        Ok((response, report))
+    }
+
+    /// Normalize every choice of a multi-choice response into a candidate
+    ///
+    /// The first choice is the response itself; the others are read with the
+    /// content, finish reason, tool call and logprob paths rebased onto the
+    /// choice.
+    fn extract_candidates(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+        first: &UniformResponse,
+        tracker: &mut LossinessTracker,
+    ) -> Result<Vec<Candidate>> {
+        let (Some(choices_path), Some(choice_config)) = (config.choices_path.as_deref(), choice_config(config)) else {
+            return Ok(Vec::new());
+        };
+        let selected = JSONPath::parse(choices_path)?.execute(response)?;
+        let choices: Vec<&Value> = match selected.as_slice() {
+            [Value::Array(choices)] => choices.iter().collect(),
+            _ => selected,
+        };
+        if choices.len() < 2 {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = vec![Candidate {
+            index: 0,
+            content: first.content.clone(),
+            finish_reason: first.finish_reason,
+            tool_calls: first.tool_calls.clone(),
+            logprobs: first.logprobs.clone(),
+        }];
+        for (index, choice) in choices.into_iter().enumerate().skip(1) {
+            let mut choice_tracker = LossinessTracker::new(self.strict_mode);
+            candidates.push(Candidate {
+                index,
+                content: self.extract_answer(choice, &choice_config)?.0,
+                finish_reason: self.extract_finish_reason(choice, &choice_config, &mut choice_tracker)?,
+                tool_calls: self.extract_tool_calls(choice, &choice_config, &mut choice_tracker)?,
+                logprobs: self.extract_logprobs(choice, &choice_config)?,
+            });
+
+            // Point the choice's items into the whole response
+            for mut item in choice_tracker.build_report().items {
+                let rest = item.path.strip_prefix('$').unwrap_or(&item.path).to_string();
+                item.path = format!("{}[{}]{}", choices_path, index, rest);
+                tracker.add_item(item);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Report choices that are not normalized and content blocks that none
+    /// of the content, tool call, reasoning or citation paths selected
+    fn report_unselected(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+        tracker: &mut LossinessTracker,
+    ) -> Result<()> {
+        // Choices are normalized as candidates when their paths can be
+        // rebased onto each choice; otherwise only the first is read
+        if let Some(choices_path) = config.choices_path.as_deref().filter(|_| choice_config(config).is_none()) {
+            if let [Value::Array(choices)] = JSONPath::parse(choices_path)?.execute(response)?.as_slice() {
+                for (index, choice) in choices.iter().enumerate().skip(1) {
+                    tracker.add_dropped(
+                        &format!("{}[{}]", choices_path, index),
+                        "Only the first choice is normalized",
+                        Some(choice.clone()),
+                    );
+                }
+            }
+        }
+
+        let content = JSONPath::parse(&config.content_path)?.execute(response)?;
+        let mut selected = Vec::new();
+        let paths = [config.tool_calls_path.as_deref(), config.reasoning_path.as_deref(), config.citations_path.as_deref()];
+        for path in paths.into_iter().flatten() {
+            selected.extend(JSONPath::parse(path)?.execute(response)?);
+        }
+
+        // A selected block array only contributes the text of its blocks
+        for blocks in content.iter().filter_map(|value| value.as_array()) {
+            for block in blocks {
+                let taken = block_text(block).is_some()
+                    || selected.iter().any(|s| std::ptr::eq(*s, block) || contains(block, s));
+                if !taken {
+                    tracker.add_dropped(
+                        &locate(response, block).unwrap_or_else(|| config.content_path.clone()),
+                        "Content block has no text",
+                        Some(block.clone()),
+                    );
+                }
+            }
+        }
+
+        selected.extend(content);
+        report_unselected_blocks(response, "$", &selected, tracker);
+        Ok(())
     }
     
-    /// Extract content from the response
-    fn extract_content(
+    /// Extract the content, undoing JSON mode emulation; the parsed JSON
+    /// document is returned alongside it
+    fn extract_answer(&self, response: &Value, config: &SyncNormalization) -> Result<(String, Option<Value>)> {
+        let content = self.extract_content(response, config)?;
+        if self.json_mode && json_mode::emulates_json_mode(&self.model_spec.json_output) {
+            let (json, parsed) = extract_json_content(&content, &self.model_spec.json_output)?;
+            return Ok((json, Some(parsed)));
+        }
+        Ok((content, None))
+    }
+
+    /// Extract token usage with the model's usage paths
+    fn extract_usage(
         &self,
         response: &Value,
         config: &SyncNormalization,
-    ) -> Result<String> {
-        // Special handling for Anthropic content blocks
-        if config.content_path.contains("$.content") && config.content_path.contains("text") {
-            // Try to extract text from Anthropic-style content array
-            if let Some(content_array) = response.get("content").and_then(|c| c.as_array()) {
-                // Collect all text blocks
-                let text_parts: Vec<String> = content_array.iter()
-                    .filter_map(|block| {
-                        if block.get("type").and_then(|t| t.as_str()) == Some("text") {
-                            block.get("text").and_then(|t| t.as_str()).map(|s| s.to_string())
-                        } else {
-                            None
+    ) -> Result<Option<TokenUsage>> {
+        let path = JSONPath::parse(config.usage_path.as_deref().unwrap_or("$.usage"))?;
+        let Some(usage) = path.execute(response)?.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let paths = config.usage.clone().unwrap_or_else(UsagePaths::conventional);
+        extract_usage(usage, &paths)
+    }
+
+    /// Extract reasoning with the model's reasoning paths
+    ///
+    /// Returns `None` when the response has no reasoning blocks and reports
+    /// no reasoning tokens.
+    fn extract_reasoning(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+        tokens: Option<u64>,
+    ) -> Result<Option<ReasoningOutput>> {
+        let mut blocks = Vec::new();
+        
+        if let Some(path) = config.reasoning_path.as_deref() {
+            let selected = JSONPath::parse(path)?.execute(response)?;
+            let items: Vec<&Value> = match selected.as_slice() {
+                [Value::Array(items)] => items.iter().collect(),
+                _ => selected,
+            };
+            
+            let text_path = JSONPath::parse(config.reasoning_text_path.as_deref().unwrap_or("$.text"))?;
+            let signature_path = JSONPath::parse(config.reasoning_signature_path.as_deref().unwrap_or("$.signature"))?;
+            let redacted_path = JSONPath::parse(config.reasoning_redacted_path.as_deref().unwrap_or("$.data"))?;
+            
+            for item in items {
+                let block = match item {
+                    Value::String(text) => ReasoningBlock { text: Some(text.clone()), ..Default::default() },
+                    _ => {
+                        // Summaries may be split over several parts
+                        let texts: Vec<&str> = text_path.execute(item)?.into_iter()
+                            .filter_map(|t| t.as_str())
+                            .collect();
+                        ReasoningBlock {
+                            text: Some(texts.concat()).filter(|_| !texts.is_empty()),
+                            signature: signature_path.execute(item)?.first()
+                                .and_then(|s| s.as_str())
+                                .map(|s| s.to_string()),
+                            redacted: redacted_path.execute(item)?.first()
+                                .and_then(|d| d.as_str())
+                                .map(|d| d.to_string()),
                         }
-                    })
-                    .collect();
-                
-                if !text_parts.is_empty() {
-                    return Ok(text_parts.join(""));
+                    }
+                };
+                if block != ReasoningBlock::default() {
+                    blocks.push(block);
                 }
             }
         }
         
-        // Standard JSONPath extraction
-        let path = JSONPath::parse(&config.content_path)?;
-        let results = path.execute(response)?;
+        if blocks.is_empty() && tokens.unwrap_or(0) == 0 {
+            return Ok(None);
+        }
         
-        // Get the first result and convert to string
-        if let Some(value) = results.first() {
-            match value {
-                Value::String(s) => Ok(s.clone()),
-                Value::Null => Ok(String::new()), // Handle null content explicitly
-                Value::Array(arr) => {
-                    // Handle array of content blocks
-                    let text_parts: Vec<String> = arr.iter()
-                        .filter_map(|v| {
-                            if let Some(text) = v.get("text").and_then(|t| t.as_str()) {
-                                Some(text.to_string())
-                            } else {
-                                v.as_str().map(|s| s.to_string())
-                            }
-                        })
-                        .collect();
-                    Ok(text_parts.join(""))
+        let text = blocks.iter()
+            .filter_map(|block| block.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(Some(ReasoningOutput { text, blocks, tokens }))
+    }
+    
+    /// Extract per-token log probabilities with the model's logprob paths
+    ///
+    /// Returns `None` when the model declares no logprobs path or the
+    /// response carries none.
+    fn extract_logprobs(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+    ) -> Result<Option<Vec<TokenLogprob>>> {
+        let Some(path) = config.logprobs_path.as_deref() else {
+            return Ok(None);
+        };
+        let selected = JSONPath::parse(path)?.execute(response)?;
+        let entries: Vec<&Value> = match selected.as_slice() {
+            [Value::Array(entries)] => entries.iter().collect(),
+            _ => selected,
+        };
+        
+        let token_path = JSONPath::parse(config.logprob_token_path.as_deref().unwrap_or("$.token"))?;
+        let value_path = JSONPath::parse(config.logprob_value_path.as_deref().unwrap_or("$.logprob"))?;
+        let top_path = JSONPath::parse(config.top_logprobs_path.as_deref().unwrap_or("$.top_logprobs"))?;
+        let read = |entry: &Value| -> Result<Option<(String, f64)>> {
+            let token = token_path.execute(entry)?.first().and_then(|t| t.as_str()).map(str::to_string);
+            let logprob = value_path.execute(entry)?.first().and_then(|l| l.as_f64());
+            Ok(token.zip(logprob))
+        };
+        
+        let mut logprobs = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let Some((token, logprob)) = read(entry)? else {
+                continue;
+            };
+            let alternatives: Vec<&Value> = match top_path.execute(entry)?.as_slice() {
+                [Value::Array(alternatives)] => alternatives.iter().collect(),
+                selected => selected.to_vec(),
+            };
+            let mut top_logprobs = Vec::with_capacity(alternatives.len());
+            for alternative in alternatives {
+                if let Some((token, logprob)) = read(alternative)? {
+                    top_logprobs.push(TopLogprob { token, logprob });
                 }
-                _ => Ok(value.to_string()),
             }
-        } else {
-            // If no content found, return empty string
-            Ok(String::new())
+            logprobs.push(TokenLogprob { token, logprob, top_logprobs });
         }
+        
+        Ok(Some(logprobs).filter(|logprobs| !logprobs.is_empty()))
+    }
+    
+    /// Extract citations with the model's citation paths, or from document
+    /// IDs written inline in the content
+    fn extract_citations(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+        content: &str,
+        tracker: &mut LossinessTracker,
+    ) -> Result<Vec<Citation>> {
+        let Some(path) = config.citations_path.as_deref() else {
+            return Ok(inline_citations(content, &self.document_ids));
+        };
+        let selected = JSONPath::parse(path)?.execute(response)?;
+        let entries: Vec<&Value> = match selected.as_slice() {
+            [Value::Array(entries)] => entries.iter().collect(),
+            _ => selected,
+        };
+        
+        let document_path = JSONPath::parse(config.citation_document_path.as_deref().unwrap_or("$.document_id"))?;
+        let text_path = JSONPath::parse(config.citation_text_path.as_deref().unwrap_or("$.text"))?;
+        
+        let mut citations = Vec::new();
+        for entry in entries {
+            let text = text_path.execute(entry)?.first().and_then(|t| t.as_str()).map(str::to_string);
+            let documents: Vec<&Value> = document_path.execute(entry)?.into_iter()
+                .flat_map(|d| match d {
+                    Value::Array(documents) => documents.iter().collect(),
+                    _ => vec![d],
+                })
+                .collect();
+            for document in documents {
+                // Providers cite by document ID or by position in the request
+                let document_id = match document {
+                    Value::String(id) => Some(id.clone()),
+                    _ => document.as_u64().and_then(|index| self.document_ids.get(index as usize).cloned()),
+                };
+                match document_id {
+                    Some(document_id) => citations.push(Citation { document_id, text: text.clone() }),
+                    None => tracker.add_dropped(
+                        &locate(response, entry).unwrap_or_else(|| path.to_string()),
+                        "Citation does not refer to a document of the request",
+                        Some(entry.clone()),
+                    ),
+                }
+            }
+        }
+        Ok(citations)
+    }
+    
+    /// Extract content from the response
+    ///
+    /// Several matches, e.g. a filter over content blocks, are concatenated.
+    fn extract_content(
+        &self,
+        response: &Value,
+        config: &SyncNormalization,
+    ) -> Result<String> {
+        let path = JSONPath::parse(&config.content_path)?;
+        let results = path.execute(response)?;
+        
+        // No match yields empty content
+        Ok(results.into_iter().map(content_text).collect())
     }
     
     /// Extract and map finish reason
@@ -112,18 +463,23 @@ impl ResponseNormalizer {
         &self,
         response: &Value,
         config: &SyncNormalization,
+        tracker: &mut LossinessTracker,
     ) -> Result<FinishReason> {
         let path = JSONPath::parse(&config.finish_reason_path)?;
         let results = path.execute(response)?;
         
         if let Some(value) = results.first() {
             if let Some(reason_str) = value.as_str() {
-                // Map using the provider's mapping table
-                if let Some(mapped) = config.finish_reason_map.get(reason_str) {
-                    return self.parse_finish_reason(mapped);
+                let reason = map_finish_reason(reason_str, config);
+                if reason == FinishReason::Other {
+                    tracker.add_map_fallback(
+                        &locate(response, value).unwrap_or_else(|| config.finish_reason_path.clone()),
+                        &format!("Unmapped finish reason '{}' normalized to 'other'", reason_str),
+                        Some((*value).clone()),
+                        serde_json::to_value(FinishReason::Other).ok(),
+                    );
                 }
-                // Try to parse directly if not in mapping
-                return self.parse_finish_reason(reason_str);
+                return Ok(reason);
             }
         }
         
@@ -131,106 +487,199 @@ impl ResponseNormalizer {
         Ok(FinishReason::Stop)
     }
     
-    /// Parse finish reason string to enum
-    fn parse_finish_reason(&self, reason: &str) -> Result<FinishReason> {
-        Ok(match reason.to_lowercase().as_str() {
-            "stop" | "end_turn" => FinishReason::Stop,
-            "length" | "max_tokens" => FinishReason::Length,
-            "tool_call" | "tool_calls" | "tool_use" => FinishReason::ToolCall,
-            "end_conversation" | "end" => FinishReason::EndConversation,
-            _ => FinishReason::Other,
-        })
-    }
-    
-    /// Extract tool calls from the response
+    /// Extract tool calls with the model's tool call paths
     fn extract_tool_calls(
         &self,
         response: &Value,
-        _config: &SyncNormalization,
+        config: &SyncNormalization,
+        tracker: &mut LossinessTracker,
     ) -> Result<Option<Vec<ToolCall>>> {
-        // Try common patterns for tool calls
-        let tool_calls = self.try_extract_openai_tools(response)
-            .or_else(|| self.try_extract_anthropic_tools(response));
+        let Some(path) = config.tool_calls_path.as_deref() else {
+            return Ok(None);
+        };
+        let selected = JSONPath::parse(path)?.execute(response)?;
         
-        Ok(tool_calls)
+        // A path to the array of calls selects one value; a filter selects each call
+        let calls: Vec<&Value> = match selected.as_slice() {
+            [Value::Array(calls)] => calls.iter().collect(),
+            _ => selected,
+        };
+        
+        let name_path = JSONPath::parse(config.tool_name_path.as_deref().unwrap_or("$.name"))?;
+        let args_path = JSONPath::parse(config.tool_args_path.as_deref().unwrap_or("$.arguments"))?;
+        let id_path = JSONPath::parse(config.tool_id_path.as_deref().unwrap_or("$.id"))?;
+        
+        let mut tool_calls = Vec::with_capacity(calls.len());
+        for call in calls {
+            let Some(name) = name_path.execute(call)?.first().and_then(|n| n.as_str()) else {
+                tracker.add_dropped(
+                    &locate(response, call).unwrap_or_else(|| path.to_string()),
+                    "Tool call has no name",
+                    Some(call.clone()),
+                );
+                continue;
+            };
+            
+            let arguments = match (args_path.execute(call)?.first(), config.args_encoding) {
+                (Some(Value::String(args)), ArgsEncoding::JsonString) => {
+                    let parsed = parse_arguments(args);
+                    if parsed.is_string() {
+                        tracker.add_map_fallback(
+                            &locate(response, call).map_or_else(|| path.to_string(), |p| format!("{}.arguments", p)),
+                            &format!("Arguments of tool call '{}' are not valid JSON and are kept as text", name),
+                            Some(Value::String(args.clone())),
+                            Some(parsed.clone()),
+                        );
+                    }
+                    parsed
+                }
+                (Some(args), _) => (*args).clone(),
+                (None, _) => Value::Null,
+            };
+            
+            let id = id_path.execute(call)?.first()
+                .and_then(|i| i.as_str())
+                .map(|s| s.to_string());
+            
+            tool_calls.push(ToolCall {
+                name: name.to_string(),
+                arguments,
+                id,
+            });
+        }
+        
+        Ok(Some(tool_calls).filter(|calls| !calls.is_empty()))
     }
-    
-    /// Try to extract OpenAI-style tool calls
-    fn try_extract_openai_tools(&self, response: &Value) -> Option<Vec<ToolCall>> {
-        // OpenAI format: choices[0].message.tool_calls
-        response.get("choices")
-            .and_then(|c| c.get(0))
-            .and_then(|choice| choice.get("message"))
-            .and_then(|msg| msg.get("tool_calls"))
-            .and_then(|tools| tools.as_array())
-            .map(|tools| {
-                tools.iter()
-                    .filter_map(|tool| {
-                        let name = tool.get("function")
-                            .and_then(|f| f.get("name"))
-                            .and_then(|n| n.as_str())?;
-                        
-                        let arguments = tool.get("function")
-                            .and_then(|f| f.get("arguments"))
-                            .cloned()
-                            .unwrap_or(Value::Null);
-                        
-                        let id = tool.get("id")
-                            .and_then(|i| i.as_str())
-                            .map(|s| s.to_string());
-                        
-                        Some(ToolCall {
-                            name: name.to_string(),
-                            arguments,
-                            id,
-                        })
-                    })
-                    .collect()
-            })
-            .filter(|v: &Vec<ToolCall>| !v.is_empty())
+}
+
+/// Extract the JSON document and its parsed value from an emulated JSON
+/// mode reply
+pub(crate) fn extract_json_content(content: &str, config: &JsonOutputConfig) -> Result<(String, Value)> {
+    json_mode::extract_json(content, config.prefill)
+        .ok_or_else(|| Error::Validation {
+            field: "content".to_string(),
+            message: "Response does not contain a JSON document".to_string(),
+            expected: Some("JSON object".to_string()),
+        })
+}
+
+/// Map a provider finish reason through the model's finish reason table,
+/// else parse it directly
+pub(crate) fn map_finish_reason(reason: &str, config: &SyncNormalization) -> FinishReason {
+    let reason = config.finish_reason_map.get(reason).map(String::as_str).unwrap_or(reason);
+    match reason.to_lowercase().as_str() {
+        "stop" | "end_turn" => FinishReason::Stop,
+        "length" | "max_tokens" => FinishReason::Length,
+        "tool_call" | "tool_calls" | "tool_use" => FinishReason::ToolCall,
+        "end_conversation" | "end" => FinishReason::EndConversation,
+        _ => FinishReason::Other,
     }
-    
-    /// Try to extract Anthropic-style tool calls
-    fn try_extract_anthropic_tools(&self, response: &Value) -> Option<Vec<ToolCall>> {
-        // Anthropic format: content array with tool_use blocks
-        response.get("content")
-            .and_then(|content| content.as_array())
-            .map(|blocks| {
-                blocks.iter()
-                    .filter_map(|block| {
-                        if block.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
-                            let name = block.get("name")
-                                .and_then(|n| n.as_str())?;
-                            
-                            let arguments = block.get("input")
-                                .cloned()
-                                .unwrap_or(Value::Null);
-                            
-                            let id = block.get("id")
-                                .and_then(|i| i.as_str())
-                                .map(|s| s.to_string());
-                            
-                            Some(ToolCall {
-                                name: name.to_string(),
-                                arguments,
-                                id,
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
-            })
-            .filter(|v: &Vec<ToolCall>| !v.is_empty())
+}
+
+/// Normalization rules for a single choice, with the paths under the first
+/// choice rebased onto the choice itself
+///
+/// Returns `None` when the model declares no choices path or its content and
+/// finish reason are not read from the first choice.
+fn choice_config(config: &SyncNormalization) -> Option<SyncNormalization> {
+    let prefix = format!("{}[0]", config.choices_path.as_deref()?);
+    let rebase = |path: &str| path.strip_prefix(prefix.as_str()).map(|rest| format!("${}", rest));
+    Some(SyncNormalization {
+        content_path: rebase(&config.content_path)?,
+        finish_reason_path: rebase(&config.finish_reason_path)?,
+        tool_calls_path: config.tool_calls_path.as_deref().and_then(rebase),
+        logprobs_path: config.logprobs_path.as_deref().and_then(rebase),
+        reasoning_path: None,
+        ..config.clone()
+    })
+}
+
+/// Text of a selected content value; arrays of content blocks are joined
+fn content_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(arr) => arr.iter().filter_map(block_text).collect(),
+        _ => value.to_string(),
+    }
+}
+
+/// Text of a content block, or of a plain string in a block array
+fn block_text(block: &Value) -> Option<&str> {
+    match block.get("text").and_then(|t| t.as_str()) {
+        Some(text) => Some(text),
+        None => block.as_str(),
+    }
+}
+
+/// Report typed content blocks sitting next to selected blocks that no path
+/// selected themselves; returns whether `value` holds a selected value
+fn report_unselected_blocks(
+    value: &Value,
+    path: &str,
+    selected: &[&Value],
+    tracker: &mut LossinessTracker,
+) -> bool {
+    if selected.iter().any(|s| std::ptr::eq(*s, value)) {
+        return true;
+    }
+    match value {
+        Value::Object(map) => map.iter().fold(false, |found, (key, child)| {
+            report_unselected_blocks(child, &format!("{}.{}", path, key), selected, tracker) | found
+        }),
+        Value::Array(items) => {
+            let holds: Vec<bool> = items.iter().enumerate()
+                .map(|(index, item)| report_unselected_blocks(item, &format!("{}[{}]", path, index), selected, tracker))
+                .collect();
+            if holds.contains(&true) {
+                for (index, item) in items.iter().enumerate().filter(|(index, _)| !holds[*index]) {
+                    if let Some(block_type) = item.get("type").and_then(|t| t.as_str()) {
+                        tracker.add_dropped(
+                            &format!("{}[{}]", path, index),
+                            &format!("Content block type '{}' is not normalized", block_type),
+                            Some(item.clone()),
+                        );
+                    }
+                }
+            }
+            holds.contains(&true)
+        }
+        _ => false,
     }
 }
 
+/// Whether `target` sits inside `value`, compared by identity
+fn contains(value: &Value, target: &Value) -> bool {
+    std::ptr::eq(value, target) || match value {
+        Value::Object(map) => map.values().any(|child| contains(child, target)),
+        Value::Array(items) => items.iter().any(|item| contains(item, target)),
+        _ => false,
+    }
+}
+
+/// JSONPath of a value inside the response, found by identity
+fn locate(root: &Value, target: &Value) -> Option<String> {
+    fn walk(value: &Value, target: &Value, path: String) -> Option<String> {
+        if std::ptr::eq(value, target) {
+            return Some(path);
+        }
+        match value {
+            Value::Object(map) => map.iter()
+                .find_map(|(key, child)| walk(child, target, format!("{}.{}", path, key))),
+            Value::Array(items) => items.iter().enumerate()
+                .find_map(|(index, item)| walk(item, target, format!("{}[{}]", path, index))),
+            _ => None,
+        }
+    }
+    walk(root, target, "$".to_string())
+}
+
 /// Normalize a response using model-specific rules
 pub fn normalize_response(
     provider_response: &Value,
     model_spec: &ModelSpec,
     model_id: &str,
-) -> Result<UniformResponse> {
+) -> Result<(UniformResponse, LossinessReport)> {
     let normalizer = ResponseNormalizer::new(model_spec.clone());
     normalizer.normalize_response(provider_response, model_id)
 }
@@ -238,6 +687,7 @@ pub fn normalize_response(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::LossinessCode;
     use serde_json::json;
     use std::collections::HashMap;
     
@@ -274,12 +724,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: false,
                 strategy: "none".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: json!({}),
             constraints: Constraints {
                 system_prompt_location: "message_role".to_string(),
@@ -289,7 +744,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: HashMap::new(),
@@ -304,6 +761,26 @@ mod tests {
                         ("length".to_string(), "length".to_string()),
                         ("tool_calls".to_string(), "tool_call".to_string()),
                     ]),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: Some("$.choices[0].message.tool_calls".to_string()),
+                    tool_name_path: Some("$.function.name".to_string()),
+                    tool_args_path: Some("$.function.arguments".to_string()),
+                    tool_id_path: Some("$.id".to_string()),
+                    args_encoding: ArgsEncoding::JsonString,
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -311,8 +788,10 @@ mod tests {
                         type_path: "$.type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         }
     }
     
@@ -341,7 +820,7 @@ mod tests {
             }
         });
         
-        let result = normalizer.normalize_response(&openai_response, "gpt-5").unwrap();
+        let (result, _) = normalizer.normalize_response(&openai_response, "gpt-5").unwrap();
         
         assert_eq!(result.model, "gpt-5");
         assert_eq!(result.content, "Hello! How can I help you today?");
@@ -380,7 +859,7 @@ mod tests {
             }
         });
         
-        let result = normalizer.normalize_response(&anthropic_response, "claude-opus-4-1").unwrap();
+        let (result, _) = normalizer.normalize_response(&anthropic_response, "claude-opus-4-1").unwrap();
         
         assert_eq!(result.model, "claude-opus-4-1");
         assert_eq!(result.content, "Hello! I'm Claude, how can I assist you?");
@@ -411,13 +890,89 @@ mod tests {
             }]
         });
         
-        let result = normalizer.normalize_response(&response_with_tools, "gpt-5").unwrap();
+        let (result, _) = normalizer.normalize_response(&response_with_tools, "gpt-5").unwrap();
         
         assert!(result.tool_calls.is_some());
         let tools = result.tool_calls.unwrap();
         assert_eq!(tools.len(), 1);
         assert_eq!(tools[0].name, "get_weather");
         assert_eq!(tools[0].id, Some("call_123".to_string()));
+        assert_eq!(tools[0].arguments, json!({"location": "San Francisco"}));
+    }
+    
+    #[test]
+    fn test_extract_anthropic_tool_calls() {
+        let mut model_spec = create_test_model_spec();
+        let sync = &mut model_spec.response_normalization.sync;
+        sync.content_path = "$.content[?(@.type == 'text')].text".to_string();
+        sync.tool_calls_path = Some("$.content[?(@.type == 'tool_use')]".to_string());
+        sync.tool_name_path = Some("$.name".to_string());
+        sync.tool_args_path = Some("$.input".to_string());
+        sync.tool_id_path = Some("$.id".to_string());
+        sync.args_encoding = ArgsEncoding::Object;
+        let normalizer = ResponseNormalizer::new(model_spec);
+        
+        let response = json!({
+            "content": [
+                {"type": "thinking", "thinking": "The user wants the weather."},
+                {"type": "text", "text": "Let me check. "},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}},
+                {"type": "text", "text": "One moment."}
+            ],
+            "stop_reason": "tool_use"
+        });
+        
+        let (result, _) = normalizer.normalize_response(&response, "claude-opus-4-1").unwrap();
+        assert_eq!(result.content, "Let me check. One moment.");
+        
+        let tools = result.tool_calls.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "get_weather");
+        assert_eq!(tools[0].id, Some("toolu_1".to_string()));
+        assert_eq!(tools[0].arguments, json!({"city": "Paris"}));
+    }
+    
+    #[test]
+    fn test_extract_reasoning() {
+        let mut model_spec = create_test_model_spec();
+        let sync = &mut model_spec.response_normalization.sync;
+        sync.content_path = "$.content[?(@.type == 'text')].text".to_string();
+        sync.reasoning_path = Some("$.content[?(@.type == 'thinking' || @.type == 'redacted_thinking')]".to_string());
+        sync.reasoning_text_path = Some("$.thinking".to_string());
+        let normalizer = ResponseNormalizer::new(model_spec);
+        
+        let response = json!({
+            "content": [
+                {"type": "thinking", "thinking": "Paris is in France.", "signature": "sig-1"},
+                {"type": "redacted_thinking", "data": "opaque"},
+                {"type": "text", "text": "Paris."}
+            ],
+            "usage": {"input_tokens": 10, "output_tokens": 20}
+        });
+        
+        let (result, _) = normalizer.normalize_response(&response, "claude-opus-4-1").unwrap();
+        assert_eq!(result.content, "Paris.");
+        
+        let reasoning = result.reasoning.unwrap();
+        assert_eq!(reasoning.text, "Paris is in France.");
+        assert_eq!(reasoning.blocks, vec![
+            ReasoningBlock {
+                text: Some("Paris is in France.".to_string()),
+                signature: Some("sig-1".to_string()),
+                redacted: None,
+            },
+            ReasoningBlock { text: None, signature: None, redacted: Some("opaque".to_string()) },
+        ]);
+        
+        // Reasoning tokens alone still report reasoning
+        let response = json!({
+            "choices": [{"message": {"content": "Hi"}, "finish_reason": "stop"}],
+            "usage": {"input_tokens": 5, "output_tokens": 9, "output_tokens_details": {"reasoning_tokens": 7}}
+        });
+        let (result, _) = ResponseNormalizer::new(create_test_model_spec()).normalize_response(&response, "gpt-5").unwrap();
+        let reasoning = result.reasoning.unwrap();
+        assert_eq!(reasoning.tokens, Some(7));
+        assert!(reasoning.blocks.is_empty());
     }
     
     #[test]
@@ -434,7 +989,281 @@ mod tests {
             }]
         });
         
-        let result = normalizer.normalize_response(&empty_response, "test").unwrap();
+        let (result, _) = normalizer.normalize_response(&empty_response, "test").unwrap();
         assert_eq!(result.content, "");
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_emulated_json_mode() {
+        let mut model_spec = create_test_model_spec();
+        model_spec.json_output.native_param = false;
+        model_spec.json_output.strategy = "system_prompt".to_string();
+        model_spec.json_output.prefill = true;
+
+        let response = |content: &str| json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": content},
+                "finish_reason": "stop"
+            }]
+        });
+
+        // The reply continues after the prefilled brace
+        let normalizer = ResponseNormalizer::new(model_spec.clone()).with_json_mode(true);
+        let (result, _) = normalizer.normalize_response(&response("\"answer\": 42}"), "gpt-5").unwrap();
+        assert_eq!(result.content, "{\"answer\": 42}");
+        assert_eq!(result.structured_output.unwrap().parsed, Some(json!({"answer": 42})));
+        assert!(normalizer.normalize_response(&response("No JSON here"), "gpt-5").is_err());
+
+        // A JSON response format expects JSON as well
+        let normalizer = ResponseNormalizer::new(model_spec.clone())
+            .with_response_format(ResponseFormat::JsonObject, StrictMode::Warn);
+        let (result, _) = normalizer.normalize_response(&response("\"answer\": 42}"), "gpt-5").unwrap();
+        assert_eq!(result.content, "{\"answer\": 42}");
+
+        // Without JSON mode the content is left alone
+        let normalizer = ResponseNormalizer::new(model_spec).with_json_mode(false);
+        let (result, _) = normalizer.normalize_response(&response("No JSON here"), "gpt-5").unwrap();
+        assert_eq!(result.content, "No JSON here");
+    }
+
+    #[test]
+    fn test_declared_usage_paths() {
+        let mut model_spec = create_test_model_spec();
+        model_spec.response_normalization.sync.usage = Some(UsagePaths {
+            input_tokens_path: Some("$.prompt_tokens".to_string()),
+            output_tokens_path: Some("$.completion_tokens".to_string()),
+            cache_read_tokens_path: Some("$.prompt_tokens_details.cached_tokens".to_string()),
+            ..UsagePaths::default()
+        });
+        let normalizer = ResponseNormalizer::new(model_spec);
+
+        let response = json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }],
+            "usage": {
+                "prompt_tokens": 12,
+                "completion_tokens": 3,
+                "prompt_tokens_details": {"cached_tokens": 8}
+            }
+        });
+
+        let usage = normalizer.normalize_response(&response, "gpt-5").unwrap().0.usage.unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 3);
+        assert_eq!(usage.total_tokens, 15);
+        assert_eq!(usage.cache_read_tokens, Some(8));
+    }
+
+    #[test]
+    fn test_response_lossiness() {
+        let normalizer = ResponseNormalizer::new(create_test_model_spec());
+        let response = json!({
+            "choices": [
+                {
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [
+                            {"id": "call_1", "function": {"name": "lookup", "arguments": "{\"q\": "}},
+                            {"id": "call_2", "function": {"arguments": "{}"}}
+                        ]
+                    },
+                    "finish_reason": "content_filter"
+                },
+                {"message": {"role": "assistant", "content": "Second"}, "finish_reason": "stop"}
+            ]
+        });
+
+        let (result, report) = normalizer.normalize_response(&response, "gpt-5").unwrap();
+        assert_eq!(result.finish_reason, FinishReason::Other);
+        assert_eq!(result.tool_calls.unwrap()[0].arguments, json!("{\"q\": "));
+
+        let items: Vec<_> = report.items.iter()
+            .map(|item| (item.code, item.path.as_str()))
+            .collect();
+        assert_eq!(items, vec![
+            (LossinessCode::MapFallback, "$.choices[0].finish_reason"),
+            (LossinessCode::MapFallback, "$.choices[0].message.tool_calls[0].arguments"),
+            (LossinessCode::Drop, "$.choices[0].message.tool_calls[1]"),
+        ]);
+
+        // Strict mode rejects what cannot be represented
+        let strict = ResponseNormalizer::new(create_test_model_spec()).with_strict_mode(StrictMode::Strict);
+        assert!(matches!(
+            strict.normalize_response(&response, "gpt-5"),
+            Err(Error::StrictnessViolation { .. })
+        ));
+        let clean = json!({"choices": [{"message": {"content": "Hi"}, "finish_reason": "stop"}]});
+        assert!(strict.normalize_response(&clean, "gpt-5").unwrap().1.items.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_content_blocks_reported() {
+        let mut model_spec = create_test_model_spec();
+        let sync = &mut model_spec.response_normalization.sync;
+        sync.content_path = "$.content[?(@.type == 'text')].text".to_string();
+        sync.tool_calls_path = Some("$.content[?(@.type == 'tool_use')]".to_string());
+        sync.tool_name_path = Some("$.name".to_string());
+        sync.tool_args_path = Some("$.input".to_string());
+        sync.args_encoding = ArgsEncoding::Object;
+        let normalizer = ResponseNormalizer::new(model_spec.clone());
+
+        let response = json!({
+            "content": [
+                {"type": "text", "text": "Checking."},
+                {"type": "server_tool_use", "id": "srv_1", "name": "web_search"},
+                {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {}}
+            ],
+            "stop_reason": "tool_use"
+        });
+        let (_, report) = normalizer.normalize_response(&response, "claude-opus-4-1").unwrap();
+        let paths: Vec<_> = report.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.content[1]"]);
+
+        // A path selecting the whole block array drops blocks without text
+        model_spec.response_normalization.sync.content_path = "$.content".to_string();
+        let (result, report) = ResponseNormalizer::new(model_spec)
+            .normalize_response(&response, "claude-opus-4-1")
+            .unwrap();
+        assert_eq!(result.content, "Checking.");
+        let paths: Vec<_> = report.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.content[1]"]);
+    }
+
+    #[test]
+    fn test_multiple_candidates() {
+        let mut model_spec = create_test_model_spec();
+        model_spec.response_normalization.sync.choices_path = Some("$.choices".to_string());
+        let normalizer = ResponseNormalizer::new(model_spec);
+
+        let response = json!({
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "Paris."}, "finish_reason": "stop"},
+                {"index": 1, "message": {"role": "assistant", "content": "Paris is the"}, "finish_reason": "length"},
+                {"index": 2, "message": {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "function": {"name": "lookup", "arguments": "{\"city\": \"Paris\"}"}
+                }]}, "finish_reason": "content_filter"}
+            ]
+        });
+
+        let (result, report) = normalizer.normalize_response(&response, "gpt-5").unwrap();
+        assert_eq!(result.content, "Paris.");
+        assert_eq!(result.candidates.len(), 3);
+        assert_eq!(result.candidates[0].content, "Paris.");
+        assert_eq!(result.candidates[1].content, "Paris is the");
+        assert_eq!(result.candidates[1].finish_reason, FinishReason::Length);
+        assert_eq!(result.candidates[2].index, 2);
+        assert_eq!(result.candidates[2].finish_reason, FinishReason::Other);
+        assert_eq!(result.candidates[2].tool_calls.as_ref().unwrap()[0].arguments, json!({"city": "Paris"}));
+
+        // Extra choices are kept, and their items point into the response
+        let paths: Vec<_> = report.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.choices[2].finish_reason"]);
+
+        // A single choice yields no candidate list
+        let single = json!({"choices": [{"message": {"content": "Hi"}, "finish_reason": "stop"}]});
+        assert!(normalizer.normalize_response(&single, "gpt-5").unwrap().0.candidates.is_empty());
+    }
+
+    #[test]
+    fn test_unnormalized_choices_reported() {
+        // The finish reason is read outside the choices, so only the first
+        // output can be normalized
+        let mut model_spec = create_test_model_spec();
+        let sync = &mut model_spec.response_normalization.sync;
+        sync.choices_path = Some("$.output".to_string());
+        sync.content_path = "$.output[0].content".to_string();
+        sync.finish_reason_path = "$.status".to_string();
+        sync.tool_calls_path = None;
+        let normalizer = ResponseNormalizer::new(model_spec);
+
+        let response = json!({
+            "status": "stop",
+            "output": [{"content": "Paris."}, {"content": "Lyon."}]
+        });
+        let (result, report) = normalizer.normalize_response(&response, "gpt-5").unwrap();
+        assert_eq!(result.content, "Paris.");
+        assert!(result.candidates.is_empty());
+        let items: Vec<_> = report.items.iter().map(|item| (item.code, item.path.as_str())).collect();
+        assert_eq!(items, vec![(LossinessCode::Drop, "$.output[1]")]);
+    }
+
+    #[test]
+    fn test_extract_logprobs() {
+        let mut model_spec = create_test_model_spec();
+        model_spec.response_normalization.sync.choices_path = Some("$.choices".to_string());
+        model_spec.response_normalization.sync.logprobs_path = Some("$.choices[0].logprobs.content".to_string());
+        let normalizer = ResponseNormalizer::new(model_spec);
+
+        let response = json!({
+            "choices": [
+                {"message": {"content": "Hi"}, "finish_reason": "stop", "logprobs": {"content": [
+                    {"token": "Hi", "logprob": -0.1, "bytes": [72, 105], "top_logprobs": [
+                        {"token": "Hi", "logprob": -0.1},
+                        {"token": "Hello", "logprob": -2.5}
+                    ]}
+                ]}},
+                {"message": {"content": "Hey"}, "finish_reason": "stop", "logprobs": {"content": [
+                    {"token": "Hey", "logprob": -1.2, "top_logprobs": []}
+                ]}}
+            ]
+        });
+
+        let (result, report) = normalizer.normalize_response(&response, "gpt-5").unwrap();
+        assert!(report.items.is_empty());
+        assert_eq!(result.logprobs, Some(vec![TokenLogprob {
+            token: "Hi".to_string(),
+            logprob: -0.1,
+            top_logprobs: vec![
+                TopLogprob { token: "Hi".to_string(), logprob: -0.1 },
+                TopLogprob { token: "Hello".to_string(), logprob: -2.5 },
+            ],
+        }]));
+        let second = result.candidates[1].logprobs.as_ref().unwrap();
+        assert_eq!((second[0].token.as_str(), second[0].logprob), ("Hey", -1.2));
+        assert!(second[0].top_logprobs.is_empty());
+
+        // Responses without logprobs leave them unset
+        let plain = json!({"choices": [{"message": {"content": "Hi"}, "finish_reason": "stop"}]});
+        assert!(normalizer.normalize_response(&plain, "gpt-5").unwrap().0.logprobs.is_none());
+    }
+
+    #[test]
+    fn test_extract_citations() {
+        let documents = vec!["doc-1".to_string(), "doc-2".to_string()];
+
+        // Anthropic cites by the document's position in the request
+        let mut model_spec = create_test_model_spec();
+        let sync = &mut model_spec.response_normalization.sync;
+        sync.content_path = "$.content[?(@.type == 'text')].text".to_string();
+        sync.citations_path = Some("$.content.*.citations.*".to_string());
+        sync.citation_document_path = Some("$.document_index".to_string());
+        sync.citation_text_path = Some("$.cited_text".to_string());
+        let normalizer = ResponseNormalizer::new(model_spec).with_documents(documents.clone());
+
+        let response = json!({
+            "content": [{"type": "text", "text": "Paris.", "citations": [
+                {"type": "char_location", "cited_text": "Paris is the capital of France.", "document_index": 0},
+                {"type": "char_location", "cited_text": "Unknown", "document_index": 5}
+            ]}],
+            "stop_reason": "end_turn"
+        });
+        let (result, report) = normalizer.normalize_response(&response, "claude").unwrap();
+        assert_eq!(result.citations, vec![Citation {
+            document_id: "doc-1".to_string(),
+            text: Some("Paris is the capital of France.".to_string()),
+        }]);
+        let paths: Vec<_> = report.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.content[0].citations[1]"]);
+
+        // Emulated grounding yields IDs written inline
+        let normalizer = ResponseNormalizer::new(create_test_model_spec()).with_documents(documents);
+        let response = json!({
+            "choices": [{"message": {"content": "Berlin [doc-2]."}, "finish_reason": "stop"}]
+        });
+        let (result, _) = normalizer.normalize_response(&response, "gpt-5").unwrap();
+        assert_eq!(result.citations, vec![Citation { document_id: "doc-2".to_string(), text: None }]);
+    }
+}
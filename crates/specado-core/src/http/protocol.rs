@@ -0,0 +1,370 @@
+//! Stream protocol decoders for provider streaming responses
+//!
+//! This module frames a raw byte stream into JSON events according to the
+//! `protocol` declared in a model's `StreamNormalization`:
+//! - `sse`: server-sent events (`event:`/`data:` fields, multi-line data,
+//!   keep-alive comments and the `[DONE]` sentinel)
+//! - `ndjson`: newline-delimited JSON
+
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// Streaming protocol declared by a provider spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamProtocol {
+    /// Server-sent events
+    Sse,
+    /// Newline-delimited JSON
+    Ndjson,
+}
+
+impl StreamProtocol {
+    /// Protocol names accepted in `response_normalization.stream.protocol`
+    pub const SUPPORTED: &'static [&'static str] = &["sse", "ndjson"];
+
+    /// Parse a protocol name from a provider spec
+    pub fn parse(protocol: &str) -> Result<Self> {
+        match protocol {
+            "sse" => Ok(Self::Sse),
+            "ndjson" => Ok(Self::Ndjson),
+            other => Err(Error::Validation {
+                field: "response_normalization.stream.protocol".to_string(),
+                message: format!("Unsupported stream protocol '{}'", other),
+                expected: Some(format!("one of: {}", Self::SUPPORTED.join(", "))),
+            }),
+        }
+    }
+
+    /// Create a fresh decoder for this protocol
+    pub fn decoder(&self) -> Box<dyn FrameDecoder> {
+        match self {
+            Self::Sse => Box::new(SseDecoder::new()),
+            Self::Ndjson => Box::new(NdjsonDecoder::new()),
+        }
+    }
+}
+
+/// A single framed event from a provider stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamFrame {
+    /// Event name, when the protocol carries one (SSE `event:` field)
+    pub event: Option<String>,
+    /// Parsed event payload
+    pub data: Value,
+}
+
+/// Incremental decoder from bytes to stream frames
+pub trait FrameDecoder: Send {
+    /// Feed a chunk of bytes and return every frame it completes
+    fn decode(&mut self, chunk: &[u8]) -> Vec<Result<StreamFrame>>;
+
+    /// Flush any buffered data at the end of the byte stream
+    fn finish(&mut self) -> Vec<Result<StreamFrame>>;
+
+    /// Whether the stream signalled completion (e.g. `[DONE]`)
+    fn is_done(&self) -> bool;
+}
+
+/// Buffer that yields complete lines from arbitrarily split chunks
+///
+/// Accepts `\n`, `\r\n` and `\r` line endings, and only decodes complete
+/// lines so multi-byte characters split across chunks stay intact.
+#[derive(Debug, Default)]
+struct LineBuffer {
+    bytes: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push(&mut self, chunk: &[u8]) {
+        self.bytes.extend_from_slice(chunk);
+    }
+
+    /// Take the next complete line, without its terminator
+    fn next_line(&mut self, eof: bool) -> Option<String> {
+        let pos = self.bytes.iter().position(|b| *b == b'\n' || *b == b'\r')?;
+        let mut end = pos + 1;
+        if self.bytes[pos] == b'\r' {
+            match self.bytes.get(pos + 1) {
+                Some(b'\n') => end += 1,
+                // A trailing `\r` may be the first half of `\r\n`
+                None if !eof => return None,
+                _ => {}
+            }
+        }
+        let line: Vec<u8> = self.bytes.drain(..end).collect();
+        Some(String::from_utf8_lossy(&line[..pos]).into_owned())
+    }
+
+    /// Take whatever is left once the stream has ended
+    fn take_remainder(&mut self) -> Option<String> {
+        if self.bytes.is_empty() {
+            None
+        } else {
+            let rest = std::mem::take(&mut self.bytes);
+            Some(String::from_utf8_lossy(&rest).into_owned())
+        }
+    }
+}
+
+/// Parse a frame payload as JSON
+fn parse_data(data: &str) -> Result<Value> {
+    serde_json::from_str(data).map_err(|e| Error::Json {
+        message: format!("Failed to parse stream event: {}", e),
+        source: e,
+    })
+}
+
+/// Server-sent events decoder
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    lines: LineBuffer,
+    event: Option<String>,
+    data: Vec<String>,
+    done: bool,
+}
+
+impl SseDecoder {
+    /// Create a new SSE decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn process_line(&mut self, line: &str, frames: &mut Vec<Result<StreamFrame>>) {
+        // A blank line dispatches the pending event
+        if line.is_empty() {
+            self.dispatch(frames);
+            return;
+        }
+        // Comment lines are used as keep-alives
+        if line.starts_with(':') {
+            return;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            // `id` and `retry` only matter for reconnection
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self, frames: &mut Vec<Result<StreamFrame>>) {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return;
+        }
+
+        let data = std::mem::take(&mut self.data).join("\n");
+        if data.trim() == "[DONE]" {
+            self.done = true;
+            return;
+        }
+
+        frames.push(parse_data(&data).map(|data| StreamFrame { event, data }));
+    }
+}
+
+impl FrameDecoder for SseDecoder {
+    fn decode(&mut self, chunk: &[u8]) -> Vec<Result<StreamFrame>> {
+        let mut frames = Vec::new();
+        if self.done {
+            return frames;
+        }
+
+        self.lines.push(chunk);
+        while let Some(line) = self.lines.next_line(false) {
+            self.process_line(&line, &mut frames);
+            if self.done {
+                break;
+            }
+        }
+        frames
+    }
+
+    fn finish(&mut self) -> Vec<Result<StreamFrame>> {
+        let mut frames = Vec::new();
+        while !self.done {
+            match self.lines.next_line(true).or_else(|| self.lines.take_remainder()) {
+                Some(line) => self.process_line(&line, &mut frames),
+                None => break,
+            }
+        }
+        // Be lenient with servers that close without a trailing blank line
+        if !self.done {
+            self.dispatch(&mut frames);
+        }
+        frames
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+/// Newline-delimited JSON decoder
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    lines: LineBuffer,
+}
+
+impl NdjsonDecoder {
+    /// Create a new NDJSON decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn process_line(line: &str, frames: &mut Vec<Result<StreamFrame>>) {
+        let line = line.trim();
+        if !line.is_empty() {
+            frames.push(parse_data(line).map(|data| StreamFrame { event: None, data }));
+        }
+    }
+}
+
+impl FrameDecoder for NdjsonDecoder {
+    fn decode(&mut self, chunk: &[u8]) -> Vec<Result<StreamFrame>> {
+        let mut frames = Vec::new();
+        self.lines.push(chunk);
+        while let Some(line) = self.lines.next_line(false) {
+            Self::process_line(&line, &mut frames);
+        }
+        frames
+    }
+
+    fn finish(&mut self) -> Vec<Result<StreamFrame>> {
+        let mut frames = Vec::new();
+        while let Some(line) = self.lines.next_line(true).or_else(|| self.lines.take_remainder()) {
+            Self::process_line(&line, &mut frames);
+        }
+        frames
+    }
+
+    fn is_done(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const OPENAI_SSE: &[u8] = include_bytes!("../../tests/fixtures/streams/openai_chat.sse");
+    const ANTHROPIC_SSE: &[u8] = include_bytes!("../../tests/fixtures/streams/anthropic_messages.sse");
+    const MULTILINE_SSE: &[u8] = include_bytes!("../../tests/fixtures/streams/multiline.sse");
+    const OPENAI_NDJSON: &[u8] = include_bytes!("../../tests/fixtures/streams/openai_chat.ndjson");
+
+    /// Decode a fixture fed in chunks of the given size
+    fn decode_all(protocol: StreamProtocol, bytes: &[u8], chunk_size: usize) -> Vec<StreamFrame> {
+        let mut decoder = protocol.decoder();
+        let mut frames = Vec::new();
+        for chunk in bytes.chunks(chunk_size) {
+            frames.extend(decoder.decode(chunk));
+        }
+        frames.extend(decoder.finish());
+        frames.into_iter().map(|f| f.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_protocol_parsing() {
+        assert_eq!(StreamProtocol::parse("sse").unwrap(), StreamProtocol::Sse);
+        assert_eq!(StreamProtocol::parse("ndjson").unwrap(), StreamProtocol::Ndjson);
+
+        let err = StreamProtocol::parse("carrier-pigeon").unwrap_err();
+        assert!(err.to_string().contains("response_normalization.stream.protocol"));
+    }
+
+    #[test]
+    fn test_sse_openai_fixture() {
+        let frames = decode_all(StreamProtocol::Sse, OPENAI_SSE, 4096);
+
+        // Keep-alive comments are skipped and [DONE] ends the stream
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].data["choices"][0]["delta"]["content"], "The weather");
+        assert_eq!(frames[4].data["usage"]["total_tokens"], 21);
+        assert!(frames.iter().all(|f| f.event.is_none()));
+    }
+
+    #[test]
+    fn test_sse_event_names() {
+        let frames = decode_all(StreamProtocol::Sse, ANTHROPIC_SSE, 4096);
+
+        assert_eq!(frames.len(), 12);
+        assert_eq!(frames[0].event.as_deref(), Some("message_start"));
+        assert_eq!(frames[2].event.as_deref(), Some("ping"));
+        assert_eq!(frames[11].data, json!({"type": "message_stop"}));
+    }
+
+    #[test]
+    fn test_sse_chunk_boundaries() {
+        let whole = decode_all(StreamProtocol::Sse, ANTHROPIC_SSE, 4096);
+        for chunk_size in [1, 2, 7, 64] {
+            assert_eq!(decode_all(StreamProtocol::Sse, ANTHROPIC_SSE, chunk_size), whole);
+        }
+    }
+
+    #[test]
+    fn test_sse_multiline_data() {
+        let frames = decode_all(StreamProtocol::Sse, MULTILINE_SSE, 5);
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data["delta"]["text"], "split");
+    }
+
+    #[test]
+    fn test_sse_crlf_and_split_utf8() {
+        let bytes = "data: {\"text\":\"caf\u{e9} \u{1f600}\"}\r\n\r\ndata: [DONE]\r\n\r\n".as_bytes();
+        let frames = decode_all(StreamProtocol::Sse, bytes, 1);
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data["text"], "caf\u{e9} \u{1f600}");
+    }
+
+    #[test]
+    fn test_sse_done_stops_decoding() {
+        let mut decoder = SseDecoder::new();
+        let frames = decoder.decode(b"data: [DONE]\n\ndata: {\"late\":true}\n\n");
+
+        assert!(frames.is_empty());
+        assert!(decoder.is_done());
+        assert!(decoder.finish().is_empty());
+    }
+
+    #[test]
+    fn test_sse_invalid_json() {
+        let mut decoder = SseDecoder::new();
+        let frames = decoder.decode(b"data: {not json}\n\n");
+
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].is_err());
+    }
+
+    #[test]
+    fn test_ndjson_fixture() {
+        let frames = decode_all(StreamProtocol::Ndjson, OPENAI_NDJSON, 3);
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].data["choices"][0]["delta"]["content"], " world");
+        assert_eq!(frames[2].data["choices"][0]["finish_reason"], "length");
+    }
+
+    #[test]
+    fn test_ndjson_without_trailing_newline() {
+        let frames = decode_all(StreamProtocol::Ndjson, b"{\"a\":1}\n{\"a\":2}", 4);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].data["a"], 2);
+    }
+
+    #[test]
+    fn test_schema_allows_supported_protocols() {
+        let validator = specado_schemas::ProviderSpecValidator::new().unwrap();
+        assert_eq!(validator.stream_protocols(), StreamProtocol::SUPPORTED);
+    }
+}
@@ -0,0 +1,606 @@
+//! Stream normalization for provider streaming responses
+//!
+//! This module turns provider stream events into uniform [`StreamEvent`]s
+//! using the event routes declared in the model's `StreamNormalization`.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures_util::{stream, Stream, StreamExt};
+use serde_json::Value;
+
+use super::normalizer::map_finish_reason;
+use super::protocol::{FrameDecoder, StreamFrame, StreamProtocol};
+use crate::translation::jsonpath::JSONPath;
+use crate::types::{EventRoute, FinishReason, ModelSpec, StreamEvent, StreamHandle};
+use crate::{Error, Result};
+
+/// Stream normalizer that converts provider stream events to uniform events
+#[derive(Debug)]
+pub struct StreamNormalizer {
+    /// Model specification containing normalization rules
+    model_spec: ModelSpec,
+    /// Event routes with their JSONPaths parsed
+    routes: Arc<[Route]>,
+    /// Tool call state of each choice
+    tools: BTreeMap<usize, ToolCalls>,
+    /// Whether the response identifier has been emitted
+    started: bool,
+}
+
+/// Tool calls streamed for one choice
+#[derive(Debug, Default)]
+struct ToolCalls {
+    /// Indexes of tool calls that have started but not ended
+    open: BTreeSet<usize>,
+    /// Index assigned to the next tool call when the provider has none
+    next_index: usize,
+    /// Most recently started tool call
+    current: Option<usize>,
+}
+
+impl StreamNormalizer {
+    /// Create a new stream normalizer for a specific model
+    ///
+    /// Fails if any of the model's event routes has an invalid JSONPath.
+    pub fn new(model_spec: ModelSpec) -> Result<Self> {
+        let selector = &model_spec.response_normalization.stream.event_selector;
+        let routes = selector.routes.iter()
+            .map(|route| Route::compile(route, &selector.type_path))
+            .collect::<Result<Arc<[Route]>>>()?;
+
+        Ok(Self {
+            model_spec,
+            routes,
+            tools: BTreeMap::new(),
+            started: false,
+        })
+    }
+
+    /// Normalize a single provider event
+    ///
+    /// Every route that matches the event contributes its events, in route order.
+    pub fn normalize_event(&mut self, event: &Value) -> Result<Vec<StreamEvent>> {
+        let routes = Arc::clone(&self.routes);
+        let mut events = Vec::new();
+
+        for route in routes.iter() {
+            if route.matches(event)? {
+                self.apply_route(route, event, &mut events)?;
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Close any tool calls still open when the stream ends
+    pub fn finish(&mut self) -> Vec<StreamEvent> {
+        let choices: Vec<usize> = self.tools.keys().copied().collect();
+        choices.into_iter().flat_map(|choice| self.close_open_tools(choice)).collect()
+    }
+
+    /// Emit the events for a matched route
+    fn apply_route(
+        &mut self,
+        route: &Route,
+        event: &Value,
+        events: &mut Vec<StreamEvent>,
+    ) -> Result<()> {
+        let choice = select_index(event, route.choice_path.as_ref())?.unwrap_or(0);
+
+        match route.emit.as_str() {
+            "start" if !self.started => {
+                if let Some(id) = select_string(event, route.id_path.as_ref().map(|p| &p.path))? {
+                    self.started = true;
+                    events.push(StreamEvent::ResponseStart { id });
+                }
+            }
+            "delta" => {
+                if let Some(text) = select_string(event, route.text_path.as_ref())? {
+                    if !text.is_empty() {
+                        events.push(StreamEvent::TextDelta { choice, text });
+                    }
+                }
+            }
+            "reasoning" => self.apply_reasoning_route(route, event, events)?,
+            "tool" => self.apply_tool_route(route, choice, event, events)?,
+            "tool_end" => {
+                match select_index(event, route.index_path.as_ref().map(|p| &p.path))? {
+                    Some(index) => {
+                        if self.tools.entry(choice).or_default().open.remove(&index) {
+                            events.push(StreamEvent::ToolCallEnd { choice, index });
+                        }
+                    }
+                    None => events.extend(self.close_open_tools(choice)),
+                }
+            }
+            "stop" => {
+                events.extend(self.close_open_tools(choice));
+                let finish_reason = match select_string(event, route.finish_reason_path.as_ref())? {
+                    Some(reason) => map_finish_reason(&reason, &self.model_spec.response_normalization.sync),
+                    None => FinishReason::Stop,
+                };
+                events.push(StreamEvent::Finish { choice, finish_reason });
+            }
+            "error" => {
+                let message = select(event, route.text_path.as_ref())?
+                    .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+                    .unwrap_or_else(|| event.to_string());
+                return Err(Error::Provider {
+                    provider: self.model_spec.family.clone(),
+                    message: format!("Stream error: {}", message),
+                    source: None,
+                });
+            }
+            // Usage is emitted below; lifecycle events have no uniform counterpart
+            _ => {}
+        }
+
+        if let Some(usage) = select(event, route.usage_path.as_ref())?.filter(|v| !v.is_null()) {
+            events.push(StreamEvent::Usage { usage: usage.clone() });
+        }
+
+        Ok(())
+    }
+
+    /// Emit reasoning text, signature and redacted content events
+    fn apply_reasoning_route(
+        &self,
+        route: &Route,
+        event: &Value,
+        events: &mut Vec<StreamEvent>,
+    ) -> Result<()> {
+        let index = select_index(event, route.index_path.as_ref().map(|p| &p.path))?.unwrap_or(0);
+
+        if let Some(text) = select_string(event, route.text_path.as_ref())?.filter(|t| !t.is_empty()) {
+            events.push(StreamEvent::ReasoningDelta { index, text });
+        }
+        if let Some(signature) = select_string(event, route.signature_path.as_ref())?.filter(|s| !s.is_empty()) {
+            events.push(StreamEvent::ReasoningSignature { index, signature });
+        }
+        if let Some(data) = select_string(event, route.redacted_path.as_ref())?.filter(|d| !d.is_empty()) {
+            events.push(StreamEvent::ReasoningRedacted { index, data });
+        }
+        Ok(())
+    }
+
+    /// Emit tool call start and argument events for every tool call delta
+    /// in the event
+    fn apply_tool_route(
+        &mut self,
+        route: &Route,
+        choice: usize,
+        event: &Value,
+        events: &mut Vec<StreamEvent>,
+    ) -> Result<()> {
+        let items = match &route.tool_items {
+            Some(items) => items.execute(event)?,
+            None => vec![event],
+        };
+
+        let tools = self.tools.entry(choice).or_default();
+        for item in items {
+            tools.apply_delta(route, choice, event, item, events)?;
+        }
+        Ok(())
+    }
+
+    /// End every open tool call of a choice in index order
+    fn close_open_tools(&mut self, choice: usize) -> Vec<StreamEvent> {
+        let Some(tools) = self.tools.get_mut(&choice) else {
+            return Vec::new();
+        };
+        tools.current = None;
+        std::mem::take(&mut tools.open)
+            .into_iter()
+            .map(|index| StreamEvent::ToolCallEnd { choice, index })
+            .collect()
+    }
+}
+
+impl ToolCalls {
+    /// Emit the start and argument events for one tool call delta
+    fn apply_delta(
+        &mut self,
+        route: &Route,
+        choice: usize,
+        event: &Value,
+        item: &Value,
+        events: &mut Vec<StreamEvent>,
+    ) -> Result<()> {
+        let name = route.select_tool_string(&route.name_path, event, item)?;
+        let provided_index = match &route.index_path {
+            Some(path) => select_index(path.target(event, item), Some(&path.path))?,
+            None => None,
+        };
+
+        let index = match (provided_index, &name) {
+            (Some(index), _) => index,
+            (None, Some(_)) => self.next_index,
+            (None, None) => self.current.unwrap_or(0),
+        };
+
+        if let Some(name) = name.filter(|n| !n.is_empty()) {
+            if !self.open.contains(&index) {
+                let id = route.select_tool_string(&route.id_path, event, item)?;
+                self.open.insert(index);
+                self.current = Some(index);
+                self.next_index = self.next_index.max(index + 1);
+                events.push(StreamEvent::ToolCallStart { choice, index, id, name });
+            }
+        }
+
+        if let Some(path) = &route.args_path {
+            let delta = match select(path.target(event, item), Some(&path.path))? {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            if !delta.is_empty() && self.open.contains(&index) {
+                events.push(StreamEvent::ToolCallArgsDelta { choice, index, delta });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An event route with its JSONPaths parsed
+#[derive(Debug)]
+struct Route {
+    when: String,
+    emit: String,
+    type_path: JSONPath,
+    text_path: Option<JSONPath>,
+    finish_reason_path: Option<JSONPath>,
+    usage_path: Option<JSONPath>,
+    choice_path: Option<JSONPath>,
+    signature_path: Option<JSONPath>,
+    redacted_path: Option<JSONPath>,
+    /// Tool call deltas in the event, when the tool paths select `[*]`
+    tool_items: Option<JSONPath>,
+    index_path: Option<ToolPath>,
+    id_path: Option<ToolPath>,
+    name_path: Option<ToolPath>,
+    args_path: Option<ToolPath>,
+}
+
+/// A tool route path, relative to each tool call delta or to the event
+#[derive(Debug)]
+struct ToolPath {
+    path: JSONPath,
+    per_item: bool,
+}
+
+impl ToolPath {
+    /// The value this path selects from
+    fn target<'a>(&self, event: &'a Value, item: &'a Value) -> &'a Value {
+        if self.per_item { item } else { event }
+    }
+}
+
+impl Route {
+    /// Parse a route's JSONPaths, filling in the defaults for its emit
+    fn compile(route: &EventRoute, default_type_path: &str) -> Result<Self> {
+        let parse = |path: Option<&str>| path.map(JSONPath::parse).transpose();
+
+        let text_path = match route.emit.as_str() {
+            "error" => route.text_path.as_deref().or(Some("$.error.message")),
+            _ => route.text_path.as_deref(),
+        };
+        let usage_path = match route.emit.as_str() {
+            "usage" => route.usage_path.as_deref().or(Some("$.usage")),
+            _ => route.usage_path.as_deref(),
+        };
+
+        // Tool paths through `[*]` select from each tool call delta in turn
+        let tool_paths = [&route.index_path, &route.id_path, &route.name_path, &route.args_path];
+        let items_prefix = tool_paths.iter()
+            .filter_map(|path| path.as_deref())
+            .find_map(|path| path.find("[*]").map(|at| &path[..at + 3]));
+        let tool_path = |path: &Option<String>| -> Result<Option<ToolPath>> {
+            let Some(path) = path.as_deref() else {
+                return Ok(None);
+            };
+            let relative = items_prefix.and_then(|prefix| path.strip_prefix(prefix));
+            Ok(Some(match relative {
+                Some(rest) => ToolPath { path: JSONPath::parse(&format!("${}", rest))?, per_item: true },
+                None => ToolPath { path: JSONPath::parse(path)?, per_item: false },
+            }))
+        };
+
+        Ok(Self {
+            when: route.when.clone(),
+            emit: route.emit.clone(),
+            type_path: JSONPath::parse(route.type_path.as_deref().unwrap_or(default_type_path))?,
+            text_path: parse(text_path)?,
+            finish_reason_path: parse(route.finish_reason_path.as_deref())?,
+            usage_path: parse(usage_path)?,
+            choice_path: parse(route.choice_path.as_deref())?,
+            signature_path: parse(route.signature_path.as_deref())?,
+            redacted_path: parse(route.redacted_path.as_deref())?,
+            tool_items: parse(items_prefix)?,
+            index_path: tool_path(&route.index_path)?,
+            id_path: tool_path(&route.id_path)?,
+            name_path: tool_path(&route.name_path)?,
+            args_path: tool_path(&route.args_path)?,
+        })
+    }
+
+    /// Check whether the route applies to an event
+    fn matches(&self, event: &Value) -> Result<bool> {
+        Ok(match select(event, Some(&self.type_path))? {
+            Some(Value::String(s)) => *s == self.when,
+            Some(Value::Object(map)) => map.get(&self.when).is_some_and(|v| !v.is_null()),
+            _ => false,
+        })
+    }
+
+    /// Select a string at a tool path
+    fn select_tool_string(&self, path: &Option<ToolPath>, event: &Value, item: &Value) -> Result<Option<String>> {
+        match path {
+            Some(path) => select_string(path.target(event, item), Some(&path.path)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Select the first value at an optional JSONPath
+fn select<'a>(event: &'a Value, path: Option<&JSONPath>) -> Result<Option<&'a Value>> {
+    match path {
+        Some(path) => path.execute_single(event),
+        None => Ok(None),
+    }
+}
+
+/// Select a string value at an optional JSONPath
+fn select_string(event: &Value, path: Option<&JSONPath>) -> Result<Option<String>> {
+    Ok(select(event, path)?.and_then(|v| v.as_str()).map(str::to_string))
+}
+
+/// Select an index value at an optional JSONPath
+fn select_index(event: &Value, path: Option<&JSONPath>) -> Result<Option<usize>> {
+    Ok(select(event, path)?.and_then(|v| v.as_u64()).map(|i| i as usize))
+}
+
+/// State threaded through the normalized stream
+struct StreamState<S> {
+    bytes: Pin<Box<S>>,
+    decoder: Box<dyn FrameDecoder>,
+    normalizer: StreamNormalizer,
+    pending: VecDeque<Result<StreamEvent>>,
+    done: bool,
+}
+
+impl<S> StreamState<S> {
+    /// Normalize decoded frames, stopping at the first error
+    fn handle_frames(&mut self, frames: Vec<Result<StreamFrame>>) {
+        for frame in frames {
+            if self.done {
+                return;
+            }
+            match frame.and_then(|frame| self.normalizer.normalize_event(&frame.data)) {
+                Ok(events) => self.pending.extend(events.into_iter().map(Ok)),
+                Err(e) => {
+                    self.pending.push_back(Err(e));
+                    self.done = true;
+                }
+            }
+        }
+        if self.decoder.is_done() {
+            self.end();
+        }
+    }
+
+    /// Finish the stream, closing open tool calls
+    fn end(&mut self) {
+        if !self.done {
+            self.pending.extend(self.normalizer.finish().into_iter().map(Ok));
+            self.done = true;
+        }
+    }
+}
+
+/// Normalize a provider byte stream using model-specific rules
+///
+/// The byte stream is framed with the decoder selected by the model's
+/// `response_normalization.stream.protocol`.
+pub fn normalize_stream<S, B, E>(byte_stream: S, model_spec: &ModelSpec) -> Result<StreamHandle>
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let protocol = StreamProtocol::parse(&model_spec.response_normalization.stream.protocol)?;
+    let state = StreamState {
+        bytes: Box::pin(byte_stream),
+        decoder: protocol.decoder(),
+        normalizer: StreamNormalizer::new(model_spec.clone())?,
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    Ok(StreamHandle::new(stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((event, state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.bytes.next().await {
+                Some(Ok(chunk)) => {
+                    let frames = state.decoder.decode(chunk.as_ref());
+                    state.handle_frames(frames);
+                }
+                Some(Err(e)) => {
+                    state.pending.push_back(Err(Error::Http {
+                        message: format!("Failed to read stream: {}", e),
+                        status_code: None,
+                        source: Some(anyhow::Error::new(e)),
+                    }));
+                    state.done = true;
+                }
+                None => {
+                    let frames = state.decoder.finish();
+                    state.handle_frames(frames);
+                    state.end();
+                }
+            }
+        }
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    fn load_model(path: &str, model_id: &str) -> ModelSpec {
+        let content = std::fs::read_to_string(format!(
+            "{}/../../providers/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            path
+        ))
+        .unwrap();
+        let spec: crate::ProviderSpec = serde_json::from_str(&content).unwrap();
+        spec.models.into_iter().find(|m| m.id == model_id).unwrap()
+    }
+
+    fn sse(events: &[Value]) -> Vec<std::result::Result<Vec<u8>, std::io::Error>> {
+        let mut body = String::new();
+        for event in events {
+            body.push_str(&format!("data: {}\n\n", event));
+        }
+        body.push_str("data: [DONE]\n\n");
+        // Split into small chunks so lines cross chunk boundaries
+        body.into_bytes().chunks(17).map(|c| Ok(c.to_vec())).collect()
+    }
+
+    async fn collect(model: &ModelSpec, events: &[Value]) -> Vec<StreamEvent> {
+        normalize_stream(stream::iter(sse(events)), model)
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_openai_text_stream() {
+        let model = load_model("openai/gpt-5.json", "gpt-5");
+        let events = collect(&model, &[
+            json!({"choices": [{"index": 0, "delta": {"role": "assistant", "content": ""}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {"content": "Hel"}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {"content": "lo"}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {}, "finish_reason": "stop"}]}),
+            json!({"choices": [], "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}}),
+        ]).await;
+
+        assert_eq!(events, vec![
+            StreamEvent::TextDelta { choice: 0, text: "Hel".to_string() },
+            StreamEvent::TextDelta { choice: 0, text: "lo".to_string() },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::Stop },
+            StreamEvent::Usage { usage: json!({"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}) },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_openai_tool_call_stream() {
+        let model = load_model("openai/gpt-5.json", "gpt-5");
+        let events = collect(&model, &[
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [{"index": 0, "id": "call_1", "type": "function", "function": {"name": "get_weather", "arguments": ""}}]}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [{"index": 0, "function": {"arguments": "{\"city\":"}}]}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [{"index": 0, "function": {"arguments": "\"Paris\"}"}}]}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {}, "finish_reason": "tool_calls"}]}),
+        ]).await;
+
+        assert_eq!(events, vec![
+            StreamEvent::ToolCallStart { choice: 0, index: 0, id: Some("call_1".to_string()), name: "get_weather".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 0, delta: "{\"city\":".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 0, delta: "\"Paris\"}".to_string() },
+            StreamEvent::ToolCallEnd { choice: 0, index: 0 },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::ToolCall },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_openai_parallel_tool_call_stream() {
+        let model = load_model("openai/gpt-5.json", "gpt-5");
+        let events = collect(&model, &[
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [
+                {"index": 0, "id": "call_1", "type": "function", "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}},
+                {"index": 1, "id": "call_2", "type": "function", "function": {"name": "get_time", "arguments": ""}}
+            ]}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [{"index": 1, "function": {"arguments": "{}"}}]}, "finish_reason": null}]}),
+            json!({"choices": [{"index": 0, "delta": {}, "finish_reason": "tool_calls"}]}),
+        ]).await;
+
+        assert_eq!(events, vec![
+            StreamEvent::ToolCallStart { choice: 0, index: 0, id: Some("call_1".to_string()), name: "get_weather".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 0, delta: "{\"city\":\"Paris\"}".to_string() },
+            StreamEvent::ToolCallStart { choice: 0, index: 1, id: Some("call_2".to_string()), name: "get_time".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 1, delta: "{}".to_string() },
+            StreamEvent::ToolCallEnd { choice: 0, index: 0 },
+            StreamEvent::ToolCallEnd { choice: 0, index: 1 },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::ToolCall },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream() {
+        let model = load_model("anthropic/claude-opus-4.1.json", "claude-opus-4-1-20250805");
+        let events = collect(&model, &[
+            json!({"type": "message_start", "message": {"id": "msg_1", "usage": {"input_tokens": 12, "output_tokens": 1}}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Checking"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"city\": \"Paris\"}"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 20}}),
+            json!({"type": "message_stop"}),
+        ]).await;
+
+        assert_eq!(events, vec![
+            StreamEvent::ResponseStart { id: "msg_1".to_string() },
+            StreamEvent::Usage { usage: json!({"input_tokens": 12, "output_tokens": 1}) },
+            StreamEvent::TextDelta { choice: 0, text: "Checking".to_string() },
+            StreamEvent::ToolCallStart { choice: 0, index: 1, id: Some("toolu_1".to_string()), name: "get_weather".to_string() },
+            StreamEvent::ToolCallArgsDelta { choice: 0, index: 1, delta: "{\"city\": \"Paris\"}".to_string() },
+            StreamEvent::ToolCallEnd { choice: 0, index: 1 },
+            StreamEvent::Finish { choice: 0, finish_reason: FinishReason::ToolCall },
+            StreamEvent::Usage { usage: json!({"output_tokens": 20}) },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_error_event() {
+        let model = load_model("anthropic/claude-opus-4.1.json", "claude-opus-4-1-20250805");
+        let mut handle = normalize_stream(stream::iter(sse(&[
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ])), &model).unwrap();
+
+        let err = handle.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+        assert!(handle.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unterminated_tool_is_closed() {
+        let model = load_model("openai/gpt-5.json", "gpt-5");
+        let body: Vec<std::result::Result<Vec<u8>, std::io::Error>> = vec![Ok(format!(
+            "data: {}\n",
+            json!({"choices": [{"index": 0, "delta": {"tool_calls": [{"index": 0, "id": "call_1", "function": {"name": "f", "arguments": "{}"}}]}}]})
+        ).into_bytes())];
+
+        let events: Vec<StreamEvent> = normalize_stream(stream::iter(body), &model)
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(events.last(), Some(&StreamEvent::ToolCallEnd { choice: 0, index: 0 }));
+    }
+}
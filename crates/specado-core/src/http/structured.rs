@@ -0,0 +1,136 @@
+//! Structured output validation
+//!
+//! Opt-in post-normalization step for prompts that request
+//! `ResponseFormat::JsonSchema`: the response content is parsed and checked
+//! against the requested schema, and the outcome is attached to the
+//! [`UniformResponse`] as a [`StructuredOutput`].
+
+use specado_schemas::ValidationHelpers;
+
+use crate::types::{ResponseFormat, StructuredOutput, StructuredOutputError, UniformResponse};
+use crate::{Error, Result, StrictMode};
+
+/// Parse content and validate it against a JSON Schema
+pub fn validate_structured_output(content: &str, schema: &serde_json::Value) -> StructuredOutput {
+    let parsed: serde_json::Value = match serde_json::from_str(content.trim()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return StructuredOutput {
+                parsed: None,
+                errors: vec![StructuredOutputError {
+                    path: "$".to_string(),
+                    message: format!("Content is not valid JSON: {}", e),
+                }],
+            };
+        }
+    };
+
+    let errors = match ValidationHelpers::validate_against_schema(&parsed, schema) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations.errors.into_iter()
+            .map(|error| StructuredOutputError { path: error.path, message: error.message })
+            .collect(),
+    };
+
+    StructuredOutput { parsed: Some(parsed), errors }
+}
+
+/// Validate a response against the requested response format
+///
+/// Formats without a JSON Schema leave the response untouched. In
+/// [`StrictMode::Strict`] content that does not match the schema is an error;
+/// otherwise the violations are reported in `structured_output.errors`.
+pub fn apply_structured_output(
+    response: &mut UniformResponse,
+    format: &ResponseFormat,
+    strict_mode: StrictMode,
+) -> Result<()> {
+    let Some(schema) = format.schema() else {
+        return Ok(());
+    };
+
+    let structured = validate_structured_output(&response.content, schema);
+    if strict_mode == StrictMode::Strict && !structured.is_valid() {
+        let details: Vec<String> = structured.errors.iter()
+            .map(|error| format!("{}: {}", error.path, error.message))
+            .collect();
+        return Err(Error::Validation {
+            field: "content".to_string(),
+            message: format!("Response does not match the requested schema: {}", details.join("; ")),
+            expected: Some("JSON matching the response format schema".to_string()),
+        });
+    }
+
+    response.structured_output = Some(structured);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FinishReason;
+    use serde_json::json;
+
+    fn response(content: &str) -> UniformResponse {
+        UniformResponse {
+            model: "test-model".to_string(),
+            content: content.to_string(),
+            finish_reason: FinishReason::Stop,
+            tool_calls: None,
+            raw_metadata: json!({}),
+            usage: None,
+            structured_output: None,
+            reasoning: None,
+            candidates: Vec::new(),
+            logprobs: None,
+            citations: Vec::new(),
+            response_id: None,
+            lossiness: None,
+        }
+    }
+
+    fn format() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            json_schema: json!({
+                "name": "answer",
+                "schema": {
+                    "type": "object",
+                    "required": ["answer"],
+                    "properties": {"answer": {"type": "integer"}}
+                }
+            }),
+            strict: Some(true),
+        }
+    }
+
+    #[test]
+    fn test_valid_structured_output() {
+        let mut response = response("{\"answer\": 42}");
+        apply_structured_output(&mut response, &format(), StrictMode::Strict).unwrap();
+
+        let structured = response.structured_output.unwrap();
+        assert!(structured.is_valid());
+        assert_eq!(structured.parsed, Some(json!({"answer": 42})));
+    }
+
+    #[test]
+    fn test_invalid_structured_output() {
+        let mut invalid = response("{\"answer\": \"forty-two\"}");
+        apply_structured_output(&mut invalid, &format(), StrictMode::Warn).unwrap();
+
+        let structured = invalid.structured_output.clone().unwrap();
+        assert!(!structured.is_valid());
+        assert_eq!(structured.errors[0].path, "$.answer");
+
+        assert!(apply_structured_output(&mut invalid, &format(), StrictMode::Strict).is_err());
+
+        let mut not_json = response("forty-two");
+        apply_structured_output(&mut not_json, &format(), StrictMode::Coerce).unwrap();
+        assert!(not_json.structured_output.unwrap().parsed.is_none());
+
+        // Formats without a schema are not validated
+        let mut text = response("forty-two");
+        apply_structured_output(&mut text, &ResponseFormat::JsonObject, StrictMode::Strict).unwrap();
+        assert!(text.structured_output.is_none());
+    }
+}
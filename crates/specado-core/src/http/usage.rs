@@ -0,0 +1,86 @@
+//! Token usage extraction
+//!
+//! Reads token counts out of a provider usage object using the JSONPaths a
+//! model declares in its response normalization rules, so OpenAI-style
+//! `prompt_tokens` and Anthropic-style `input_tokens` both land in the same
+//! [`TokenUsage`].
+
+use serde_json::Value;
+
+use crate::response::TokenUsage;
+use crate::translation::jsonpath::JSONPath;
+use crate::types::UsagePaths;
+use crate::Result;
+
+/// Extract token counts from a usage object
+///
+/// Returns `None` when the usage object holds none of the declared counts.
+pub fn extract_usage(usage: &Value, paths: &UsagePaths) -> Result<Option<TokenUsage>> {
+    let input = select_count(usage, paths.input_tokens_path.as_deref())?;
+    let output = select_count(usage, paths.output_tokens_path.as_deref())?;
+    let total = select_count(usage, paths.total_tokens_path.as_deref())?;
+    let reasoning = select_count(usage, paths.reasoning_tokens_path.as_deref())?;
+    let cache_read = select_count(usage, paths.cache_read_tokens_path.as_deref())?;
+    let cache_write = select_count(usage, paths.cache_write_tokens_path.as_deref())?;
+
+    if [input, output, total, reasoning, cache_read, cache_write].iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let input_tokens = input.unwrap_or(0);
+    let output_tokens = output.unwrap_or(0);
+    Ok(Some(TokenUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens: total.unwrap_or(input_tokens + output_tokens),
+        reasoning_tokens: reasoning,
+        cache_read_tokens: cache_read,
+        cache_write_tokens: cache_write,
+    }))
+}
+
+/// Select a token count at an optional JSONPath
+fn select_count(usage: &Value, path: Option<&str>) -> Result<Option<u64>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let path = JSONPath::parse(path)?;
+    Ok(path.execute(usage)?.into_iter().next().and_then(Value::as_u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_declared_paths() {
+        let paths = UsagePaths {
+            input_tokens_path: Some("$.prompt_tokens".to_string()),
+            output_tokens_path: Some("$.completion_tokens".to_string()),
+            reasoning_tokens_path: Some("$.completion_tokens_details.reasoning_tokens".to_string()),
+            cache_read_tokens_path: Some("$.prompt_tokens_details.cached_tokens".to_string()),
+            ..UsagePaths::default()
+        };
+        let usage = json!({
+            "prompt_tokens": 14,
+            "completion_tokens": 7,
+            "completion_tokens_details": {"reasoning_tokens": 3},
+            "prompt_tokens_details": {"cached_tokens": 10}
+        });
+
+        let usage = extract_usage(&usage, &paths).unwrap().unwrap();
+        assert_eq!(usage.input_tokens, 14);
+        assert_eq!(usage.output_tokens, 7);
+        assert_eq!(usage.total_tokens, 21);
+        assert_eq!(usage.reasoning_tokens, Some(3));
+        assert_eq!(usage.cache_read_tokens, Some(10));
+        assert_eq!(usage.cache_write_tokens, None);
+    }
+
+    #[test]
+    fn test_no_counts() {
+        let usage = extract_usage(&json!({"prompt_tokens": 5}), &UsagePaths::conventional()).unwrap();
+        assert!(usage.is_none());
+    }
+}
@@ -27,6 +27,7 @@ pub mod llm;
 pub mod provider_discovery;
 pub mod response;
 pub mod specs;
+pub mod tokenizer;
 pub mod translation;
 pub mod types;
 
@@ -40,31 +41,35 @@ pub use types::{
     PromptSpec, ProviderSpec,
     
     // Message types
-    Message, MessageRole,
+    Message, MessageRole, MessageContent, ContentPart, MediaSource,
     
     // Tool types
     Tool, ToolChoice, ToolCall,
     
     // Configuration types
     ResponseFormat, SamplingParams, Limits, MediaConfig, AdvancedParams,
-    ReasoningEffort, ReasoningMode, VerbosityLevel,
+    ReasoningEffort, ReasoningMode, VerbosityLevel, RagConfig, RagDocument, ConversationConfig,
+    Preferences, TruncationMode,
     
     // Provider types
     ProviderInfo, ModelSpec, Endpoints, EndpointConfig,
     InputModes, ToolingConfig, JsonOutputConfig,
-    Constraints, ConstraintLimits, Mappings,
+    MessageRendering, ContentBlockTemplates, ContentMode, ToolRendering, ToolChoiceTemplates,
+    Constraints, ConstraintLimits, Mappings, ModelExtensions, RagConfiguration, ConversationManagement,
+    ConstraintExtensions, PromptTruncation,
     ResponseNormalization, SyncNormalization, StreamNormalization,
-    EventSelector, EventRoute,
+    EventSelector, EventRoute, UsagePaths, ArgsEncoding,
     
     // Translation types
     TranslationResult, LossinessReport, LossinessItem, LossinessSummary,
     TranslationMetadata,
     
     // Response types
-    UniformResponse, FinishReason,
+    UniformResponse, FinishReason, StructuredOutput, StructuredOutputError,
+    ReasoningOutput, ReasoningBlock, Candidate, TokenLogprob, TopLogprob, Citation,
     
     // Stream types
-    StreamHandle,
+    StreamHandle, StreamEvent,
 };
 
 // Re-export error enums
@@ -73,12 +78,18 @@ pub use error::LossinessCode;
 // Re-export high-level LLM interface
 pub use llm::{LLM, GenerationMode};
 
+// Re-export stream accumulation
+pub use http::StreamAccumulator;
+
 // Re-export response extensions
 pub use response::{ResponseExt, TokenUsage, ToolCallInfo};
 
 // Re-export specs types
 pub use specs::{Capabilities, CapabilityDetector};
 
+// Re-export token counting
+pub use tokenizer::{Tokenizer, HeuristicTokenizer, BpeTokenizer, load_vocabulary, register_tokenizer, tokenizer_for};
+
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -90,7 +101,7 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///
 /// See `translation::translate` for full documentation.
 pub use translation::{
-    translate, StrictnessAction, StrictnessPolicy, PolicyResult,
+    convert, translate, translate_reverse, StrictnessAction, StrictnessPolicy, PolicyResult,
     TransformationPipeline, TransformationRule, TransformationRuleBuilder,
     TransformationType, TransformationDirection, TransformationError,
     ValueType, ConversionFormula, Condition,
@@ -106,9 +117,23 @@ pub use translation::{
 ///   - `provider_spec`: The provider specification
 ///   - `model_id`: The model to use
 ///   - `request_body`: The request payload
+///   - `response_format` (optional): the prompt's response format; when
+///     the translation's `metadata.json_mode` is set the JSON document is
+///     extracted from the reply and parsed into `structured_output`, and a
+///     JSON Schema format is validated against the reply
+///   - `strict_mode` (optional): with `"Strict"`, a reply that does not match
+///     the response format's schema, or that the model's normalization rules
+///     cannot fully represent, is an error
+///   - `candidate_count` (optional): the prompt's candidate count; models
+///     without a native candidate count are called this many times and the
+///     replies are returned as `candidates`
+///   - `document_ids` (optional): IDs of the prompt's RAG documents, in
+///     order; citations are reported against them
 ///
 /// # Returns
-/// A normalized UniformResponse or an error
+/// A normalized UniformResponse or an error; its `lossiness` reports what
+/// the response normalization could not represent, with the items of
+/// repeated candidate calls under `candidates[N]`
 pub async fn run(provider_request: &serde_json::Value) -> Result<UniformResponse> {
     // Extract components from the provider request
     let provider_spec_json = provider_request.get("provider_spec")
@@ -155,17 +180,125 @@ pub async fn run(provider_request: &serde_json::Value) -> Result<UniformResponse
     let response = client.execute_chat_completion(model, request_body.clone()).await?;
     
     // Normalize the response using the model's response_normalization config
-    let normalized = http::normalize_response(&response, model, model_id)?;
+    let strict_mode: StrictMode = match provider_request.get("strict_mode") {
+        Some(mode) => serde_json::from_value(mode.clone()).map_err(|e| Error::Json {
+            message: format!("Failed to parse strict_mode: {}", e),
+            source: e,
+        })?,
+        None => StrictMode::Warn,
+    };
+    let document_ids = provider_request.get("document_ids")
+        .and_then(|ids| ids.as_array())
+        .map(|ids| ids.iter().filter_map(|id| id.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    let mut normalizer = http::ResponseNormalizer::new(model.clone())
+        .with_strict_mode(strict_mode)
+        .with_documents(document_ids);
+    if let Some(format) = provider_request.get("response_format") {
+        let format: ResponseFormat = serde_json::from_value(format.clone())
+            .map_err(|e| Error::Json {
+                message: format!("Failed to parse response_format: {}", e),
+                source: e,
+            })?;
+        normalizer = normalizer.with_response_format(format, strict_mode);
+    }
+    let (mut normalized, lossiness) = normalizer.normalize_response(&response, model_id)?;
+    let mut tracker = translation::LossinessTracker::new(strict_mode);
+    for item in lossiness.items {
+        tracker.add_item(item);
+    }
+
+    // Emulate several candidates through repeated calls
+    let candidate_count = provider_request.get("candidate_count").and_then(|v| v.as_u64()).unwrap_or(1);
+    if candidate_count > 1 && !model.mappings.paths.contains_key(translation::CANDIDATE_COUNT_PATH) {
+        normalized.candidates = normalized.candidates();
+        for index in 1..candidate_count as usize {
+            let response = client.execute_chat_completion(model, request_body.clone()).await?;
+            let (candidate, lossiness) = normalizer.normalize_response(&response, model_id)?;
+            for mut item in lossiness.items {
+                item.path = format!("candidates[{}]{}", index, item.path.strip_prefix('$').unwrap_or(&item.path));
+                tracker.add_item(item);
+            }
+            if let (Some(total), Some(usage)) = (normalized.usage.as_mut(), candidate.usage.as_ref()) {
+                total.accumulate(usage);
+            }
+            normalized.candidates.push(Candidate {
+                index,
+                content: candidate.content,
+                finish_reason: candidate.finish_reason,
+                tool_calls: candidate.tool_calls,
+                logprobs: candidate.logprobs,
+            });
+        }
+    }
+    normalized.lossiness = Some(tracker.build_report());
     
     Ok(normalized)
 }
 
-/// Placeholder stream function (to be implemented in L3)
-pub async fn stream(_provider_request_json: &serde_json::Value) -> Result<StreamHandle> {
-    Err(Error::Unsupported {
-        message: "Stream functionality not yet implemented (L3)".to_string(),
-        feature: Some("stream".to_string()),
-    })
+/// Stream a compiled provider request
+///
+/// This function sends the compiled provider request to the provider's
+/// streaming endpoint and normalizes each provider event into a uniform
+/// [`StreamEvent`] using the model's `response_normalization.stream` rules.
+///
+/// # Arguments
+/// * `provider_request_json` - The compiled provider request, with the same
+///   shape as the one accepted by [`run`]
+///
+/// # Returns
+/// A [`StreamHandle`] yielding normalized events, or an error if the request
+/// could not be started
+pub async fn stream(provider_request_json: &serde_json::Value) -> Result<StreamHandle> {
+    let provider_spec_json = provider_request_json.get("provider_spec")
+        .ok_or_else(|| Error::Validation {
+            field: "provider_spec".to_string(),
+            message: "Missing provider_spec in request".to_string(),
+            expected: Some("ProviderSpec object".to_string()),
+        })?;
+    
+    let model_id = provider_request_json.get("model_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Validation {
+            field: "model_id".to_string(),
+            message: "Missing or invalid model_id".to_string(),
+            expected: Some("String model ID".to_string()),
+        })?;
+    
+    let mut request_body = provider_request_json.get("request_body")
+        .cloned()
+        .ok_or_else(|| Error::Validation {
+            field: "request_body".to_string(),
+            message: "Missing request_body".to_string(),
+            expected: Some("Request payload object".to_string()),
+        })?;
+    
+    // Ask the provider for a streamed response unless the caller already decided
+    if let Some(body) = request_body.as_object_mut() {
+        body.entry("stream").or_insert(serde_json::Value::Bool(true));
+    }
+    
+    let provider_spec: ProviderSpec = serde_json::from_value(provider_spec_json.clone())
+        .map_err(|e| Error::Json {
+            message: format!("Failed to parse provider_spec: {}", e),
+            source: e,
+        })?;
+    
+    let client = http::HttpClient::with_default_config(provider_spec)?;
+    
+    let model = client.get_model(model_id)
+        .ok_or_else(|| Error::Provider {
+            provider: client.provider_spec().provider.name.clone(),
+            message: format!("Model '{}' not found", model_id),
+            source: None,
+        })?;
+    
+    // Reject specs we cannot decode before opening the connection
+    http::StreamProtocol::parse(&model.response_normalization.stream.protocol)?;
+    
+    let response = client.execute_streaming_chat_completion(model, request_body).await?;
+    
+    http::normalize_stream(response.bytes_stream(), model)
 }
 
 #[cfg(test)]
@@ -250,9 +250,12 @@ impl LLM {
         let messages = vec![
             Message {
                 role: MessageRole::User,
-                content: prompt.to_string(),
+                content: prompt.into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }
         ];
         
@@ -269,6 +272,9 @@ impl LLM {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         })
     }
     
@@ -292,6 +298,9 @@ impl LLM {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         })
     }
     
@@ -306,6 +315,11 @@ impl LLM {
                 top_k: None,
                 frequency_penalty: None,
                 presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
             },
             GenerationMode::Precise => SamplingParams {
                 temperature: Some(0.2),
@@ -313,6 +327,11 @@ impl LLM {
                 top_k: None,
                 frequency_penalty: None,
                 presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
             },
             GenerationMode::Fast => SamplingParams {
                 temperature: Some(0.7),
@@ -320,6 +339,11 @@ impl LLM {
                 top_k: None,
                 frequency_penalty: None,
                 presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
             },
             GenerationMode::Balanced => SamplingParams {
                 temperature: Some(0.5),
@@ -327,6 +351,11 @@ impl LLM {
                 top_k: None,
                 frequency_penalty: None,
                 presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
             },
         };
         
@@ -30,9 +30,12 @@ pub fn message_strategy() -> impl Strategy<Value = Message> {
     ).prop_map(|(role, content, name)| {
         Message {
             role,
-            content,
+            content: content.into(),
             name,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }
     })
 }
@@ -52,6 +55,11 @@ pub fn sampling_params_strategy() -> impl Strategy<Value = SamplingParams> {
             top_k,
             frequency_penalty,
             presence_penalty,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         }
     })
 }
@@ -120,6 +128,9 @@ pub fn prompt_spec_strategy() -> impl Strategy<Value = PromptSpec> {
             media: None,
             advanced: None,
             strict_mode,
+            rag: None,
+            conversation: None,
+            preferences: None,
         }
     })
 }
@@ -250,7 +261,7 @@ mod tests {
     proptest! {
         #[test]
         fn test_message_strategy_generates_valid_messages(msg in message_strategy()) {
-            assert!(!msg.content.is_empty());
+            assert!(!msg.content.text().is_empty());
             // Roles are always valid by construction
         }
 
@@ -3,7 +3,9 @@
 //! This module extends the UniformResponse type with convenience methods
 //! to provide a consistent interface regardless of the underlying provider.
 
-use crate::types::{UniformResponse, FinishReason};
+use crate::http::extract_usage;
+use crate::types::{Candidate, UniformResponse, FinishReason, Message, ReasoningOutput, UsagePaths};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Extension trait for UniformResponse to provide unified access methods
@@ -22,6 +24,16 @@ pub trait ResponseExt {
     
     /// Get any tool calls in the response
     fn tool_calls(&self) -> Vec<ToolCallInfo>;
+    
+    /// Get the model's reasoning, if any was returned
+    fn reasoning(&self) -> Option<&ReasoningOutput>;
+    
+    /// Get every candidate; a single-candidate response yields itself
+    fn candidates(&self) -> Vec<Candidate>;
+    
+    /// Build the assistant message that continues the conversation, carrying
+    /// tool calls and the reasoning blocks providers require on later turns
+    fn to_message(&self) -> Message;
 }
 
 impl ResponseExt for UniformResponse {
@@ -30,30 +42,14 @@ impl ResponseExt for UniformResponse {
     }
     
     fn usage(&self) -> TokenUsage {
-        // Extract usage from raw_metadata
-        let usage_data = self.raw_metadata.get("usage");
-        
-        TokenUsage {
-            input_tokens: usage_data
-                .and_then(|u| u.get("input_tokens"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0),
-            output_tokens: usage_data
-                .and_then(|u| u.get("output_tokens"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0),
-            total_tokens: usage_data
-                .and_then(|u| u.get("total_tokens"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0),
-            reasoning_tokens: usage_data
-                .and_then(|u| u.get("output_tokens_details"))
-                .and_then(|d| d.get("reasoning_tokens"))
-                .and_then(|v| v.as_u64()),
-            cache_tokens: usage_data
-                .and_then(|u| u.get("cache_creation_input_tokens"))
-                .and_then(|v| v.as_u64()),
+        if let Some(usage) = &self.usage {
+            return usage.clone();
         }
+
+        // Responses built without a model spec fall back to the conventional keys
+        self.raw_metadata.get("usage")
+            .and_then(|usage| extract_usage(usage, &UsagePaths::conventional()).ok().flatten())
+            .unwrap_or_default()
     }
     
     fn is_truncated(&self) -> bool {
@@ -75,16 +71,66 @@ impl ResponseExt for UniformResponse {
             })
             .unwrap_or_default()
     }
+    
+    fn reasoning(&self) -> Option<&ReasoningOutput> {
+        self.reasoning.as_ref()
+    }
+    
+    fn candidates(&self) -> Vec<Candidate> {
+        if !self.candidates.is_empty() {
+            return self.candidates.clone();
+        }
+        vec![Candidate {
+            index: 0,
+            content: self.content.clone(),
+            finish_reason: self.finish_reason,
+            tool_calls: self.tool_calls.clone(),
+            logprobs: None,
+        }]
+    }
+    
+    fn to_message(&self) -> Message {
+        let mut message = Message::assistant(self.content.clone());
+        message.tool_calls = self.tool_calls.clone().filter(|calls| !calls.is_empty());
+        message.reasoning = self.reasoning.as_ref()
+            .map(|reasoning| reasoning.blocks.clone())
+            .filter(|blocks| !blocks.is_empty());
+        message
+    }
 }
 
 /// Token usage information
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub output_tokens: u64,
     pub total_tokens: u64,
-    pub reasoning_tokens: Option<u64>,  // GPT-5 specific
-    pub cache_tokens: Option<u64>,      // Claude specific
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<u64>,
+    /// Input tokens served from the provider's prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens: Option<u64>,
+    /// Input tokens written to the provider's prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens: Option<u64>,
+}
+
+impl TokenUsage {
+    /// Add the counts of another response, e.g. one of several calls made
+    /// for a single request
+    pub fn accumulate(&mut self, other: &TokenUsage) {
+        fn add(total: &mut Option<u64>, other: Option<u64>) {
+            if let Some(other) = other {
+                *total = Some(total.unwrap_or(0) + other);
+            }
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+        add(&mut self.reasoning_tokens, other.reasoning_tokens);
+        add(&mut self.cache_read_tokens, other.cache_read_tokens);
+        add(&mut self.cache_write_tokens, other.cache_write_tokens);
+    }
 }
 
 /// Simplified tool call information
@@ -118,11 +164,29 @@ mod tests {
                     "total_tokens": 30
                 }
             }),
+            usage: None,
+            structured_output: None,
+            reasoning: None,
+            candidates: Vec::new(),
+            logprobs: None,
+            citations: Vec::new(),
+            response_id: None,
+            lossiness: None,
         };
         
         let usage = response.usage();
         assert_eq!(usage.input_tokens, 10);
         assert_eq!(usage.output_tokens, 20);
         assert_eq!(usage.total_tokens, 30);
+        
+        let mut total = usage.clone();
+        total.accumulate(&TokenUsage { reasoning_tokens: Some(4), ..usage });
+        assert_eq!(total.total_tokens, 60);
+        assert_eq!(total.reasoning_tokens, Some(4));
+        
+        // A single-candidate response is its own candidate
+        let candidates = response.candidates();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].content, "test");
     }
 }
\ No newline at end of file
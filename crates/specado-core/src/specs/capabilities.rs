@@ -421,7 +421,7 @@ mod tests {
         assert!(!old_cached.is_fresh());
     }
     
-    use crate::types::{ModelSpec, Endpoints, EndpointConfig, InputModes, ToolingConfig, JsonOutputConfig, Constraints, ConstraintLimits, Mappings, ResponseNormalization, SyncNormalization, StreamNormalization, EventSelector};
+    use crate::types::{ModelSpec, Endpoints, EndpointConfig, InputModes, ToolingConfig, JsonOutputConfig, Constraints, ConstraintLimits, Mappings, ResponseNormalization, SyncNormalization, ArgsEncoding, StreamNormalization, EventSelector};
     use serde_json::json;
     
     fn create_test_model_spec() -> ModelSpec {
@@ -455,12 +455,17 @@ mod tests {
                 parallel_tool_calls_default: true,
                 can_disable_parallel_tool_calls: true,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: true,
                 strategy: "json_schema".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None, // Test inference from spec structure
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: json!({
                 "max_tokens": {
                     "type": "integer",
@@ -486,7 +491,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 16384,
                     max_system_prompt_bytes: 32768,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: std::collections::HashMap::new(),
@@ -497,6 +504,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish_reason".to_string(),
                     finish_reason_map: std::collections::HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -504,8 +531,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         }
     }
     
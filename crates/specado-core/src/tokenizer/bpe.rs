@@ -0,0 +1,121 @@
+//! Byte-level BPE token counts from a local vocabulary file
+//!
+//! Vocabularies use the tiktoken format: one token per line, base64-encoded,
+//! followed by its merge rank. Text is split into words, numbers,
+//! punctuation and whitespace runs, and each piece is merged pairwise by
+//! lowest rank, as the OpenAI tokenizers do.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use regex::Regex;
+
+use super::Tokenizer;
+use crate::{Error, Result};
+
+/// Pre-tokenization pattern of the cl100k vocabulary, without its
+/// look-ahead for trailing whitespace
+const PIECE_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+/// Counts tokens by byte-pair encoding with a vocabulary's merge ranks
+pub struct BpeTokenizer {
+    ranks: HashMap<Vec<u8>, u32>,
+    pieces: Regex,
+}
+
+impl BpeTokenizer {
+    /// Load a tiktoken-format vocabulary file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let vocabulary = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io {
+                message: format!("Failed to read vocabulary from {:?}", path),
+                source: e,
+            })?;
+        Self::from_vocabulary(&vocabulary)
+    }
+
+    /// Parse a tiktoken-format vocabulary
+    pub fn from_vocabulary(vocabulary: &str) -> Result<Self> {
+        let mut ranks = HashMap::new();
+        for (number, line) in vocabulary.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let invalid = || Error::Configuration {
+                message: format!("Invalid vocabulary line {}: expected a base64 token and its rank", number + 1),
+                source: None,
+            };
+            let (token, rank) = line.split_once(' ').ok_or_else(invalid)?;
+            let token = STANDARD.decode(token).map_err(|_| invalid())?;
+            let rank = rank.trim().parse::<u32>().map_err(|_| invalid())?;
+            ranks.insert(token, rank);
+        }
+
+        Ok(Self {
+            ranks,
+            pieces: Regex::new(PIECE_PATTERN).expect("valid pre-tokenization pattern"),
+        })
+    }
+
+    /// Tokens of one pre-tokenized piece
+    ///
+    /// Bytes missing from the vocabulary count as one token each.
+    fn count_piece(&self, piece: &[u8]) -> u32 {
+        if self.ranks.contains_key(piece) {
+            return 1;
+        }
+
+        // Token boundaries, merged pairwise while a merge is in the vocabulary
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let merge = bounds.windows(3)
+                .enumerate()
+                .filter_map(|(index, window)| self.ranks.get(&piece[window[0]..window[2]]).map(|rank| (*rank, index)))
+                .min();
+            match merge {
+                Some((_, index)) => {
+                    bounds.remove(index + 1);
+                }
+                None => return (bounds.len() - 1) as u32,
+            }
+        }
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn count_tokens(&self, text: &str) -> u32 {
+        self.pieces.find_iter(text)
+            .map(|piece| self.count_piece(piece.as_str().as_bytes()))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "a", "b", "c", " ", "ab", "abc"
+    const VOCABULARY: &str = "YQ== 0\nYg== 1\nYw== 2\nIA== 3\nYWI= 4\nYWJj 5\n";
+
+    #[test]
+    fn test_count_tokens() {
+        let tokenizer = BpeTokenizer::from_vocabulary(VOCABULARY).unwrap();
+        assert_eq!(tokenizer.count_tokens(""), 0);
+        assert_eq!(tokenizer.count_tokens("abc"), 1);
+        // " abc" has no merge with the space
+        assert_eq!(tokenizer.count_tokens("abc abc"), 3);
+        assert_eq!(tokenizer.count_tokens("cab"), 2);
+        // Bytes outside the vocabulary count one each
+        assert_eq!(tokenizer.count_tokens("xyz"), 3);
+    }
+
+    #[test]
+    fn test_invalid_vocabulary() {
+        assert!(BpeTokenizer::from_vocabulary("YQ==").is_err());
+        assert!(BpeTokenizer::from_vocabulary("not-base64! 0").is_err());
+        assert!(BpeTokenizer::from_vocabulary("YQ== first").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Offline token estimate from the text length
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use super::Tokenizer;
+
+/// Estimates tokens as the number of characters over an average token
+/// length, rounded up
+#[derive(Debug, Clone, Copy)]
+pub struct HeuristicTokenizer {
+    chars_per_token: f32,
+}
+
+impl HeuristicTokenizer {
+    /// Estimator for a family averaging `chars_per_token` characters a token
+    pub fn new(chars_per_token: f32) -> Self {
+        Self { chars_per_token }
+    }
+}
+
+impl Default for HeuristicTokenizer {
+    fn default() -> Self {
+        Self::new(4.0)
+    }
+}
+
+impl Tokenizer for HeuristicTokenizer {
+    fn count_tokens(&self, text: &str) -> u32 {
+        (text.chars().count() as f32 / self.chars_per_token).ceil() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_tokens() {
+        let tokenizer = HeuristicTokenizer::default();
+        assert_eq!(tokenizer.count_tokens(""), 0);
+        assert_eq!(tokenizer.count_tokens("abcd"), 1);
+        assert_eq!(tokenizer.count_tokens("abcde"), 2);
+        assert_eq!(HeuristicTokenizer::new(3.5).count_tokens("abcdefg"), 2);
+        assert_eq!(tokenizer.count_tokens("éèêë"), 1);
+    }
+}
@@ -0,0 +1,160 @@
+//! Token counting before a request is sent
+//!
+//! Prompt truncation and `limits.max_prompt_tokens` validation need the size
+//! of a prompt in tokens. Each model family has a [`Tokenizer`], selected by
+//! the longest registered prefix of `ModelSpec.family`: a fast offline
+//! estimate by default, or an exact BPE tokenizer loaded from a local
+//! vocabulary file with [`load_vocabulary`].
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+pub mod bpe;
+pub mod heuristic;
+
+pub use bpe::BpeTokenizer;
+pub use heuristic::HeuristicTokenizer;
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::types::{ContentPart, Message, MessageContent};
+use crate::Result;
+
+/// Tokens a message costs beyond its content
+pub const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+/// Tokens counted for an image, audio or file part
+pub const MEDIA_PART_TOKENS: u32 = 256;
+
+/// Counts the tokens of a text for a model family
+pub trait Tokenizer: Send + Sync {
+    /// Number of tokens in the text
+    fn count_tokens(&self, text: &str) -> u32;
+}
+
+/// Tokenizers by model family prefix
+pub struct TokenizerRegistry {
+    families: Vec<(String, Arc<dyn Tokenizer>)>,
+    fallback: Arc<dyn Tokenizer>,
+}
+
+impl TokenizerRegistry {
+    /// Registry with heuristic estimates for the known families
+    pub fn new() -> Self {
+        Self {
+            families: vec![
+                ("gpt".to_string(), Arc::new(HeuristicTokenizer::new(4.0))),
+                ("claude".to_string(), Arc::new(HeuristicTokenizer::new(3.5))),
+            ],
+            fallback: Arc::new(HeuristicTokenizer::default()),
+        }
+    }
+
+    /// Use a tokenizer for the families starting with `family`
+    pub fn register(&mut self, family: &str, tokenizer: Arc<dyn Tokenizer>) {
+        self.families.retain(|(prefix, _)| prefix != family);
+        self.families.push((family.to_string(), tokenizer));
+    }
+
+    /// Tokenizer for a model family
+    pub fn tokenizer(&self, family: &str) -> Arc<dyn Tokenizer> {
+        self.families.iter()
+            .filter(|(prefix, _)| family.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or_else(|| self.fallback.clone(), |(_, tokenizer)| tokenizer.clone())
+    }
+}
+
+impl Default for TokenizerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn registry() -> &'static RwLock<TokenizerRegistry> {
+    static REGISTRY: OnceLock<RwLock<TokenizerRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(TokenizerRegistry::new()))
+}
+
+/// Use a tokenizer for the model families starting with `family`
+pub fn register_tokenizer(family: &str, tokenizer: Arc<dyn Tokenizer>) {
+    if let Ok(mut registry) = registry().write() {
+        registry.register(family, tokenizer);
+    }
+}
+
+/// Count tokens exactly for the model families starting with `family`,
+/// using a tiktoken-format vocabulary file
+pub fn load_vocabulary(family: &str, path: impl AsRef<Path>) -> Result<()> {
+    let tokenizer = BpeTokenizer::from_file(path)?;
+    register_tokenizer(family, Arc::new(tokenizer));
+    Ok(())
+}
+
+/// Tokenizer for a model family
+pub fn tokenizer_for(family: &str) -> Arc<dyn Tokenizer> {
+    match registry().read() {
+        Ok(registry) => registry.tokenizer(family),
+        Err(_) => Arc::new(HeuristicTokenizer::default()),
+    }
+}
+
+/// Tokens of a message, including the tool calls it requests
+pub fn count_message_tokens(tokenizer: &dyn Tokenizer, message: &Message) -> u32 {
+    let content = match &message.content {
+        MessageContent::Text(text) => tokenizer.count_tokens(text),
+        MessageContent::Parts(parts) => parts.iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => tokenizer.count_tokens(text),
+                _ => MEDIA_PART_TOKENS,
+            })
+            .sum(),
+    };
+    let tool_calls: u32 = message.tool_calls.iter()
+        .flatten()
+        .map(|call| tokenizer.count_tokens(&call.name) + tokenizer.count_tokens(&call.arguments.to_string()))
+        .sum();
+    MESSAGE_OVERHEAD_TOKENS + content + tool_calls
+}
+
+/// Tokens of a conversation
+pub fn count_prompt_tokens(tokenizer: &dyn Tokenizer, messages: &[Message]) -> u32 {
+    messages.iter().map(|message| count_message_tokens(tokenizer, message)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Words;
+
+    impl Tokenizer for Words {
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.split_whitespace().count() as u32
+        }
+    }
+
+    #[test]
+    fn test_registry_selects_longest_prefix() {
+        let mut registry = TokenizerRegistry::new();
+        let text = "one two three four five six seven eight";
+        assert_eq!(registry.tokenizer("gpt-5").count_tokens(text), 10);
+        assert_eq!(registry.tokenizer("claude-opus").count_tokens(text), 12);
+        assert_eq!(registry.tokenizer("unknown").count_tokens(text), 10);
+
+        registry.register("gpt-5", Arc::new(Words));
+        assert_eq!(registry.tokenizer("gpt-5").count_tokens(text), 8);
+        assert_eq!(registry.tokenizer("gpt-4").count_tokens(text), 10);
+    }
+
+    #[test]
+    fn test_count_message_tokens() {
+        let tokenizer = HeuristicTokenizer::default();
+        assert_eq!(count_message_tokens(&tokenizer, &Message::user("")), MESSAGE_OVERHEAD_TOKENS);
+        assert_eq!(count_message_tokens(&tokenizer, &Message::user("abcdefghi")), MESSAGE_OVERHEAD_TOKENS + 3);
+
+        let parts = Message::user(vec![ContentPart::text("abcd"), ContentPart::image_url("https://example.com/a.png")]);
+        assert_eq!(count_message_tokens(&tokenizer, &parts), MESSAGE_OVERHEAD_TOKENS + 1 + MEDIA_PART_TOKENS);
+    }
+}
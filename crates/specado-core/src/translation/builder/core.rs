@@ -59,6 +59,7 @@ impl TranslationResultBuilder {
             timestamp: chrono::Utc::now().to_rfc3339(),
             duration_ms: None,
             strict_mode: context.strict_mode,
+            json_mode: false,
         };
 
         let lossiness_tracker = LossinessTracker::new(context.strict_mode);
@@ -132,6 +133,7 @@ impl TranslationResultBuilder {
             timestamp: chrono::Utc::now().to_rfc3339(),
             duration_ms: None,
             strict_mode,
+            json_mode: false,
         };
         self.with_metadata(metadata)
     }
@@ -110,6 +110,7 @@ impl TranslationResultBuilder {
             timestamp: chrono::Utc::now().to_rfc3339(),
             duration_ms: None,
             strict_mode: context.strict_mode,
+            json_mode: false,
         };
 
         Self {
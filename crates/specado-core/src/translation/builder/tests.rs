@@ -33,6 +33,7 @@ fn test_builder_with_metadata() {
         timestamp: "2025-01-01T00:00:00Z".to_string(),
         duration_ms: Some(100),
         strict_mode: StrictMode::Warn,
+        json_mode: false,
     };
 
     let result = TranslationResultBuilder::new()
@@ -119,6 +120,7 @@ fn test_builder_getters() {
         timestamp: "now".to_string(),
         duration_ms: None,
         strict_mode: StrictMode::Strict,
+        json_mode: false,
     };
 
     let builder = TranslationResultBuilder::new()
@@ -191,6 +193,7 @@ fn test_merge_builders() {
             timestamp: "now".to_string(),
             duration_ms: None,
             strict_mode: StrictMode::Warn,
+            json_mode: false,
         });
 
     let builder2 = TranslationResultBuilder::new()
@@ -345,7 +348,7 @@ fn create_test_context() -> crate::translation::TranslationContext {
     use crate::{
         Constraints, ConstraintLimits, EndpointConfig, Endpoints, InputModes, JsonOutputConfig,
         Mappings, Message, MessageRole, ProviderInfo, ProviderSpec, PromptSpec, 
-        ResponseNormalization, StreamNormalization, SyncNormalization, ToolingConfig, ModelSpec,
+        ResponseNormalization, StreamNormalization, SyncNormalization, ArgsEncoding, ToolingConfig, ModelSpec,
     };
     use std::collections::HashMap;
 
@@ -353,9 +356,12 @@ fn create_test_context() -> crate::translation::TranslationContext {
         model_class: "Chat".to_string(),
         messages: vec![Message {
             role: MessageRole::User,
-            content: "Test".to_string(),
+            content: "Test".into(),
             name: None,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }],
         tools: None,
         tool_choice: None,
@@ -365,6 +371,9 @@ fn create_test_context() -> crate::translation::TranslationContext {
         media: None,
         advanced: None,
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     };
 
     let provider_spec = ProviderSpec {
@@ -407,12 +416,17 @@ fn create_test_context() -> crate::translation::TranslationContext {
             parallel_tool_calls_default: false,
             can_disable_parallel_tool_calls: false,
             disable_switch: None,
+            rendering: None,
         },
         json_output: JsonOutputConfig {
             native_param: true,
             strategy: "native".to_string(),
+            instruction: None,
+            prefill: false,
         },
         capabilities: None,
+        transformations: Vec::new(),
+        message_rendering: None,
         parameters: serde_json::json!({}),
         constraints: Constraints {
             system_prompt_location: "first".to_string(),
@@ -422,7 +436,9 @@ fn create_test_context() -> crate::translation::TranslationContext {
             limits: ConstraintLimits {
                 max_tool_schema_bytes: 100000,
                 max_system_prompt_bytes: 10000,
+                context_window: None,
             },
+            extensions: None,
         },
         mappings: Mappings {
             paths: HashMap::new(),
@@ -433,6 +449,26 @@ fn create_test_context() -> crate::translation::TranslationContext {
                 content_path: "content".to_string(),
                 finish_reason_path: "finish".to_string(),
                 finish_reason_map: HashMap::new(),
+                usage_path: None,
+                usage: None,
+                tool_calls_path: None,
+                tool_name_path: None,
+                tool_args_path: None,
+                tool_id_path: None,
+                args_encoding: ArgsEncoding::default(),
+                reasoning_path: None,
+                reasoning_text_path: None,
+                reasoning_signature_path: None,
+                reasoning_redacted_path: None,
+                choices_path: None,
+                logprobs_path: None,
+                logprob_token_path: None,
+                logprob_value_path: None,
+                top_logprobs_path: None,
+                citations_path: None,
+                citation_document_path: None,
+                citation_text_path: None,
+                response_id_path: None,
             },
             stream: StreamNormalization {
                 protocol: "sse".to_string(),
@@ -440,8 +476,10 @@ fn create_test_context() -> crate::translation::TranslationContext {
                     type_path: "type".to_string(),
                     routes: vec![],
                 },
+                usage: None,
             },
         },
+        extensions: None,
     };
 
     crate::translation::TranslationContext::new(prompt_spec, provider_spec, model_spec, StrictMode::Warn)
@@ -538,16 +538,19 @@ mod tests {
     use crate::{PromptSpec, ProviderSpec, ModelSpec, ProviderInfo, MessageRole, Message};
     use crate::{Endpoints, EndpointConfig, InputModes, ToolingConfig, JsonOutputConfig};
     use crate::{Constraints, ConstraintLimits, Mappings, ResponseNormalization};
-    use crate::{SyncNormalization, StreamNormalization, EventSelector};
+    use crate::{SyncNormalization, ArgsEncoding, StreamNormalization, EventSelector};
     
     fn create_test_context() -> TranslationContext {
         let prompt = PromptSpec {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test".to_string(),
+                content: "Test".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: None,
             tool_choice: None,
@@ -557,6 +560,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         let provider = ProviderSpec {
@@ -596,12 +602,17 @@ mod tests {
                     parallel_tool_calls_default: false,
                     can_disable_parallel_tool_calls: false,
                     disable_switch: None,
+                    rendering: None,
                 },
                 json_output: JsonOutputConfig {
                     native_param: false,
                     strategy: "none".to_string(),
+                    instruction: None,
+                    prefill: false,
                 },
                 capabilities: None,
+                transformations: Vec::new(),
+                message_rendering: None,
                 parameters: serde_json::json!({}),
                 constraints: Constraints {
                     system_prompt_location: "first".to_string(),
@@ -617,7 +628,9 @@ mod tests {
                     limits: ConstraintLimits {
                         max_tool_schema_bytes: 100000,
                         max_system_prompt_bytes: 10000,
+                        context_window: None,
                     },
+                    extensions: None,
                 },
                 mappings: Mappings {
                     paths: HashMap::new(),
@@ -628,6 +641,26 @@ mod tests {
                         content_path: "content".to_string(),
                         finish_reason_path: "finish".to_string(),
                         finish_reason_map: HashMap::new(),
+                        usage_path: None,
+                        usage: None,
+                        tool_calls_path: None,
+                        tool_name_path: None,
+                        tool_args_path: None,
+                        tool_id_path: None,
+                        args_encoding: ArgsEncoding::default(),
+                        reasoning_path: None,
+                        reasoning_text_path: None,
+                        reasoning_signature_path: None,
+                        reasoning_redacted_path: None,
+                        choices_path: None,
+                        logprobs_path: None,
+                        logprob_token_path: None,
+                        logprob_value_path: None,
+                        top_logprobs_path: None,
+                        citations_path: None,
+                        citation_document_path: None,
+                        citation_text_path: None,
+                        response_id_path: None,
                     },
                     stream: StreamNormalization {
                         protocol: "sse".to_string(),
@@ -635,8 +668,10 @@ mod tests {
                             type_path: "type".to_string(),
                             routes: vec![],
                         },
+                        usage: None,
                     },
                 },
+                extensions: None,
             }],
         };
         
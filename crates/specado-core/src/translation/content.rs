@@ -0,0 +1,435 @@
+//! Message content rendering for provider requests
+//!
+//! Maps uniform messages and [`ContentPart`]s to the provider's native
+//! message and content block format, including assistant tool calls and
+//! tool results. Parts the model cannot accept are dropped with lossiness
+//! tracking.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use super::lossiness::LossinessTracker;
+use super::rendering::render_template;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use crate::types::{
+    ContentBlockTemplates, ContentMode, ContentPart, MediaSource, Message, MessageContent,
+    MessageRole, ModelSpec, ToolCall,
+};
+use crate::Result;
+
+pub use crate::types::ContentBlockFormat;
+
+impl ContentBlockFormat {
+    /// Built-in content block templates for this format
+    pub fn default_blocks(&self) -> ContentBlockTemplates {
+        match self {
+            ContentBlockFormat::OpenAi => ContentBlockTemplates {
+                text: Some(json!({ "type": "text", "text": "{{text}}" })),
+                image_url: Some(json!({
+                    "type": "image_url",
+                    "image_url": { "url": "{{url}}", "detail": "{{detail}}" },
+                })),
+                image_base64: Some(json!({
+                    "type": "image_url",
+                    "image_url": { "url": "{{data_url}}", "detail": "{{detail}}" },
+                })),
+                audio_url: None,
+                audio_base64: Some(json!({
+                    "type": "input_audio",
+                    "input_audio": { "data": "{{data}}", "format": "{{format}}" },
+                })),
+                file: Some(json!({
+                    "type": "file",
+                    "file": { "file_id": "{{file_id}}", "filename": "{{filename}}" },
+                })),
+                thinking: None,
+                redacted_thinking: None,
+                tool_call: Some(json!({
+                    "id": "{{id}}",
+                    "type": "function",
+                    "function": { "name": "{{name}}", "arguments": "{{arguments_json}}" },
+                })),
+                tool_calls_field: Some("tool_calls".to_string()),
+                tool_result: Some(json!({
+                    "role": "tool",
+                    "tool_call_id": "{{tool_call_id}}",
+                    "content": "{{content}}",
+                })),
+            },
+            ContentBlockFormat::Anthropic => ContentBlockTemplates {
+                text: Some(json!({ "type": "text", "text": "{{text}}" })),
+                image_url: Some(json!({
+                    "type": "image",
+                    "source": { "type": "url", "url": "{{url}}" },
+                })),
+                image_base64: Some(json!({
+                    "type": "image",
+                    "source": { "type": "base64", "media_type": "{{mime_type}}", "data": "{{data}}" },
+                })),
+                audio_url: None,
+                audio_base64: None,
+                file: Some(json!({
+                    "type": "document",
+                    "source": { "type": "file", "file_id": "{{file_id}}" },
+                })),
+                thinking: Some(json!({
+                    "type": "thinking",
+                    "thinking": "{{text}}",
+                    "signature": "{{signature}}",
+                })),
+                redacted_thinking: Some(json!({ "type": "redacted_thinking", "data": "{{data}}" })),
+                tool_call: Some(json!({
+                    "type": "tool_use",
+                    "id": "{{id}}",
+                    "name": "{{name}}",
+                    "input": "{{arguments}}",
+                })),
+                tool_calls_field: None,
+                tool_result: Some(json!({
+                    "role": "user",
+                    "content": [{
+                        "type": "tool_result",
+                        "tool_use_id": "{{tool_call_id}}",
+                        "content": "{{content}}",
+                    }],
+                })),
+            },
+        }
+    }
+}
+
+/// Renders messages and their content into provider format
+pub struct ContentRenderer<'a> {
+    blocks: ContentBlockTemplates,
+    content_mode: ContentMode,
+    images_supported: bool,
+    tools_supported: bool,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> ContentRenderer<'a> {
+    /// Create a renderer for the given format and model capabilities
+    ///
+    /// Block templates and content mode come from the model's
+    /// `message_rendering`, falling back to the format defaults.
+    pub fn new(
+        format: ContentBlockFormat,
+        model_spec: &ModelSpec,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let rendering = model_spec.message_rendering.as_ref();
+        Self {
+            blocks: rendering
+                .and_then(|r| r.blocks.clone())
+                .unwrap_or_else(|| format.default_blocks()),
+            content_mode: rendering.map(|r| r.content_mode).unwrap_or_default(),
+            images_supported: model_spec.input_modes.images,
+            tools_supported: model_spec.tooling.tools_supported,
+            policy,
+            tracker,
+        }
+    }
+
+    /// Render a whole message at position `index`
+    pub fn render_message(&self, index: usize, message: &Message) -> Result<Value> {
+        let path = format!("messages[{}]", index);
+        let content = self.render(&format!("{}.content", path), &message.content)?;
+
+        if message.role == MessageRole::Tool {
+            return self.render_tool_result(&path, message, content);
+        }
+
+        let mut rendered = json!({ "role": message.role, "content": content });
+
+        if let Some(tool_calls) = message.tool_calls.as_ref().filter(|calls| !calls.is_empty()) {
+            match self.blocks.tool_call.as_ref().filter(|_| self.tools_supported) {
+                None => self.apply_unsupported(
+                    &format!("{}.tool_calls", path),
+                    "tool_calls",
+                    serde_json::to_value(tool_calls).ok(),
+                )?,
+                Some(template) => {
+                    let mut calls = Vec::with_capacity(tool_calls.len());
+                    for (call_index, call) in tool_calls.iter().enumerate() {
+                        let call_path = format!("{}.tool_calls[{}]", path, call_index);
+                        calls.push(render_template(template, &self.tool_call_vars(&call_path, template, call)?));
+                    }
+                    match &self.blocks.tool_calls_field {
+                        Some(field) => {
+                            if message.content.is_blank() {
+                                rendered["content"] = Value::Null;
+                            }
+                            rendered[field.as_str()] = Value::Array(calls);
+                        }
+                        None => {
+                            let mut blocks = self.as_blocks(&message.content, rendered["content"].take());
+                            blocks.extend(calls);
+                            rendered["content"] = Value::Array(blocks);
+                        }
+                    }
+                }
+            }
+        }
+
+        let reasoning = self.render_reasoning(&path, message)?;
+        if !reasoning.is_empty() {
+            // Reasoning blocks lead the assistant turn
+            let content = match rendered["content"].take() {
+                Value::Array(blocks) => blocks,
+                other => self.as_blocks(&message.content, other),
+            };
+            rendered["content"] = Value::Array(reasoning.into_iter().chain(content).collect());
+        }
+
+        Ok(rendered)
+    }
+
+    /// Render the reasoning blocks of an assistant message at `path`
+    ///
+    /// Providers without reasoning block templates do not take reasoning
+    /// back and generate it afresh, so the blocks are dropped with
+    /// lossiness tracking.
+    fn render_reasoning(&self, path: &str, message: &Message) -> Result<Vec<Value>> {
+        let Some(blocks) = message.reasoning.as_ref().filter(|_| message.role == MessageRole::Assistant) else {
+            return Ok(Vec::new());
+        };
+
+        let mut rendered = Vec::with_capacity(blocks.len());
+        for (index, block) in blocks.iter().enumerate() {
+            let (block_json, feature) = match (&block.redacted, &block.text) {
+                (Some(data), _) => (
+                    self.blocks.redacted_thinking.as_ref()
+                        .map(|template| render_template(template, &vars([("data", json!(data))]))),
+                    "redacted_thinking",
+                ),
+                (None, Some(text)) => (
+                    self.blocks.thinking.as_ref().map(|template| {
+                        let vars = vars([("text", json!(text))])
+                            .with("signature", block.signature.as_ref().map(|s| json!(s)));
+                        render_template(template, &vars)
+                    }),
+                    "thinking",
+                ),
+                (None, None) => continue,
+            };
+
+            match block_json {
+                Some(block_json) => rendered.push(block_json),
+                None => self.apply_unsupported(
+                    &format!("{}.reasoning[{}]", path, index),
+                    feature,
+                    serde_json::to_value(block).ok(),
+                )?,
+            }
+        }
+        Ok(rendered)
+    }
+
+    /// Render message content at `path` (e.g. `messages[1].content`)
+    ///
+    /// Plain text is passed through as a string unless the content mode asks
+    /// for blocks; content parts become an array of provider blocks.
+    pub fn render(&self, path: &str, content: &MessageContent) -> Result<Value> {
+        let parts = match content {
+            MessageContent::Text(text) => match self.content_mode {
+                ContentMode::Auto => return Ok(Value::String(text.clone())),
+                ContentMode::Blocks => return Ok(Value::Array(self.text_block(text).into_iter().collect())),
+            },
+            MessageContent::Parts(parts) => parts,
+        };
+
+        let mut blocks = Vec::with_capacity(parts.len());
+        for (index, part) in parts.iter().enumerate() {
+            let part_path = format!("{}[{}]", path, index);
+            match self.render_part(part) {
+                Ok(block) => blocks.push(block),
+                Err(feature) => self.drop_part(&part_path, part, feature)?,
+            }
+        }
+
+        Ok(Value::Array(blocks))
+    }
+
+    /// Render a text block, if the provider declares one
+    pub fn text_block(&self, text: &str) -> Option<Value> {
+        let template = self.blocks.text.as_ref()?;
+        Some(render_template(template, &vars([("text", json!(text))])))
+    }
+
+    /// Render a single part, or name the feature the provider lacks
+    fn render_part(&self, part: &ContentPart) -> std::result::Result<Value, &'static str> {
+        let (template, feature, vars) = match part {
+            ContentPart::Text { text } => (&self.blocks.text, "text_input", vars([("text", json!(text))])),
+            ContentPart::Image { .. } if !self.images_supported => return Err("image_input"),
+            ContentPart::Image { source, detail } => {
+                let detail = detail.as_ref().map(|d| json!(d));
+                match source {
+                    MediaSource::Url { url } => (
+                        &self.blocks.image_url,
+                        "image_url_input",
+                        vars([("url", json!(url))]).with("detail", detail),
+                    ),
+                    MediaSource::Base64 { data, mime_type } => (
+                        &self.blocks.image_base64,
+                        "image_input",
+                        vars([
+                            ("data", json!(data)),
+                            ("mime_type", json!(mime_type)),
+                            ("data_url", json!(format!("data:{};base64,{}", mime_type, data))),
+                        ]).with("detail", detail),
+                    ),
+                }
+            }
+            ContentPart::Audio { source } => match source {
+                MediaSource::Url { url } => (&self.blocks.audio_url, "audio_url_input", vars([("url", json!(url))])),
+                MediaSource::Base64 { data, mime_type } => (
+                    &self.blocks.audio_base64,
+                    "audio_input",
+                    vars([
+                        ("data", json!(data)),
+                        ("mime_type", json!(mime_type)),
+                        ("format", json!(audio_format(mime_type))),
+                    ]),
+                ),
+            },
+            ContentPart::File { file_id, filename, mime_type } => (
+                &self.blocks.file,
+                "file_input",
+                vars([("file_id", json!(file_id))])
+                    .with("filename", filename.as_ref().map(|f| json!(f)))
+                    .with("mime_type", mime_type.as_ref().map(|m| json!(m))),
+            ),
+        };
+
+        match template {
+            Some(template) => Ok(render_template(template, &vars)),
+            None => Err(feature),
+        }
+    }
+
+    /// Render a tool result message
+    fn render_tool_result(&self, path: &str, message: &Message, content: Value) -> Result<Value> {
+        let tool_call_id = message.tool_call_id.clone().unwrap_or_default();
+
+        let Some(template) = self.blocks.tool_result.as_ref().filter(|_| self.tools_supported) else {
+            // Keep the result in the conversation as a plain user turn
+            let policy_result = self.policy.evaluate_feature_emulation(
+                path,
+                "tool_result",
+                "user message",
+                serde_json::to_value(message).ok(),
+            );
+            self.apply_policy(policy_result)?;
+            return Ok(json!({
+                "role": MessageRole::User,
+                "content": format!("Tool result for {}: {}", tool_call_id, message.content.text()),
+            }));
+        };
+
+        Ok(render_template(template, &vars([("tool_call_id", json!(tool_call_id)), ("content", content)])))
+    }
+
+    /// Template variables for the tool call at `path`
+    ///
+    /// Arguments are offered both as an object (`{{arguments}}`) and as a
+    /// JSON-encoded string (`{{arguments_json}}`). A template that needs the
+    /// object form cannot carry a string that is not valid JSON, so such
+    /// arguments are replaced with an empty object under the strictness
+    /// policy.
+    fn tool_call_vars(&self, path: &str, template: &Value, call: &ToolCall) -> Result<Map<String, Value>> {
+        let (arguments, arguments_json) = match &call.arguments {
+            Value::String(args) => match serde_json::from_str(args) {
+                Ok(arguments) => (arguments, args.clone()),
+                Err(_) if template.to_string().contains("{{arguments}}") => {
+                    let policy_result = self.policy.evaluate_value_replacement(
+                        &format!("{}.arguments", path),
+                        call.arguments.clone(),
+                        json!({}),
+                        "Tool call arguments are not valid JSON",
+                    );
+                    self.apply_policy(policy_result)?;
+                    (json!({}), args.clone())
+                }
+                Err(_) => (json!({}), args.clone()),
+            },
+            Value::Null => (json!({}), Value::Null.to_string()),
+            other => (other.clone(), other.to_string()),
+        };
+        Ok(vars([("name", json!(call.name)), ("arguments", arguments), ("arguments_json", json!(arguments_json))])
+            .with("id", call.id.as_ref().map(|id| json!(id))))
+    }
+
+    /// Turn rendered content into a list of blocks, omitting blank text
+    fn as_blocks(&self, content: &MessageContent, rendered: Value) -> Vec<Value> {
+        if matches!(content, MessageContent::Text(_)) && content.is_blank() {
+            return Vec::new();
+        }
+        match rendered {
+            Value::Array(blocks) => blocks,
+            Value::String(text) => self.text_block(&text).into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Drop a part the provider cannot represent, applying the strictness policy
+    fn drop_part(&self, path: &str, part: &ContentPart, feature: &str) -> Result<()> {
+        self.apply_unsupported(path, feature, serde_json::to_value(part).ok())
+    }
+
+    /// Record an unsupported feature and apply the strictness policy
+    fn apply_unsupported(&self, path: &str, feature: &str, value: Option<Value>) -> Result<()> {
+        let policy_result = self.policy.evaluate_unsupported_feature(path, feature, value);
+        self.apply_policy(policy_result)
+    }
+
+    /// Track the lossiness item of a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        if let Some(lossiness_item) = policy_result.lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+
+        match policy_result.action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+}
+
+/// Template variables for a block
+fn vars<const N: usize>(entries: [(&str, Value); N]) -> Map<String, Value> {
+    entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect()
+}
+
+/// Optional template variables
+trait WithVar {
+    fn with(self, key: &str, value: Option<Value>) -> Self;
+}
+
+impl WithVar for Map<String, Value> {
+    fn with(mut self, key: &str, value: Option<Value>) -> Self {
+        if let Some(value) = value {
+            self.insert(key.to_string(), value);
+        }
+        self
+    }
+}
+
+/// Audio format name from a MIME type (e.g. `audio/wav` -> `wav`)
+fn audio_format(mime_type: &str) -> &str {
+    match mime_type {
+        "audio/mpeg" => "mp3",
+        other => other.rsplit('/').next().unwrap_or(other),
+    }
+}
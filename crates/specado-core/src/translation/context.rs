@@ -108,7 +108,7 @@ mod tests {
     use crate::{
         Constraints, ConstraintLimits, EndpointConfig, Endpoints, InputModes, JsonOutputConfig,
         Mappings, Message, MessageRole, ProviderInfo, ResponseNormalization, StreamNormalization,
-        SyncNormalization, ToolingConfig,
+        SyncNormalization, ArgsEncoding, ToolingConfig,
     };
     use std::collections::HashMap;
 
@@ -117,9 +117,12 @@ mod tests {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test".to_string(),
+                content: "Test".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: None,
             tool_choice: None,
@@ -129,6 +132,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let provider_spec = ProviderSpec {
@@ -171,12 +177,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: true,
                 strategy: "native".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: serde_json::json!({}),
             constraints: Constraints {
                 system_prompt_location: "first".to_string(),
@@ -186,7 +197,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: HashMap::new(),
@@ -197,6 +210,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish".to_string(),
                     finish_reason_map: HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -204,8 +237,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         };
 
         TranslationContext::new(prompt_spec, provider_spec, model_spec, StrictMode::Warn)
@@ -0,0 +1,179 @@
+//! Server-side conversation continuation
+//!
+//! Models that declare `extensions.conversation_management` parameters
+//! receive the prompt's conversation ID and parent message ID (for example
+//! as `previous_response_id`), and the turns the provider already holds are
+//! left out of the request. Other models get the whole conversation replayed
+//! from `messages`, which is reported as emulation.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::{ConversationConfig, ConversationManagement, Message, MessageRole};
+use crate::{LossinessItem, Result};
+
+/// Passes a prompt's conversation settings to a provider request
+pub struct ConversationTranslator<'a> {
+    conversation: Option<&'a ConversationConfig>,
+    config: Option<&'a ConversationManagement>,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> ConversationTranslator<'a> {
+    /// Create a translator for the context's prompt and model
+    pub fn new(
+        context: &'a TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        Self {
+            conversation: context.prompt_spec.conversation.as_ref(),
+            config: context.model_spec.extensions.as_ref().and_then(|e| e.conversation_management.as_ref()),
+            policy,
+            tracker,
+        }
+    }
+
+    /// Messages to send: without history, or when the provider continues
+    /// the conversation itself, only the turns after the last assistant
+    /// message are kept
+    pub fn messages(&self, messages: &[Message]) -> Result<Vec<Message>> {
+        let Some(conversation) = self.conversation else {
+            return Ok(messages.to_vec());
+        };
+
+        if self.continuation(conversation).is_some() || !conversation.preserve_history {
+            return Ok(new_turns(messages));
+        }
+
+        if has_state(conversation) {
+            self.apply_policy(self.policy.evaluate_feature_emulation(
+                "conversation",
+                "server-side conversation state",
+                "history replay",
+                Some(json!(conversation)),
+            ))?;
+        }
+        Ok(messages.to_vec())
+    }
+
+    /// Set the conversation and parent message IDs on the request through
+    /// the model's declared parameters
+    pub fn apply(&self, request: &mut Value) -> Result<()> {
+        let Some(conversation) = self.conversation else {
+            return Ok(());
+        };
+        let Some(params) = self.continuation(conversation) else {
+            return Ok(());
+        };
+
+        for (param, id) in params {
+            request[param] = json!(id);
+        }
+
+        // A conversation ID the model has no parameter for is not needed
+        // when the parent message continues the conversation
+        if conversation.conversation_id.is_some()
+            && self.config.is_none_or(|config| config.conversation_id_param.is_none())
+        {
+            self.apply_policy(self.policy.evaluate_unsupported_feature(
+                "conversation.conversation_id",
+                "conversation_id",
+                conversation.conversation_id.as_ref().map(|id| json!(id)),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Request parameters continuing the conversation on the provider, or
+    /// `None` when the model cannot continue it
+    ///
+    /// Branching needs a model that accepts an earlier parent message.
+    fn continuation(&self, conversation: &'a ConversationConfig) -> Option<Vec<(&'a str, &'a str)>> {
+        let config = self.config?;
+        let parent = match conversation.branch_from.as_deref() {
+            Some(_) if !config.supports_branching => return None,
+            Some(branch_from) => Some(branch_from),
+            None => conversation.parent_message_id.as_deref(),
+        };
+
+        let params: Vec<(&str, &str)> = [
+            (config.conversation_id_param.as_deref(), conversation.conversation_id.as_deref()),
+            (config.parent_message_param.as_deref(), parent),
+        ]
+        .into_iter()
+        .filter_map(|(param, id)| param.zip(id))
+        .collect();
+        Some(params).filter(|params| !params.is_empty())
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Whether the settings refer to conversation state held by a provider
+fn has_state(conversation: &ConversationConfig) -> bool {
+    conversation.conversation_id.is_some()
+        || conversation.parent_message_id.is_some()
+        || conversation.branch_from.is_some()
+}
+
+/// System messages and the turns after the last assistant message
+fn new_turns(messages: &[Message]) -> Vec<Message> {
+    let start = messages.iter()
+        .rposition(|m| m.role == MessageRole::Assistant)
+        .map_or(0, |last| last + 1);
+    messages.iter()
+        .enumerate()
+        .filter(|(index, m)| *index >= start || m.role == MessageRole::System)
+        .map(|(_, m)| m.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_turns() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("Hi"),
+            Message::assistant("Hello"),
+            Message::user("Capital of France?"),
+        ];
+        let roles: Vec<_> = new_turns(&messages).iter().map(|m| m.role).collect();
+        assert_eq!(roles, vec![MessageRole::System, MessageRole::User]);
+
+        // A conversation without an assistant turn is all new
+        assert_eq!(new_turns(&messages[..2]).len(), 2);
+    }
+}
@@ -0,0 +1,163 @@
+//! JSON mode emulation for providers without a native response format
+//!
+//! Models whose `json_output.strategy` is `"system_prompt"` are asked for
+//! JSON through an instruction added to the system prompt, optionally with
+//! the assistant turn prefilled with `{`. The response side undoes the
+//! prefill and pulls the JSON document out of the model's reply.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use serde_json::{Map, Value};
+
+use super::rendering::{append_system_text, interpolate};
+use crate::types::{JsonOutputConfig, Message, MessageRole, ResponseFormat};
+
+/// Instruction used when the model does not declare one
+pub const DEFAULT_JSON_INSTRUCTION: &str =
+    "Respond only with a valid JSON object. Do not wrap it in code fences or add any other text.";
+
+/// Schema clause appended to the default instruction for JSON Schema formats
+const DEFAULT_SCHEMA_INSTRUCTION: &str = "The JSON object must conform to this JSON Schema:\n{{schema}}";
+
+/// Text the assistant turn is prefilled with
+pub const JSON_PREFILL: &str = "{";
+
+/// Whether the strategy emulates JSON mode through the system prompt
+pub fn emulates_json_mode(config: &JsonOutputConfig) -> bool {
+    !config.native_param && config.strategy == "system_prompt"
+}
+
+/// Build the system prompt instruction for a response format
+///
+/// Returns `None` for plain text formats, which need no emulation.
+pub fn json_instruction(format: &ResponseFormat, config: &JsonOutputConfig) -> Option<String> {
+    if matches!(format, ResponseFormat::Text) {
+        return None;
+    }
+    let schema = format.schema();
+
+    let mut vars = Map::new();
+    if let Some(schema) = schema {
+        vars.insert(
+            "schema".to_string(),
+            Value::String(serde_json::to_string_pretty(schema).unwrap_or_default()),
+        );
+    }
+
+    let instruction = match (&config.instruction, schema) {
+        (Some(template), _) => interpolate(template, &vars),
+        (None, Some(_)) => format!(
+            "{}\n\n{}",
+            DEFAULT_JSON_INSTRUCTION,
+            interpolate(DEFAULT_SCHEMA_INSTRUCTION, &vars)
+        ),
+        (None, None) => DEFAULT_JSON_INSTRUCTION.to_string(),
+    };
+    Some(instruction)
+}
+
+/// Add the JSON instruction to the conversation's system prompt
+///
+/// The instruction is appended to the first system message, or becomes a new
+/// leading system message; the message renderer then places it according to
+/// the model's `system_prompt_location`. With `prefill` the conversation ends
+/// with an assistant turn holding [`JSON_PREFILL`].
+pub fn apply_json_instruction(messages: &[Message], instruction: &str, prefill: bool) -> Vec<Message> {
+    let mut messages = messages.to_vec();
+    append_system_text(&mut messages, instruction);
+
+    if prefill && messages.last().is_none_or(|m| m.role != MessageRole::Assistant) {
+        messages.push(Message::assistant(JSON_PREFILL));
+    }
+
+    messages
+}
+
+/// Extract the JSON document from an emulated JSON mode reply
+///
+/// Restores the prefilled `{` when the reply continues after it, strips code
+/// fences and surrounding prose, and returns the JSON text with its parsed
+/// value, or `None` when no JSON document can be found.
+pub fn extract_json(content: &str, prefilled: bool) -> Option<(String, Value)> {
+    let mut text = content.trim().to_string();
+    if prefilled && !text.starts_with(JSON_PREFILL) {
+        text.insert_str(0, JSON_PREFILL);
+    }
+
+    if let Ok(value) = serde_json::from_str(&text) {
+        return Some((text, value));
+    }
+
+    let unfenced = strip_code_fence(&text).unwrap_or(&text);
+    let start = unfenced.find(['{', '['])?;
+    let close = if unfenced[start..].starts_with('{') { '}' } else { ']' };
+    let end = unfenced.rfind(close)?;
+    if end < start {
+        return None;
+    }
+
+    let candidate = unfenced[start..=end].to_string();
+    let value = serde_json::from_str(&candidate).ok()?;
+    Some((candidate, value))
+}
+
+/// Body of the first fenced code block, skipping its language tag
+fn strip_code_fence(text: &str) -> Option<&str> {
+    let open = text.find("```")?;
+    let body = &text[open + 3..];
+    let body = &body[body.find('\n')? + 1..];
+    let close = body.find("```").unwrap_or(body.len());
+    Some(body[..close].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_instruction() {
+        let messages = vec![Message::system("Be brief."), Message::user("List colors")];
+        let rendered = apply_json_instruction(&messages, "Reply in JSON.", true);
+
+        assert_eq!(rendered.len(), 3);
+        assert_eq!(rendered[0].content.text(), "Be brief.\n\nReply in JSON.");
+        assert_eq!(rendered[2].role, MessageRole::Assistant);
+        assert_eq!(rendered[2].content.text(), "{");
+
+        let rendered = apply_json_instruction(&messages[1..], "Reply in JSON.", false);
+        assert_eq!(rendered[0].role, MessageRole::System);
+        assert_eq!(rendered.len(), 2);
+    }
+
+    #[test]
+    fn test_schema_instruction() {
+        let config = JsonOutputConfig {
+            native_param: false,
+            strategy: "system_prompt".to_string(),
+            instruction: Some("Match {{schema}}".to_string()),
+            prefill: false,
+        };
+        let format = ResponseFormat::JsonSchema {
+            json_schema: json!({"name": "colors", "schema": {"type": "object"}}),
+            strict: None,
+        };
+
+        let instruction = json_instruction(&format, &config).unwrap();
+        assert_eq!(instruction, "Match {\n  \"type\": \"object\"\n}");
+        assert!(json_instruction(&ResponseFormat::Text, &config).is_none());
+    }
+
+    #[test]
+    fn test_extract_json() {
+        let (text, value) = extract_json("\"colors\": [\"red\"]}", true).unwrap();
+        assert_eq!(text, "{\"colors\": [\"red\"]}");
+        assert_eq!(value, json!({"colors": ["red"]}));
+
+        let reply = "Here you go:\n```json\n{\"ok\": true}\n```\nAnything else?";
+        assert_eq!(extract_json(reply, false).unwrap().1, json!({"ok": true}));
+
+        assert!(extract_json("I cannot help with that.", false).is_none());
+    }
+}
@@ -9,7 +9,7 @@
 use super::ast::*;
 use super::error::*;
 use super::filter::FilterExecutor;
-use super::selector::{create_selector_executor, SelectionIterator, SelectorExecutor};
+use super::selector::{create_selector_executor, SelectionIterator, SelectorExecutor, WildcardSelector};
 use crate::Result;
 use serde_json::Value;
 
@@ -108,8 +108,10 @@ impl Executor {
     ) -> Result<SelectionIterator<'a>> {
         match selector {
             CompiledSelector::Filter { filter } => {
+                // Filters test the children of the selected arrays and objects
+                let children = WildcardSelector.execute(inputs)?;
                 let filter_executor = FilterExecutor::new(filter.clone());
-                filter_executor.execute(inputs)
+                filter_executor.execute(children)
             }
             CompiledSelector::RecursiveDescent { .. } => {
                 // Recursive descent needs special depth tracking
@@ -173,6 +173,20 @@ mod tests {
         assert_eq!(results.len(), 3);
     }
 
+    #[test]
+    fn test_filter() {
+        let data = json!({
+            "content": [
+                {"type": "text", "text": "a"},
+                {"type": "tool_use", "name": "lookup"},
+                {"type": "text", "text": "b"}
+            ]
+        });
+
+        let results = select("$.content[?(@.type == 'text')].text", &data).unwrap();
+        assert_eq!(results, vec![&json!("a"), &json!("b")]);
+    }
+
     #[test]
     fn test_exists() {
         let data = json!({
@@ -142,6 +142,14 @@ impl<'a> Parser<'a> {
             return self.parse_filter_selector();
         }
 
+        // Check for wildcard ([*])
+        if self.current_char() == Some('*') {
+            self.advance();
+            self.skip_whitespace();
+            self.expect_char(']')?;
+            return Ok(Selector::Wildcard);
+        }
+
         // Check for quoted property
         if self.current_char() == Some('\'') || self.current_char() == Some('"') {
             let property = self.parse_quoted_string()?;
@@ -824,6 +832,13 @@ mod tests {
         assert!(matches!(expr.selectors[1], Selector::Wildcard));
     }
 
+    #[test]
+    fn test_parse_bracket_wildcard() {
+        let parser = Parser::new("$.books[*].title").unwrap();
+        let expr = parser.parse().unwrap();
+        assert!(matches!(expr.selectors[1], Selector::Wildcard));
+    }
+
     #[test]
     fn test_parse_recursive_descent() {
         let parser = Parser::new("$..author").unwrap();
@@ -285,7 +285,7 @@ impl<'a> JSONPathMapper<'a> {
     ///
     /// Sets a value at the specified JSONPath location, creating
     /// intermediate objects as needed.
-    fn set_value_at_path(&self, path: &str, value: Value, data: &mut Value) -> Result<()> {
+    pub(crate) fn set_value_at_path(&self, path: &str, value: Value, data: &mut Value) -> Result<()> {
         // For now, use simple dot notation parsing
         // In the future, this could support full JSONPath for target paths
         if path == "$" {
@@ -447,7 +447,7 @@ mod tests {
         Message, MessageRole, ProviderInfo, Endpoints, EndpointConfig,
         InputModes, ToolingConfig, JsonOutputConfig, Constraints,
         ConstraintLimits, Mappings, ResponseNormalization,
-        SyncNormalization, StreamNormalization,
+        SyncNormalization, ArgsEncoding, StreamNormalization,
     };
     use std::collections::HashMap;
 
@@ -456,9 +456,12 @@ mod tests {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test".to_string(),
+                content: "Test".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: None,
             tool_choice: None,
@@ -468,6 +471,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let mut path_mappings = HashMap::new();
@@ -514,12 +520,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: true,
                 strategy: "native".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: serde_json::json!({}),
             constraints: Constraints {
                 system_prompt_location: "first".to_string(),
@@ -529,7 +540,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: path_mappings,
@@ -540,6 +553,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish".to_string(),
                     finish_reason_map: HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -547,8 +580,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         };
 
         TranslationContext::new(prompt_spec, provider_spec, model_spec, StrictMode::Warn)
@@ -8,31 +8,57 @@
 
 pub mod builder;
 pub mod conflict;
+pub mod content;
 pub mod context;
+pub mod conversation;
+pub mod json_mode;
 pub mod jsonpath;
 pub mod lossiness;
 pub mod mapper;
+pub mod parameters;
 pub mod provider_loader;
+pub mod rag;
+pub mod rendering;
+pub mod reverse;
+pub mod size_limits;
 pub mod strictness;
+pub mod thinking;
+pub mod tools;
+pub mod top_level;
 pub mod transformer;
+pub mod truncation;
 pub mod validator;
 
 #[cfg(test)]
 pub mod provider_spec_tests;
 
 use crate::{
-    Error, MessageRole, PromptSpec, ProviderSpec, Result, StrictMode, TranslationMetadata,
-    TranslationResult,
+    Error, LossinessCode, LossinessReport, ModelSpec, PromptSpec, ProviderSpec, Result, StrictMode,
+    TranslationMetadata, TranslationResult,
 };
+use crate::types::sampling_number;
+use parameters::{new_fields, request_fields};
+use std::collections::BTreeSet;
 use std::time::Instant;
 use std::sync::{Arc, Mutex};
 
 pub use builder::{TranslationResultBuilder, BuilderState, BuilderError, ProviderRequestBuilder};
 pub use conflict::{ConflictResolver, FieldConflict, ResolutionStrategy, ConflictResolutionConfig};
+pub use content::{ContentBlockFormat, ContentRenderer};
+pub use rag::RagTranslator;
+pub use rendering::{MessageRenderer, SystemPromptLocation};
+pub use reverse::MessageParser;
+pub use size_limits::SizeLimits;
 pub use context::TranslationContext;
+pub use conversation::ConversationTranslator;
 pub use lossiness::LossinessTracker;
 pub use mapper::JSONPathMapper;
+pub use parameters::ParameterConstraints;
 pub use strictness::{StrictnessAction, StrictnessPolicy, PolicyResult};
+pub use thinking::ExtendedThinking;
+pub use tools::ToolRenderer;
+pub use top_level::TopLevelFields;
+pub use truncation::PromptTruncator;
 pub use transformer::{
     TransformationPipeline, TransformationRule, TransformationRuleBuilder,
     TransformationType, TransformationDirection, TransformationError,
@@ -40,6 +66,10 @@ pub use transformer::{
 };
 pub use validator::{PreValidator, ValidationError, ValidationSeverity, ValidationMode};
 
+/// Mapping source of the candidate count; models that map it generate
+/// several candidates natively
+pub const CANDIDATE_COUNT_PATH: &str = "$.sampling.candidate_count";
+
 /// Main translation function that converts a PromptSpec to provider-specific format
 ///
 /// This function is the primary public API for the translation engine. It takes
@@ -84,6 +114,9 @@ pub use validator::{PreValidator, ValidationError, ValidationSeverity, Validatio
 /// #   limits: None,
 /// #   media: None,
 /// #   advanced: None,
+/// #   rag: None,
+/// #   conversation: None,
+/// #   preferences: None,
 /// #   strict_mode: StrictMode::Warn,
 /// };
 ///
@@ -111,15 +144,7 @@ pub fn translate(
     let start_time = Instant::now();
 
     // Step 1: Find the model in the provider spec
-    let model_spec = provider_spec
-        .models
-        .iter()
-        .find(|m| m.id == model_id || m.aliases.as_ref().is_some_and(|a| a.contains(&model_id.to_string())))
-        .ok_or_else(|| Error::Validation {
-            field: "model_id".to_string(),
-            message: format!("Model '{}' not found in provider '{}'", model_id, provider_spec.provider.name),
-            expected: Some(format!("One of: {:?}", provider_spec.models.iter().map(|m| &m.id).collect::<Vec<_>>())),
-        })?;
+    let model_spec = find_model(provider_spec, model_id)?;
 
     // Step 2: Create translation context
     let context = TranslationContext::new(
@@ -142,113 +167,106 @@ pub fn translate(
     // Step 6: Create JSONPath mapper with lossiness tracking (issue #18)
     let mut mapper = JSONPathMapper::new(&context);
 
-    // Step 6.5: Apply constraint-based message reordering
-    let mut reordered_messages = prompt_spec.messages.clone();
-    if context.system_prompt_location() == "first_message" {
-        // Check if we need to relocate system messages to the beginning
-        let has_system = reordered_messages.iter().any(|m| m.role == MessageRole::System);
-        let system_not_first = reordered_messages.first()
-            .map(|m| m.role != MessageRole::System)
-            .unwrap_or(false);
-        
-        if has_system && system_not_first {
-            // Separate system and non-system messages
-            let mut system_messages = Vec::new();
-            let mut other_messages = Vec::new();
-            
-            for msg in reordered_messages {
-                if msg.role == MessageRole::System {
-                    system_messages.push(msg);
-                } else {
-                    other_messages.push(msg);
-                }
-            }
-            
-            // Track the count before moving
-            let system_count = system_messages.len();
-            
-            // Recombine with system messages first
-            reordered_messages = system_messages;
-            reordered_messages.extend(other_messages);
-            
-            // Track relocation with lossiness
-            let policy_result = strictness_policy.evaluate_field_relocation(
-                "messages",
-                "messages[0]",
-                serde_json::json!(system_count),
+    // Step 6.3: Leave out the turns a provider holding the conversation
+    // already has, or report the history replay
+    let conversation_translator = ConversationTranslator::new(&context, &strictness_policy, &lossiness_tracker);
+    let conversation_messages = conversation_translator.messages(&prompt_spec.messages)?;
+
+    // Step 6.35: Fit the caller's system prompt into the model's size
+    // limit, before grounding and JSON instructions add to it
+    let size_limits = SizeLimits::new(&context, &strictness_policy, &lossiness_tracker);
+    let conversation_messages = size_limits.system_prompt(conversation_messages)?;
+
+    // Step 6.4: Ground the conversation in the RAG documents for models
+    // without a document parameter
+    let rag_translator = RagTranslator::new(&context, &strictness_policy, &lossiness_tracker);
+    let grounded_messages = rag_translator.ground(&conversation_messages)?;
+
+    // Step 6.5: Drop the oldest turns of a prompt over its token budget
+    let truncated_messages = PromptTruncator::new(&context, &strictness_policy, &lossiness_tracker).truncate(&grounded_messages)?;
+
+    // Extended thinking rejects some sampling fields, forced tool use and
+    // a prefilled assistant turn
+    let extended_thinking = ExtendedThinking::new(&context, &strictness_policy, &lossiness_tracker);
+
+    // Step 6.6: Emulate JSON mode through the system prompt for models
+    // without a native response format parameter
+    let json_instruction = prompt_spec.response_format.as_ref()
+        .filter(|_| json_mode::emulates_json_mode(&model_spec.json_output))
+        .and_then(|format| json_mode::json_instruction(format, &model_spec.json_output));
+    let json_mode = json_instruction.is_some();
+    let messages = match json_instruction {
+        Some(ref instruction) => {
+            let policy_result = strictness_policy.evaluate_feature_emulation(
+                "response_format",
+                "JSON mode",
+                "system prompt instruction",
+                Some(serde_json::json!(prompt_spec.response_format)),
             );
-            
-            // Add lossiness item if provided
+
             if let Some(lossiness_item) = policy_result.lossiness_item {
                 if let Ok(mut tracker) = lossiness_tracker.lock() {
                     tracker.add_item(lossiness_item);
                 }
             }
+
+            match policy_result.action {
+                StrictnessAction::Fail { error } => return Err(error),
+                StrictnessAction::Warn { message } => {
+                    log::warn!("{}", message);
+                }
+                StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => {}
+            }
+
+            let prefill = model_spec.json_output.prefill && !extended_thinking.is_enabled();
+            json_mode::apply_json_instruction(&truncated_messages, instruction, prefill)
         }
+        None => truncated_messages,
+    };
+
+    // Step 7: Build base provider request structure, rendering messages
+    // according to the model's message rendering template
+    let message_renderer = MessageRenderer::new(&context, &strictness_policy, &lossiness_tracker);
+    let mut provider_request = serde_json::json!({ "model": model_id });
+    if let serde_json::Value::Object(ref mut request_obj) = provider_request {
+        request_obj.extend(message_renderer.render(&messages)?);
     }
+    let rendered_fields: Vec<String> = provider_request.as_object()
+        .map(|request_obj| request_obj.keys().cloned().collect())
+        .unwrap_or_default();
 
-    // Step 7: Build base provider request structure
-    // This is a placeholder implementation - the actual mapping logic
-    // will be implemented in subsequent issues
-    let mut provider_request = serde_json::json!({
-        "model": model_id,
-        "messages": reordered_messages.iter().map(|msg| {
-            serde_json::json!({
-                "role": msg.role,
-                "content": msg.content,
-            })
-        }).collect::<Vec<_>>(),
-    });
+    // Generation parameters are the fields written from the prompt's
+    // sampling, limits and advanced settings in steps 7.5, 10, 11 and 12.5
+    let mut generation_fields = BTreeSet::new();
 
     // Step 7.5: Apply JSONPath mappings with lossiness tracking
     // This demonstrates integration of mapper with tracking
+    let existing_fields = request_fields(&provider_request);
     let prompt_as_json = serde_json::to_value(prompt_spec).unwrap_or_default();
     if let Ok(mapped_fields) = mapper.apply_mappings_with_tracker(&prompt_as_json, Some(&lossiness_tracker)) {
-        // Merge mapped fields into provider request
+        // Merge mapped fields into provider request, keeping the rendered messages
         if let (serde_json::Value::Object(ref mut request_obj), serde_json::Value::Object(mapped_obj)) = 
             (&mut provider_request, mapped_fields) {
             for (key, value) in mapped_obj {
-                request_obj.insert(key, value);
+                // Messages were already rendered in step 7
+                if key == "messages" {
+                    continue;
+                }
+                request_obj.entry(key).or_insert(value);
             }
         }
     }
+    generation_fields.extend(new_fields(&existing_fields, &provider_request));
 
-    // Step 8: Apply field transformations using the transformation pipeline
-    let mut transformation_pipeline = TransformationPipeline::new();
-    
-    // Build transformation pipeline from provider mappings
-    // In a real implementation, this would be configured from the provider spec
-    // For now, create some example transformations based on common provider needs
-    
-    // Add temperature scaling if needed (example: OpenAI uses 0-2, Anthropic uses 0-1)
-    if context.provider_name() == "anthropic" {
-        if let Ok(temp_rule) = TransformationRuleBuilder::new("temperature_scale", "$.temperature")
-            .transformation(TransformationType::UnitConversion {
-                from_unit: "openai_range".to_string(),
-                to_unit: "anthropic_range".to_string(),
-                formula: ConversionFormula::Linear { scale: 0.5, offset: 0.0 },
-            })
-            .direction(TransformationDirection::Forward)
-            .priority(10)
-            .optional()
-            .build() {
-            transformation_pipeline = transformation_pipeline.add_rule(temp_rule);
-        }
-    }
-    
-    // Apply transformations to the provider request
-    provider_request = transformation_pipeline
-        .transform(&provider_request, TransformationDirection::Forward, &context)
-        .unwrap_or(provider_request); // Fall back to original on error
-
-    // Step 9: Handle tools if present
+    // Step 9: Handle tools if present, rendering them and the tool choice
+    // in the model's native shape
     if let Some(ref tools) = prompt_spec.tools {
         if model_spec.tooling.tools_supported {
-            provider_request["tools"] = serde_json::json!(tools);
-            
-            if let Some(ref tool_choice) = prompt_spec.tool_choice {
-                provider_request["tool_choice"] = serde_json::json!(tool_choice);
-            }
+            ToolRenderer::new(&context, &strictness_policy, &lossiness_tracker).apply(
+                &mut provider_request,
+                &size_limits.tools(tools)?,
+                extended_thinking.tool_choice(prompt_spec.tool_choice.as_ref())?.as_ref(),
+            )?;
         } else {
             // Track field dropped due to provider limitations
             mapper.track_field_dropped_due_to_provider(
@@ -285,200 +303,110 @@ pub fn translate(
         }
     }
 
-    // Step 10: Apply sampling parameters
+    // Step 9.5: Pass RAG documents, connectors and search queries through
+    // the model's declared parameters
+    rag_translator.apply(&mut provider_request)?;
+
+    // Step 9.6: Continue the conversation through the model's declared
+    // conversation parameters
+    conversation_translator.apply(&mut provider_request)?;
+
+    // Step 10: Apply sampling parameters; ranges are checked against the
+    // model's declared parameters once the request is assembled
+    let existing_fields = request_fields(&provider_request);
     if let Some(ref sampling) = prompt_spec.sampling {
         if let Some(temp) = sampling.temperature {
-            // Use strictness policy to validate temperature range
-            let policy_result = strictness_policy.evaluate_value_clamping(
-                "temperature",
-                serde_json::json!(temp),
-                0.0,
-                2.0,
-                context.provider_name(),
-            );
-            
-            match policy_result.action {
-                StrictnessAction::Coerce { adjusted_value, .. } => {
-                    provider_request["temperature"] = adjusted_value;
-                }
-                StrictnessAction::Proceed => {
-                    provider_request["temperature"] = serde_json::json!(temp);
-                }
-                StrictnessAction::Warn { message } => {
-                    log::warn!("{}", message);
-                    provider_request["temperature"] = serde_json::json!(temp);
-                }
-                StrictnessAction::Fail { error } => return Err(error),
-            }
-            
-            if let Some(lossiness_item) = policy_result.lossiness_item {
-                if let Ok(mut tracker) = lossiness_tracker.lock() {
-                    tracker.add_item(lossiness_item);
-                }
-            }
+            provider_request["temperature"] = sampling_number(temp);
         }
         if let Some(top_p) = sampling.top_p {
-            // Use strictness policy to validate top_p range
-            let policy_result = strictness_policy.evaluate_value_clamping(
-                "top_p",
-                serde_json::json!(top_p),
-                0.0,
-                1.0,
-                context.provider_name(),
-            );
-            
-            match policy_result.action {
-                StrictnessAction::Coerce { adjusted_value, .. } => {
-                    provider_request["top_p"] = adjusted_value;
-                }
-                StrictnessAction::Proceed => {
-                    provider_request["top_p"] = serde_json::json!(top_p);
-                }
-                StrictnessAction::Warn { message } => {
-                    log::warn!("{}", message);
-                    provider_request["top_p"] = serde_json::json!(top_p);
-                }
-                StrictnessAction::Fail { error } => return Err(error),
-            }
-            
-            if let Some(lossiness_item) = policy_result.lossiness_item {
-                if let Ok(mut tracker) = lossiness_tracker.lock() {
-                    tracker.add_item(lossiness_item);
-                }
-            }
+            provider_request["top_p"] = sampling_number(top_p);
         }
         if let Some(top_k) = sampling.top_k {
             provider_request["top_k"] = serde_json::json!(top_k);
         }
         if let Some(freq_penalty) = sampling.frequency_penalty {
-            // Use strictness policy to validate frequency_penalty range
-            let policy_result = strictness_policy.evaluate_value_clamping(
-                "frequency_penalty",
-                serde_json::json!(freq_penalty),
-                -2.0,
-                2.0,
-                context.provider_name(),
-            );
-            
-            match policy_result.action {
-                StrictnessAction::Coerce { adjusted_value, .. } => {
-                    provider_request["frequency_penalty"] = adjusted_value;
-                }
-                StrictnessAction::Proceed => {
-                    provider_request["frequency_penalty"] = serde_json::json!(freq_penalty);
-                }
-                StrictnessAction::Warn { message } => {
-                    log::warn!("{}", message);
-                    provider_request["frequency_penalty"] = serde_json::json!(freq_penalty);
+            provider_request["frequency_penalty"] = sampling_number(freq_penalty);
+        }
+        if let Some(pres_penalty) = sampling.presence_penalty {
+            provider_request["presence_penalty"] = sampling_number(pres_penalty);
+        }
+
+        // The candidate count only reaches the request through the model's
+        // mappings; without one, candidates come from repeated calls
+        if let Some(count) = sampling.candidate_count.filter(|count| *count > 1) {
+            if !model_spec.mappings.paths.contains_key(CANDIDATE_COUNT_PATH) {
+                if let Ok(mut tracker) = lossiness_tracker.lock() {
+                    tracker.add_emulated(
+                        "sampling.candidate_count",
+                        &format!(
+                            "{} has no native candidate count; {} candidates are gathered through repeated calls",
+                            context.provider_name(),
+                            count
+                        ),
+                        Some(serde_json::json!(count)),
+                    );
                 }
-                StrictnessAction::Fail { error } => return Err(error),
             }
-            
+        }
+
+        // Stop sequences, log probabilities and logit bias also reach the
+        // request only through the model's mappings, whose target names
+        // differ between providers; a model without a mapping does not
+        // support the parameter
+        let mapped_only = [
+            ("stop", sampling.stop.as_ref().map(|stop| serde_json::json!(stop))),
+            ("logprobs", sampling.logprobs.filter(|logprobs| *logprobs).map(serde_json::Value::from)),
+            ("top_logprobs", sampling.top_logprobs.map(serde_json::Value::from)),
+            ("logit_bias", sampling.logit_bias.as_ref().map(|bias| serde_json::json!(bias))),
+        ];
+        for (name, value) in mapped_only {
+            let Some(value) = value else {
+                continue;
+            };
+            if model_spec.mappings.paths.contains_key(&format!("$.sampling.{}", name)) {
+                continue;
+            }
+
+            let policy_result = strictness_policy.evaluate_unsupported_feature(
+                &format!("sampling.{}", name),
+                name,
+                Some(value),
+            );
             if let Some(lossiness_item) = policy_result.lossiness_item {
                 if let Ok(mut tracker) = lossiness_tracker.lock() {
                     tracker.add_item(lossiness_item);
                 }
             }
-        }
-        if let Some(pres_penalty) = sampling.presence_penalty {
-            // Use strictness policy to validate presence_penalty range
-            let policy_result = strictness_policy.evaluate_value_clamping(
-                "presence_penalty",
-                serde_json::json!(pres_penalty),
-                -2.0,
-                2.0,
-                context.provider_name(),
-            );
-            
             match policy_result.action {
-                StrictnessAction::Coerce { adjusted_value, .. } => {
-                    provider_request["presence_penalty"] = adjusted_value;
-                }
-                StrictnessAction::Proceed => {
-                    provider_request["presence_penalty"] = serde_json::json!(pres_penalty);
-                }
+                StrictnessAction::Fail { error } => return Err(error),
                 StrictnessAction::Warn { message } => {
                     log::warn!("{}", message);
-                    provider_request["presence_penalty"] = serde_json::json!(pres_penalty);
-                }
-                StrictnessAction::Fail { error } => return Err(error),
-            }
-            
-            if let Some(lossiness_item) = policy_result.lossiness_item {
-                if let Ok(mut tracker) = lossiness_tracker.lock() {
-                    tracker.add_item(lossiness_item);
                 }
+                StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => {}
             }
         }
     }
 
+    // Step 10.5: Leave out the sampling fields extended thinking rejects
+    extended_thinking.sampling(&mut provider_request)?;
+
     // Step 11: Apply limits
     if let Some(ref limits) = prompt_spec.limits {
         if let Some(max_tokens) = limits.max_output_tokens {
-            // Use strictness policy to validate max_output_tokens range
-            let policy_result = strictness_policy.evaluate_value_clamping(
-                "max_output_tokens",
-                serde_json::json!(max_tokens),
-                1.0,
-                1000000.0,
-                context.provider_name(),
-            );
-            
-            match policy_result.action {
-                StrictnessAction::Coerce { adjusted_value, .. } => {
-                    provider_request["max_tokens"] = adjusted_value;
-                }
-                StrictnessAction::Proceed => {
-                    provider_request["max_tokens"] = serde_json::json!(max_tokens);
-                }
-                StrictnessAction::Warn { message } => {
-                    log::warn!("{}", message);
-                    provider_request["max_tokens"] = serde_json::json!(max_tokens);
-                }
-                StrictnessAction::Fail { error } => return Err(error),
-            }
-            
-            if let Some(lossiness_item) = policy_result.lossiness_item {
-                if let Ok(mut tracker) = lossiness_tracker.lock() {
-                    tracker.add_item(lossiness_item);
-                }
-            }
+            provider_request["max_tokens"] = serde_json::json!(max_tokens);
         }
     }
+    generation_fields.extend(new_fields(&existing_fields, &provider_request));
 
     // Step 12: Handle response format
     if let Some(ref format) = prompt_spec.response_format {
         if model_spec.json_output.native_param {
             provider_request["response_format"] = serde_json::json!(format);
-        } else if model_spec.json_output.strategy == "system_prompt" {
-            // Use strictness policy for feature emulation
-            let policy_result = strictness_policy.evaluate_feature_emulation(
-                "response_format",
-                "JSON mode",
-                "system prompt modification",
-                Some(serde_json::json!(format)),
-            );
-            
-            // Add lossiness item if provided
-            if let Some(lossiness_item) = policy_result.lossiness_item {
-                if let Ok(mut tracker) = lossiness_tracker.lock() {
-                    tracker.add_item(lossiness_item);
-                }
-            }
-            
-            // Handle the policy action
-            match policy_result.action {
-                StrictnessAction::Proceed => {
-                    // Emulate via system prompt - actual implementation would modify system prompt
-                }
-                StrictnessAction::Warn { message } => {
-                    log::warn!("{}", message);
-                    // Still proceed with emulation
-                }
-                StrictnessAction::Fail { error } => return Err(error),
-                StrictnessAction::Coerce { .. } => {
-                    // Proceed with emulation
-                }
+        } else if json_mode::emulates_json_mode(&model_spec.json_output) {
+            // Emulated through the system prompt in step 6.6; the provider
+            // has no parameter for it
+            if let Some(request_obj) = provider_request.as_object_mut() {
+                request_obj.remove("response_format");
             }
         } else {
             // Response format not supported at all
@@ -507,12 +435,23 @@ pub fn translate(
     }
 
     // Step 12.5: Handle advanced parameters for latest models
+    let existing_fields = request_fields(&provider_request);
     if let Some(ref advanced) = prompt_spec.advanced {
-        // Thinking mode (Claude Opus 4.1)
-        if let Some(thinking) = advanced.thinking {
-            provider_request["thinking"] = serde_json::json!(thinking);
-            
-            if thinking {
+        // Thinking mode (Claude Opus 4.1); thinking is off unless switched
+        // on, so a disabled switch is not sent. Models declaring `thinking`
+        // as an object take `{"type": "enabled", "budget_tokens": N}`, the
+        // budget defaulting to the declared one
+        if advanced.thinking == Some(true) {
+            if extended_thinking.is_enabled() {
+                let budget = advanced.min_thinking_tokens.map(serde_json::Value::from).or_else(|| {
+                    model_spec.parameters.pointer("/thinking/properties/budget_tokens/default").cloned()
+                });
+                provider_request["thinking"] = serde_json::json!({ "type": "enabled" });
+                if let Some(budget) = budget {
+                    provider_request["thinking"]["budget_tokens"] = budget;
+                }
+            } else {
+                provider_request["thinking"] = serde_json::json!(true);
                 if let Some(min_thinking_tokens) = advanced.min_thinking_tokens {
                     provider_request["min_thinking_tokens"] = serde_json::json!(min_thinking_tokens);
                 }
@@ -544,6 +483,30 @@ pub fn translate(
             provider_request["verbosity"] = serde_json::json!(verbosity);
         }
     }
+    generation_fields.extend(new_fields(&existing_fields, &provider_request));
+
+    // Step 12.75: Apply the model's declared field transformations to the
+    // assembled request
+    if !model_spec.transformations.is_empty() {
+        let mut transformation_pipeline = TransformationPipeline::new()
+            .with_lossiness_tracker(lossiness_tracker.clone())
+            .add_rules(model_spec.transformations.iter().cloned());
+        provider_request = transformation_pipeline.transform(
+            &provider_request,
+            TransformationDirection::Forward,
+            &context,
+        )?;
+    }
+
+    // Step 12.9: Clamp, drop and default generation parameters according to
+    // the model's declared parameters
+    ParameterConstraints::new(&context, generation_fields, &strictness_policy, &lossiness_tracker)
+        .apply(&mut provider_request)?;
+
+    // Step 12.95: Drop the fields the model does not accept when it forbids
+    // unknown top-level fields
+    TopLevelFields::new(&context, &rendered_fields, &strictness_policy, &lossiness_tracker)
+        .apply(&mut provider_request)?;
 
     // Step 13: Apply strictness policy evaluation
     // Check if we should proceed based on accumulated lossiness
@@ -574,6 +537,7 @@ pub fn translate(
         timestamp: chrono::Utc::now().to_rfc3339(),
         duration_ms: Some(duration_ms),
         strict_mode,
+        json_mode,
     };
 
     // Build final result with lossiness tracking
@@ -608,10 +572,218 @@ pub fn translate(
     Ok(result)
 }
 
+/// Reverse translation from a provider-native request back to a PromptSpec
+///
+/// Reads a request in the provider's native format, such as a logged OpenAI
+/// or Anthropic call, into a uniform `PromptSpec` that can be replayed on
+/// other providers. The model's transformation rules are undone (forward
+/// rules through their inverse), `mappings.paths` are read from their target
+/// back to their source, and the conversation is parsed with the model's
+/// message rendering template.
+///
+/// # Arguments
+///
+/// * `provider_request` - The provider-native request JSON
+/// * `provider_spec` - The provider configuration and mapping rules
+/// * `model_id` - The model the request was made for
+///
+/// # Returns
+///
+/// The recovered `PromptSpec` and a `LossinessReport` listing request
+/// fields that have no uniform equivalent and were dropped
+///
+/// # Errors
+///
+/// Returns an error if the model_id is not found in the provider spec, the
+/// request is not a JSON object or a declared transformation fails
+pub fn translate_reverse(
+    provider_request: &serde_json::Value,
+    provider_spec: &ProviderSpec,
+    model_id: &str,
+) -> Result<(PromptSpec, LossinessReport)> {
+    // Step 1: Find the model in the provider spec
+    let model_spec = find_model(provider_spec, model_id)?;
+
+    let context = TranslationContext::new(
+        PromptSpec::default(),
+        provider_spec.clone(),
+        model_spec.clone(),
+        StrictMode::Warn,
+    );
+    let lossiness_tracker = Arc::new(Mutex::new(LossinessTracker::new(StrictMode::Warn)));
+
+    // Step 2: Undo the model's declared field transformations
+    let mut request = provider_request.clone();
+    if !model_spec.transformations.is_empty() {
+        let rules = model_spec.transformations.iter().filter_map(|rule| match rule.direction {
+            TransformationDirection::Forward => rule.inverse(),
+            TransformationDirection::Reverse | TransformationDirection::Bidirectional => Some(rule.clone()),
+        });
+        let mut transformation_pipeline = TransformationPipeline::new()
+            .with_lossiness_tracker(lossiness_tracker.clone())
+            .add_rules(rules);
+        request = transformation_pipeline.transform(&request, TransformationDirection::Reverse, &context)?;
+    }
+    let serde_json::Value::Object(mut request) = request else {
+        return Err(Error::Validation {
+            field: "provider_request".to_string(),
+            message: "Provider request must be a JSON object".to_string(),
+            expected: Some("JSON object".to_string()),
+        });
+    };
+
+    // Step 3: Parse the conversation with the model's rendering template
+    let messages = MessageParser::new(&context, &lossiness_tracker).parse(&mut request);
+
+    // Step 4: Read parameters through the inverted path mappings, then
+    // through the fields translate() sets directly
+    let mut mapper = JSONPathMapper::new(&context);
+    let mut mapped_paths: Vec<_> = model_spec.mappings.paths.iter()
+        .map(|(source, target)| (source.as_str(), target.as_str()))
+        .collect();
+    mapped_paths.sort();
+    let mut fields = reverse::read_fields(
+        &mut mapper,
+        &mut request,
+        mapped_paths.into_iter().chain(reverse::DIRECT_FIELDS.iter().copied()),
+    )?;
+    fields.insert("messages".to_string(), serde_json::json!([]));
+    let mut prompt_spec: PromptSpec = serde_json::from_value(serde_json::Value::Object(fields))
+        .map_err(|e| Error::Json {
+            message: format!("Failed to build PromptSpec from provider request: {}", e),
+            source: e,
+        })?;
+    prompt_spec.messages = messages;
+
+    // Step 5: Tools, tool choice and response format
+    let mut dropped = Vec::new();
+    if let Some(tools) = request.remove("tools") {
+        match tools.as_array() {
+            Some(definitions) => {
+                let (tools, unknown) = reverse::parse_tools(definitions);
+                dropped.extend(unknown.into_iter().map(|index| (format!("tools[{}]", index), definitions[index].clone())));
+                prompt_spec.tools = Some(tools).filter(|tools| !tools.is_empty());
+            }
+            None => dropped.push(("tools".to_string(), tools)),
+        }
+    }
+    if let Some(tool_choice) = request.remove("tool_choice") {
+        match reverse::parse_tool_choice(&tool_choice) {
+            Some(choice) => prompt_spec.tool_choice = Some(choice),
+            None => dropped.push(("tool_choice".to_string(), tool_choice)),
+        }
+    }
+    if let Some(format) = request.remove("response_format") {
+        match reverse::parse_response_format(&format) {
+            Some(format) => prompt_spec.response_format = Some(format),
+            None => dropped.push(("response_format".to_string(), format)),
+        }
+    }
+
+    // Step 6: Report what is left; the model name, provider flags and the
+    // stream switch are not part of the prompt
+    request.remove("model");
+    request.remove("stream");
+    for flag in model_spec.mappings.flags.keys() {
+        request.remove(flag);
+    }
+    dropped.extend(request);
+
+    let mut tracker = Arc::try_unwrap(lossiness_tracker)
+        .map_err(|_| Error::Translation {
+            message: "Failed to access lossiness tracker".to_string(),
+            context: None,
+        })?
+        .into_inner()
+        .map_err(|_| Error::Translation {
+            message: "Failed to access lossiness tracker".to_string(),
+            context: None,
+        })?;
+    for (path, value) in dropped {
+        tracker.add_dropped(&path, "Request field has no PromptSpec equivalent", Some(value));
+    }
+
+    Ok((prompt_spec, tracker.build_report()))
+}
+
+/// Convert a request from one provider's native format to another's
+///
+/// Chains [`translate_reverse`] on the source provider with [`translate`]
+/// on the target provider, so a saved OpenAI request can be replayed against
+/// Anthropic (or any other pair of provider specs) without hand-writing the
+/// intermediate `PromptSpec`.
+///
+/// # Arguments
+///
+/// * `request_json` - The request in the source provider's native format
+/// * `from` - The source provider spec and the model the request was made for
+/// * `to` - The target provider spec and model to convert the request to
+/// * `strict_mode` - The strictness policy applied to both hops
+///
+/// # Returns
+///
+/// A `TranslationResult` holding the target provider's request and a
+/// lossiness report that merges the items of both hops, source-side drops
+/// first
+///
+/// # Errors
+///
+/// Returns an error if either model is not found, either hop fails, or
+/// `strict_mode` is Strict and a source request field would be dropped
+pub fn convert(
+    request_json: &serde_json::Value,
+    from: (&ProviderSpec, &str),
+    to: (&ProviderSpec, &str),
+    strict_mode: StrictMode,
+) -> Result<TranslationResult> {
+    let (source_spec, source_model) = from;
+    let (target_spec, target_model) = to;
+
+    // Step 1: Read the source request back into a PromptSpec
+    let (mut prompt_spec, source_lossiness) = translate_reverse(request_json, source_spec, source_model)?;
+    if strict_mode == StrictMode::Strict {
+        if let Some(item) = source_lossiness.items.iter().find(|item| item.code == LossinessCode::Drop) {
+            return Err(Error::Translation {
+                message: format!(
+                    "Strict mode: request field '{}' has no equivalent outside provider '{}'",
+                    item.path, source_spec.provider.name
+                ),
+                context: Some(item.message.clone()),
+            });
+        }
+    }
+    prompt_spec.strict_mode = strict_mode;
+
+    // Step 2: Render it for the target provider
+    let mut result = translate(&prompt_spec, target_spec, target_model, strict_mode)?;
+
+    // Step 3: Merge the lossiness of both hops
+    let mut tracker = LossinessTracker::new(strict_mode);
+    for item in source_lossiness.items.into_iter().chain(std::mem::take(&mut result.lossiness.items)) {
+        tracker.add_item(item);
+    }
+    result.lossiness = tracker.build_report();
+
+    Ok(result)
+}
+
+/// Find a model in the provider spec by ID or alias
+fn find_model<'a>(provider_spec: &'a ProviderSpec, model_id: &str) -> Result<&'a ModelSpec> {
+    provider_spec
+        .models
+        .iter()
+        .find(|m| m.id == model_id || m.aliases.as_ref().is_some_and(|a| a.contains(&model_id.to_string())))
+        .ok_or_else(|| Error::Validation {
+            field: "model_id".to_string(),
+            message: format!("Model '{}' not found in provider '{}'", model_id, provider_spec.provider.name),
+            expected: Some(format!("One of: {:?}", provider_spec.models.iter().map(|m| &m.id).collect::<Vec<_>>())),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Message, MessageRole, ProviderInfo, ModelSpec, Endpoints, EndpointConfig, InputModes, ToolingConfig, JsonOutputConfig};
+    use crate::{Message, MessageRole, ProviderInfo, ModelSpec, Endpoints, EndpointConfig, InputModes, ToolingConfig, JsonOutputConfig, Limits, Preferences, TruncationMode, Tool};
     use std::collections::HashMap;
 
     fn create_test_prompt() -> PromptSpec {
@@ -620,15 +792,21 @@ mod tests {
             messages: vec![
                 Message {
                     role: MessageRole::System,
-                    content: "You are a helpful assistant.".to_string(),
+                    content: "You are a helpful assistant.".into(),
                     name: None,
                     metadata: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    reasoning: None,
                 },
                 Message {
                     role: MessageRole::User,
-                    content: "Hello!".to_string(),
+                    content: "Hello!".into(),
                     name: None,
                     metadata: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    reasoning: None,
                 },
             ],
             tools: None,
@@ -639,6 +817,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         }
     }
 
@@ -681,12 +862,17 @@ mod tests {
                         parallel_tool_calls_default: true,
                         can_disable_parallel_tool_calls: false,
                         disable_switch: None,
+                        rendering: None,
                     },
                     json_output: JsonOutputConfig {
                         native_param: true,
                         strategy: "native".to_string(),
+                        instruction: None,
+                        prefill: false,
                     },
                     capabilities: None,
+                    transformations: Vec::new(),
+                    message_rendering: None,
                     parameters: serde_json::json!({}),
                     constraints: crate::Constraints {
                         system_prompt_location: "first".to_string(),
@@ -696,7 +882,9 @@ mod tests {
                         limits: crate::ConstraintLimits {
                             max_tool_schema_bytes: 100000,
                             max_system_prompt_bytes: 10000,
+                            context_window: None,
                         },
+                        extensions: None,
                     },
                     mappings: crate::Mappings {
                         paths: HashMap::new(),
@@ -707,6 +895,26 @@ mod tests {
                             content_path: "choices[0].message.content".to_string(),
                             finish_reason_path: "choices[0].finish_reason".to_string(),
                             finish_reason_map: HashMap::new(),
+                            usage_path: None,
+                            usage: None,
+                            tool_calls_path: None,
+                            tool_name_path: None,
+                            tool_args_path: None,
+                            tool_id_path: None,
+                            args_encoding: crate::ArgsEncoding::default(),
+                            reasoning_path: None,
+                            reasoning_text_path: None,
+                            reasoning_signature_path: None,
+                            reasoning_redacted_path: None,
+                            choices_path: None,
+                            logprobs_path: None,
+                            logprob_token_path: None,
+                            logprob_value_path: None,
+                            top_logprobs_path: None,
+                            citations_path: None,
+                            citation_document_path: None,
+                            citation_text_path: None,
+                            response_id_path: None,
                         },
                         stream: crate::StreamNormalization {
                             protocol: "sse".to_string(),
@@ -714,8 +922,10 @@ mod tests {
                                 type_path: "object".to_string(),
                                 routes: vec![],
                             },
+                            usage: None,
                         },
                     },
+                    extensions: None,
                 },
             ],
         }
@@ -828,6 +1038,11 @@ mod tests {
             top_k: Some(40),  // This conflicts with temperature
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
@@ -851,4 +1066,930 @@ mod tests {
             .collect();
         assert!(!lossiness_items.is_empty(), "Should have lossiness for dropped top_k field");
     }
+
+    #[test]
+    fn test_translate_content_parts() {
+        use crate::ContentPart;
+
+        let mut prompt = create_test_prompt();
+        prompt.messages[1].content = vec![
+            ContentPart::text("What is in this image?"),
+            ContentPart::image_base64("aGVsbG8=", "image/png"),
+        ].into();
+
+        let mut provider = create_test_provider();
+        provider.models[0].input_modes.images = true;
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let content = &result.provider_request_json["messages"][1]["content"];
+        assert_eq!(content[0], serde_json::json!({"type": "text", "text": "What is in this image?"}));
+        assert_eq!(content[1]["type"], "image_url");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,aGVsbG8=");
+
+        // Plain string content is passed through unchanged
+        assert_eq!(result.provider_request_json["messages"][0]["content"], "You are a helpful assistant.");
+
+        // Anthropic uses image blocks with a typed source
+        provider.provider.name = "anthropic".to_string();
+        provider.models[0].message_rendering = Some(crate::MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let image = &result.provider_request_json["messages"][1]["content"][1];
+        assert_eq!(image["type"], "image");
+        assert_eq!(image["source"], serde_json::json!({"type": "base64", "media_type": "image/png", "data": "aGVsbG8="}));
+    }
+
+    #[test]
+    fn test_translate_drops_unsupported_images() {
+        use crate::ContentPart;
+
+        let mut prompt = create_test_prompt();
+        prompt.messages[1].content = vec![
+            ContentPart::text("Describe this"),
+            ContentPart::image_url("https://example.com/cat.png"),
+        ].into();
+        let provider = create_test_provider();
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let content = result.provider_request_json["messages"][1]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+
+        let item = result.lossiness.items.iter()
+            .find(|item| item.path == "messages[1].content[1]")
+            .expect("dropped image should be tracked");
+        assert_eq!(item.code, crate::LossinessCode::Drop);
+
+        // Strict mode refuses to drop the image
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+    }
+
+    fn create_tool_conversation() -> PromptSpec {
+        let mut prompt = create_test_prompt();
+        prompt.messages.push(Message::assistant("").with_tool_calls(vec![crate::ToolCall {
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({"city": "Paris"}),
+            id: Some("call_1".to_string()),
+        }]));
+        prompt.messages.push(Message::tool("call_1", "sunny, 22C"));
+        prompt
+    }
+
+    #[test]
+    fn test_translate_tool_turns() {
+        let prompt = create_tool_conversation();
+        let mut provider = create_test_provider();
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        let messages = &result.provider_request_json["messages"];
+        assert_eq!(messages[2]["content"], serde_json::Value::Null);
+        assert_eq!(messages[2]["tool_calls"][0], serde_json::json!({
+            "id": "call_1",
+            "type": "function",
+            "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"},
+        }));
+        assert_eq!(messages[3], serde_json::json!({
+            "role": "tool",
+            "tool_call_id": "call_1",
+            "content": "sunny, 22C",
+        }));
+
+        // Anthropic carries tool turns as content blocks
+        provider.provider.name = "anthropic".to_string();
+        provider.models[0].message_rendering = Some(crate::MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        let messages = &result.provider_request_json["messages"];
+        assert_eq!(messages[2]["content"], serde_json::json!([
+            {"type": "tool_use", "id": "call_1", "name": "get_weather", "input": {"city": "Paris"}},
+        ]));
+        assert_eq!(messages[3]["role"], "user");
+        assert_eq!(messages[3]["content"][0], serde_json::json!({
+            "type": "tool_result",
+            "tool_use_id": "call_1",
+            "content": "sunny, 22C",
+        }));
+    }
+
+    #[test]
+    fn test_translate_invalid_tool_call_arguments() {
+        let mut prompt = create_tool_conversation();
+        prompt.messages[2].tool_calls.as_mut().unwrap()[0].arguments = serde_json::json!("{\"city\": ");
+        let mut provider = create_test_provider();
+
+        // A JSON string template sends the arguments as they are
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        assert_eq!(result.provider_request_json["messages"][2]["tool_calls"][0]["function"]["arguments"], "{\"city\": ");
+
+        // An object template cannot, so they are replaced with an empty object
+        provider.models[0].message_rendering = Some(crate::MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        });
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["messages"][2]["content"][0]["input"], serde_json::json!({}));
+        let item = result.lossiness.items.iter()
+            .find(|item| item.path == "messages[2].tool_calls[0].arguments")
+            .expect("replaced arguments should be reported");
+        assert_eq!(item.code, crate::LossinessCode::Drop);
+        assert_eq!(item.before, Some(serde_json::json!("{\"city\": ")));
+    }
+
+    #[test]
+    fn test_translate_tool_turns_without_tool_support() {
+        let prompt = create_tool_conversation();
+        let mut provider = create_test_provider();
+        provider.models[0].tooling.tools_supported = false;
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let messages = &result.provider_request_json["messages"];
+        assert!(messages[2].get("tool_calls").is_none());
+        assert_eq!(messages[3]["role"], "user");
+        assert_eq!(messages[3]["content"], "Tool result for call_1: sunny, 22C");
+
+        let codes: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.path.starts_with("messages["))
+            .map(|item| (item.path.as_str(), item.code))
+            .collect();
+        assert!(codes.contains(&("messages[2].tool_calls", crate::LossinessCode::Drop)));
+        assert!(codes.contains(&("messages[3]", crate::LossinessCode::Emulate)));
+
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_translate_system_top_level_field() {
+        let mut prompt = create_test_prompt();
+        prompt.messages.push(Message::system("Answer briefly."));
+        let mut provider = create_test_provider();
+        provider.provider.name = "anthropic".to_string();
+        provider.models[0].constraints.system_prompt_location = "top_level_field".to_string();
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["system"], "You are a helpful assistant.\n\nAnswer briefly.");
+        assert_eq!(request["messages"], serde_json::json!([{"role": "user", "content": "Hello!"}]));
+
+        // Only the system message that followed a user turn was relocated
+        let relocated: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.code == crate::LossinessCode::Relocate)
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(relocated, vec!["messages[2]"]);
+    }
+
+    #[test]
+    fn test_translate_with_rendering_template() {
+        let mut prompt = create_test_prompt();
+        prompt.messages.push(Message::user("Are you there?"));
+        prompt.messages.push(Message::assistant("Yes."));
+
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.system_prompt_location = "top_level_field".to_string();
+        provider.models[0].message_rendering = Some(serde_json::from_value(serde_json::json!({
+            "messages_field": "contents",
+            "content_field": "parts",
+            "role_map": {"assistant": "model"},
+            "system_field": "system_instruction",
+            "system_template": {"parts": [{"text": "{{text}}"}]},
+            "merge_consecutive_roles": true,
+            "content_mode": "blocks",
+            "blocks": {"text": {"text": "{{text}}"}}
+        })).unwrap());
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["system_instruction"], serde_json::json!({"parts": [{"text": "You are a helpful assistant."}]}));
+        assert_eq!(request["contents"], serde_json::json!([
+            {"role": "user", "parts": [{"text": "Hello!"}, {"text": "Are you there?"}]},
+            {"role": "model", "parts": [{"text": "Yes."}]},
+        ]));
+        assert!(request.get("messages").is_none());
+
+        // Parts without a template are unsupported
+        prompt.messages[1].content = vec![crate::ContentPart::image_url("https://example.com/cat.png")].into();
+        provider.models[0].input_modes.images = true;
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_translate_tool_turns_with_rendering_template() {
+        let mut assistant = Message::assistant("");
+        assistant.tool_calls = Some(vec![crate::ToolCall {
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({"city": "Paris"}),
+            id: Some("call_1".to_string()),
+        }]);
+        let mut prompt = create_test_prompt();
+        prompt.messages = vec![Message::user("Weather in Paris?"), assistant, Message::tool("call_1", "Sunny")];
+
+        // A provider with its own tool turn shapes is described by its spec alone
+        let mut provider = create_test_provider();
+        provider.models[0].tooling.tools_supported = true;
+        provider.models[0].message_rendering = Some(serde_json::from_value(serde_json::json!({
+            "messages_field": "contents",
+            "content_field": "parts",
+            "role_map": {"assistant": "model"},
+            "content_mode": "blocks",
+            "blocks": {
+                "text": {"text": "{{text}}"},
+                "tool_call": {"functionCall": {"id": "{{id}}", "name": "{{name}}", "args": "{{arguments}}"}},
+                "tool_result": {
+                    "role": "user",
+                    "content": [{"functionResponse": {"id": "{{tool_call_id}}", "response": {"output": "{{content}}"}}}]
+                }
+            }
+        })).unwrap());
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        let contents = &result.provider_request_json["contents"];
+        assert_eq!(contents[1], serde_json::json!({
+            "role": "model",
+            "parts": [{"functionCall": {"id": "call_1", "name": "get_weather", "args": {"city": "Paris"}}}],
+        }));
+        assert_eq!(contents[2], serde_json::json!({
+            "role": "user",
+            "parts": [{"functionResponse": {"id": "call_1", "response": {"output": [{"text": "Sunny"}]}}}],
+        }));
+
+        // The same templates read the turns back
+        let (reversed, _) = translate_reverse(&result.provider_request_json, &provider, "test-model").unwrap();
+        let messages = &reversed.messages;
+        assert_eq!(messages[1].tool_calls.as_ref().unwrap()[0].arguments, serde_json::json!({"city": "Paris"}));
+        assert_eq!(messages[2].role, MessageRole::Tool);
+        assert_eq!(messages[2].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(messages[2].content.text(), "Sunny");
+    }
+
+    #[test]
+    fn test_translate_prepend_system_to_first_user() {
+        let prompt = create_test_prompt();
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.system_prompt_location = "prepend_to_first_user".to_string();
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["messages"], serde_json::json!([
+            {"role": "user", "content": "You are a helpful assistant.\n\nHello!"},
+        ]));
+        assert!(result.lossiness.items.iter()
+            .any(|item| item.path == "messages[0]" && item.code == crate::LossinessCode::Emulate));
+    }
+
+    #[test]
+    fn test_translate_single_text_model() {
+        let mut prompt = create_test_prompt();
+        let mut provider = create_test_provider();
+        provider.models[0].input_modes.messages = false;
+        provider.models[0].input_modes.single_text = true;
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["prompt"], "system: You are a helpful assistant.\n\nuser: Hello!");
+        assert!(request.get("messages").is_none());
+        assert!(result.lossiness.items.iter().any(|item| item.code == crate::LossinessCode::Emulate));
+
+        // A lone user turn is sent unchanged
+        prompt.messages.remove(0);
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["prompt"], "Hello!");
+        assert!(result.lossiness.items.is_empty());
+    }
+    #[test]
+    fn test_translate_declared_transformations() {
+        let mut prompt = create_test_prompt();
+        prompt.sampling = Some(serde_json::from_value(serde_json::json!({"temperature": 1.5})).unwrap());
+        prompt.limits = Some(serde_json::from_value(serde_json::json!({"max_output_tokens": 128})).unwrap());
+
+        let mut provider = create_test_provider();
+        provider.models[0].transformations = serde_json::from_value(serde_json::json!([
+            {
+                "id": "temperature_scale",
+                "source_path": "$.temperature",
+                "type": "unit_conversion",
+                "from_unit": "openai",
+                "to_unit": "anthropic",
+                "formula": {"linear": {"scale": 0.5}}
+            },
+            {
+                "id": "max_tokens_rename",
+                "source_path": "$.max_tokens",
+                "type": "field_rename",
+                "new_name": "max_output_tokens"
+            }
+        ])).unwrap();
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["temperature"], serde_json::json!(0.75));
+        assert_eq!(request["max_output_tokens"], serde_json::json!(128));
+        assert!(request.get("max_tokens").is_none());
+    }
+    #[test]
+    fn test_translate_declared_parameter_ranges() {
+        let mut prompt = create_test_prompt();
+        prompt.sampling = Some(crate::SamplingParams {
+            temperature: Some(1.8),
+            top_p: Some(0.9),
+            top_k: Some(40),
+            frequency_penalty: None,
+            presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
+        });
+
+        let mut provider = create_test_provider();
+        provider.models[0].parameters = serde_json::json!({
+            "temperature": {"type": "number", "minimum": 0.0, "maximum": 1.0},
+            "top_p": {"type": "number", "minimum": 0.0, "maximum": 1.0},
+            "max_tokens": {"type": "integer", "minimum": 1, "default": 1024, "required": true}
+        });
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Coerce).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["temperature"], serde_json::json!(1.0));
+        assert_eq!(request["top_p"], serde_json::json!(0.9));
+        assert_eq!(request["max_tokens"], serde_json::json!(1024));
+        assert!(request.get("top_k").is_none());
+
+        let codes: Vec<_> = result.lossiness.items.iter()
+            .map(|item| (item.path.as_str(), item.code))
+            .collect();
+        assert!(codes.contains(&("temperature", crate::LossinessCode::Clamp)));
+        assert!(codes.contains(&("top_k", crate::LossinessCode::Drop)));
+
+        // Undeclared parameters fail in strict mode
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+
+        // Mapped parameters are checked under their request names
+        let mut mapped = provider.clone();
+        mapped.models[0].mappings.paths.insert(CANDIDATE_COUNT_PATH.to_string(), "$.n".to_string());
+        let mut candidates = prompt.clone();
+        candidates.sampling.as_mut().unwrap().candidate_count = Some(2);
+        let result = translate(&candidates, &mapped, "test-model", StrictMode::Coerce).unwrap();
+        assert!(result.provider_request_json.get("n").is_none());
+        assert!(result.lossiness.items.iter().any(|item| item.path == "n" && item.code == crate::LossinessCode::Drop));
+
+        // A required parameter without a default cannot be filled in strict mode
+        prompt.sampling = None;
+        provider.models[0].parameters["max_tokens"].as_object_mut().unwrap().remove("default");
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Warn).is_ok());
+    }
+    #[test]
+    fn test_translate_emulated_json_mode() {
+        let mut prompt = create_test_prompt();
+        prompt.response_format = Some(crate::ResponseFormat::JsonSchema {
+            json_schema: serde_json::json!({"type": "object", "required": ["answer"]}),
+            strict: None,
+        });
+
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.system_prompt_location = "top_level_field".to_string();
+        provider.models[0].json_output = JsonOutputConfig {
+            native_param: false,
+            strategy: "system_prompt".to_string(),
+            instruction: Some("Answer in JSON matching {{schema}}".to_string()),
+            prefill: true,
+        };
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert!(result.metadata.as_ref().unwrap().json_mode);
+        let request = &result.provider_request_json;
+        assert!(request.get("response_format").is_none());
+        assert_eq!(
+            request["system"],
+            "You are a helpful assistant.\n\nAnswer in JSON matching {\n  \"required\": [\n    \"answer\"\n  ],\n  \"type\": \"object\"\n}"
+        );
+        assert_eq!(request["messages"], serde_json::json!([
+            {"role": "user", "content": "Hello!"},
+            {"role": "assistant", "content": "{"},
+        ]));
+        assert!(result.lossiness.items.iter()
+            .any(|item| item.path == "response_format" && item.code == crate::LossinessCode::Emulate));
+    }
+
+    #[test]
+    fn test_translate_reasoning_round_trip() {
+        let mut assistant = Message::assistant("");
+        assistant.tool_calls = Some(vec![crate::ToolCall {
+            id: Some("toolu_1".to_string()),
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({"city": "Paris"}),
+        }]);
+        assistant.reasoning = Some(vec![
+            crate::ReasoningBlock {
+                text: Some("Check the weather first.".to_string()),
+                signature: Some("sig-1".to_string()),
+                redacted: None,
+            },
+            crate::ReasoningBlock { text: None, signature: None, redacted: Some("opaque".to_string()) },
+        ]);
+
+        let mut prompt = create_test_prompt();
+        prompt.messages = vec![Message::user("Weather in Paris?"), assistant, Message::tool("toolu_1", "Sunny")];
+
+        let mut provider = create_test_provider();
+        provider.provider.name = "anthropic".to_string();
+        provider.models[0].message_rendering = Some(crate::MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        });
+        provider.models[0].tooling.tools_supported = true;
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["messages"][1]["content"], serde_json::json!([
+            {"type": "thinking", "thinking": "Check the weather first.", "signature": "sig-1"},
+            {"type": "redacted_thinking", "data": "opaque"},
+            {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}},
+        ]));
+    }
+
+    #[test]
+    fn test_translate_reverse_openai_request() {
+        let provider = create_test_provider();
+        let request = serde_json::json!({
+            "model": "test-model",
+            "messages": [
+                {"role": "system", "content": "Be terse."},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,aGVsbG8=", "detail": "low"}},
+                ]},
+                {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "lookup", "arguments": "{\"q\":\"cat\"}"},
+                }]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "a cat"},
+            ],
+            "tools": [{"type": "function", "function": {"name": "lookup", "parameters": {"type": "object"}}}],
+            "tool_choice": "auto",
+            "response_format": {"type": "json_object"},
+            "temperature": 0.25,
+            "max_tokens": 64,
+            "reasoning_effort": "minimal",
+            "user": "user-42",
+            "stream": true,
+        });
+
+        let (prompt, lossiness) = translate_reverse(&request, &provider, "test-model").unwrap();
+        assert_eq!(prompt.messages.len(), 4);
+        assert_eq!(prompt.messages[0].role, MessageRole::System);
+        assert_eq!(prompt.messages[1].content, crate::MessageContent::Parts(vec![
+            crate::ContentPart::text("What is this?"),
+            crate::ContentPart::Image {
+                source: crate::MediaSource::Base64 { data: "aGVsbG8=".to_string(), mime_type: "image/png".to_string() },
+                detail: Some("low".to_string()),
+            },
+        ]));
+        assert_eq!(prompt.messages[2].tool_calls, Some(vec![crate::ToolCall {
+            name: "lookup".to_string(),
+            arguments: serde_json::json!({"q": "cat"}),
+            id: Some("call_1".to_string()),
+        }]));
+        assert_eq!(prompt.messages[3].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(prompt.tools.as_ref().unwrap()[0].json_schema, serde_json::json!({"type": "object"}));
+        assert!(matches!(prompt.tool_choice, Some(crate::ToolChoice::Auto)));
+        assert!(matches!(prompt.response_format, Some(crate::ResponseFormat::JsonObject)));
+        assert_eq!(prompt.sampling.as_ref().unwrap().temperature, Some(0.25));
+        assert_eq!(prompt.limits.as_ref().unwrap().max_output_tokens, Some(64));
+
+        // Values and fields without a uniform equivalent are reported, not guessed
+        let dropped: Vec<_> = lossiness.items.iter()
+            .filter(|item| item.code == crate::LossinessCode::Drop)
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["reasoning_effort", "user"]);
+    }
+
+    #[test]
+    fn test_translate_reverse_anthropic_request() {
+        let mut provider = create_test_provider();
+        provider.provider.name = "anthropic".to_string();
+        provider.models[0].message_rendering = Some(crate::MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        });
+        provider.models[0].constraints.system_prompt_location = "top_level_field".to_string();
+        provider.models[0].mappings.paths.insert(
+            "$.advanced.thinking_budget".to_string(),
+            "$.thinking.budget_tokens".to_string(),
+        );
+        provider.models[0].transformations = serde_json::from_value(serde_json::json!([{
+            "id": "temperature_scale",
+            "source_path": "$.temperature",
+            "type": "unit_conversion",
+            "from_unit": "openai",
+            "to_unit": "anthropic",
+            "formula": {"linear": {"scale": 0.5}}
+        }])).unwrap();
+
+        let request = serde_json::json!({
+            "model": "test-model",
+            "system": [{"type": "text", "text": "Be terse."}],
+            "messages": [
+                {"role": "user", "content": "Weather in Paris?"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "Look it up.", "signature": "sig-1"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}},
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "Sunny"},
+                    {"type": "text", "text": "And tomorrow?"},
+                ]},
+            ],
+            "tools": [{"name": "get_weather", "input_schema": {"type": "object"}}],
+            "tool_choice": {"type": "tool", "name": "get_weather"},
+            "thinking": {"type": "enabled", "budget_tokens": 2048},
+            "temperature": 0.4,
+        });
+
+        let (prompt, lossiness) = translate_reverse(&request, &provider, "test-model").unwrap();
+        let roles: Vec<_> = prompt.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, vec![
+            MessageRole::System,
+            MessageRole::User,
+            MessageRole::Assistant,
+            MessageRole::Tool,
+            MessageRole::User,
+        ]);
+        assert_eq!(prompt.messages[0].content, "Be terse.");
+        assert_eq!(prompt.messages[2].reasoning.as_ref().unwrap()[0].signature.as_deref(), Some("sig-1"));
+        assert_eq!(prompt.messages[2].tool_calls.as_ref().unwrap()[0].arguments, serde_json::json!({"city": "Paris"}));
+        assert_eq!(prompt.messages[3].content, "Sunny");
+        assert_eq!(prompt.messages[4].content, "And tomorrow?");
+        assert!(matches!(prompt.tool_choice, Some(crate::ToolChoice::Specific { ref name }) if name == "get_weather"));
+
+        // The forward temperature scaling is undone
+        assert_eq!(prompt.sampling.as_ref().unwrap().temperature, Some(0.8));
+        assert_eq!(prompt.advanced.as_ref().unwrap().thinking_budget, Some(2048));
+
+        // The rest of the thinking switch has no uniform equivalent
+        let dropped: Vec<_> = lossiness.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(dropped, vec!["thinking"]);
+    }
+
+    #[test]
+    fn test_translate_reverse_round_trip() {
+        let mut prompt = create_tool_conversation();
+        prompt.sampling = Some(serde_json::from_value(serde_json::json!({"temperature": 0.5, "top_p": 0.75})).unwrap());
+        let mut provider = create_test_provider();
+        provider.models[0].mappings.paths.insert("$.sampling.top_p".to_string(), "$.top_p".to_string());
+
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        let (reversed, lossiness) = translate_reverse(&result.provider_request_json, &provider, "test-model").unwrap();
+
+        assert!(lossiness.items.is_empty(), "{:?}", lossiness.items);
+        assert_eq!(
+            serde_json::to_value(&reversed.messages).unwrap(),
+            serde_json::to_value(&prompt.messages).unwrap(),
+        );
+        assert_eq!(reversed.sampling.unwrap().top_p, Some(0.75));
+
+        assert!(translate_reverse(&serde_json::json!([]), &provider, "test-model").is_err());
+        assert!(translate_reverse(&result.provider_request_json, &provider, "other-model").is_err());
+    }
+
+    #[test]
+    fn test_candidate_count() {
+        let mut prompt = create_test_prompt();
+        prompt.sampling = Some(serde_json::from_value(serde_json::json!({"n": 3})).unwrap());
+
+        // Without a mapping the candidates are emulated
+        let provider = create_test_provider();
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert!(result.provider_request_json.get("n").is_none());
+        let item = result.lossiness.items.iter()
+            .find(|item| item.path == "sampling.candidate_count")
+            .unwrap();
+        assert_eq!(item.code, LossinessCode::Emulate);
+
+        // A mapped candidate count is native
+        let mut provider = create_test_provider();
+        provider.models[0].mappings.paths.insert(CANDIDATE_COUNT_PATH.to_string(), "$.n".to_string());
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["n"], 3);
+        assert!(result.lossiness.items.iter().all(|item| item.code != LossinessCode::Emulate));
+    }
+
+    #[test]
+    fn test_stop_logprobs_and_logit_bias() {
+        let mut prompt = create_test_prompt();
+        prompt.sampling = Some(serde_json::from_value(serde_json::json!({
+            "stop": ["END", "STOP", "DONE"],
+            "logprobs": true,
+            "logit_bias": {"50256": -100.0}
+        })).unwrap());
+
+        // Mapped parameters take the provider's names and limits
+        let mut provider = create_test_provider();
+        let model = &mut provider.models[0];
+        model.mappings.paths.insert("$.sampling.stop".to_string(), "$.stop_sequences".to_string());
+        model.mappings.paths.insert("$.sampling.logprobs".to_string(), "$.logprobs".to_string());
+        model.parameters = serde_json::json!({
+            "stop_sequences": {"type": "array", "maxItems": 2},
+            "logprobs": {"type": "boolean"}
+        });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["stop_sequences"], serde_json::json!(["END", "STOP"]));
+        assert_eq!(request["logprobs"], true);
+        assert!(request.get("stop").is_none());
+        assert!(request.get("logit_bias").is_none());
+
+        let paths: Vec<_> = result.lossiness.items.iter().map(|item| item.path.as_str()).collect();
+        assert!(paths.contains(&"stop_sequences"));
+        assert!(paths.contains(&"sampling.logit_bias"));
+
+        // Unsupported parameters fail strict mode
+        let err = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap_err();
+        assert!(matches!(err, Error::StrictnessViolation { .. }));
+    }
+
+    #[test]
+    fn test_rag_documents() {
+        let mut prompt = create_test_prompt();
+        prompt.rag = Some(serde_json::from_value(serde_json::json!({
+            "documents": [{"id": "doc-1", "content": "Paris is the capital of France."}],
+            "connectors": ["web_search"]
+        })).unwrap());
+
+        // Without a document parameter the system prompt is grounded
+        let provider = create_test_provider();
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = result.provider_request_json.to_string();
+        assert!(request.contains("<document id=\\\"doc-1\\\">"));
+        let codes: Vec<_> = result.lossiness.items.iter().map(|item| (item.path.as_str(), item.code)).collect();
+        assert!(codes.contains(&("rag.documents", LossinessCode::Emulate)));
+        assert!(codes.contains(&("rag.connectors", LossinessCode::Drop)));
+
+        // Declared parameters receive the documents and connectors natively
+        let mut provider = create_test_provider();
+        provider.models[0].extensions = Some(serde_json::from_value(serde_json::json!({
+            "rag_config": {
+                "document_param": "documents",
+                "connector_param": "connectors",
+                "web_search_connector": "web-search"
+            }
+        })).unwrap());
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["documents"][0]["id"], "doc-1");
+        assert_eq!(request["connectors"], serde_json::json!([{"id": "web-search"}]));
+        assert!(!request.to_string().contains("<documents>"));
+        assert!(result.lossiness.items.iter().all(|item| !item.path.starts_with("rag.")));
+    }
+
+    #[test]
+    fn test_conversation_continuation() {
+        let mut prompt = create_test_prompt();
+        prompt.messages.push(Message::assistant("Hi! How can I help?"));
+        prompt.messages.push(Message::user("What is the capital of France?"));
+        prompt.conversation = Some(serde_json::from_value(serde_json::json!({
+            "parent_message_id": "resp_123"
+        })).unwrap());
+
+        // Without conversation parameters the history is replayed
+        let provider = create_test_provider();
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["messages"].as_array().unwrap().len(), 4);
+        let item = result.lossiness.items.iter().find(|item| item.path == "conversation").unwrap();
+        assert_eq!(item.code, LossinessCode::Emulate);
+
+        // A previous_response_id style parameter continues on the provider
+        let mut provider = create_test_provider();
+        provider.models[0].extensions = Some(serde_json::from_value(serde_json::json!({
+            "conversation_management": {"parent_message_param": "previous_response_id"}
+        })).unwrap());
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["previous_response_id"], "resp_123");
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["content"], "What is the capital of France?");
+        assert!(result.lossiness.items.iter().all(|item| item.path != "conversation"));
+
+        // Branching needs a model that supports it
+        prompt.conversation = Some(serde_json::from_value(serde_json::json!({
+            "branch_from": "resp_100"
+        })).unwrap());
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert!(result.provider_request_json.get("previous_response_id").is_none());
+        assert!(result.lossiness.items.iter().any(|item| item.path == "conversation"));
+    }
+
+    #[test]
+    fn test_prompt_truncation() {
+        let mut prompt = create_test_prompt();
+        prompt.messages.push(Message::assistant("a".repeat(400)));
+        prompt.messages.push(Message::user("u".repeat(400)));
+        prompt.messages.push(Message::assistant("ok"));
+        prompt.messages.push(Message::user("Last question?"));
+        prompt.limits = Some(Limits { max_output_tokens: None, reasoning_tokens: None, max_prompt_tokens: Some(150) });
+
+        // AUTO keeps the first user turn and trims the oldest turn that is
+        // enough to fit
+        let provider = create_test_provider();
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let messages = result.provider_request_json["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[1]["content"], "Hello!");
+        assert_eq!(messages[2]["content"].as_str().unwrap().len(), 48);
+        let item = result.lossiness.items.iter().find(|item| item.path == "messages[2].content").unwrap();
+        assert_eq!(item.code, LossinessCode::Drop);
+
+        // END trims the end of the turn that is enough to fit
+        prompt.preferences = Some(Preferences { prompt_truncation: Some(TruncationMode::End), ..Default::default() });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let messages = result.provider_request_json["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[3]["content"], "u".repeat(100));
+        let item = result.lossiness.items.iter().find(|item| item.path == "messages[3].content").unwrap();
+        assert_eq!(item.code, LossinessCode::Drop);
+        assert!(item.message.contains("from the end"));
+
+        // AUTO_PRESERVE_ORDER drops from the start against the context window
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.limits.context_window = Some(1000);
+        prompt.limits = Some(Limits { max_output_tokens: Some(980), reasoning_tokens: None, max_prompt_tokens: None });
+        prompt.preferences = Some(Preferences { prompt_truncation: Some(TruncationMode::AutoPreserveOrder), ..Default::default() });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let messages = result.provider_request_json["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["content"], "Last question?");
+        let dropped: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.code == LossinessCode::Drop)
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["messages[1]", "messages[2]", "messages[3]", "messages[4]"]);
+
+        // END drops the newest turns first, keeping the oldest
+        prompt.preferences = Some(Preferences { prompt_truncation: Some(TruncationMode::End), ..Default::default() });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let messages = result.provider_request_json["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["content"], "Hello!");
+        let dropped: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.code == LossinessCode::Drop)
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["messages[5]", "messages[4]", "messages[3]", "messages[2]"]);
+
+        // Strict mode fails instead of truncating
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+
+        prompt.preferences = Some(Preferences { prompt_truncation: Some(TruncationMode::Off), ..Default::default() });
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["messages"].as_array().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_size_limits() {
+        let mut prompt = create_test_prompt();
+        prompt.messages[0] = Message::system("Be brief. ".repeat(10));
+        prompt.tools = Some(vec![Tool {
+            name: "get_weather".to_string(),
+            description: Some("Get the weather".to_string()),
+            json_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "city": {"type": "string", "description": "City to look up the weather for"},
+                    "units": {"type": "object", "default": {"description": "metric"}}
+                }
+            }),
+        }]);
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.limits.max_system_prompt_bytes = 20;
+        provider.models[0].constraints.limits.max_tool_schema_bytes = 190;
+
+        // Coerce trims the system prompt and minifies the tool schema
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Coerce).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["messages"][0]["content"], "Be brief. Be brief. ");
+        assert_eq!(request["tools"][0]["function"]["description"], "Get the weather");
+        let properties = &request["tools"][0]["function"]["parameters"]["properties"];
+        assert!(properties["city"].get("description").is_none());
+        assert_eq!(properties["units"]["default"], serde_json::json!({"description": "metric"}));
+        let clamped: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.code == LossinessCode::Clamp)
+            .map(|item| item.path.as_str())
+            .collect();
+        assert_eq!(clamped, vec!["messages.system", "tools"]);
+
+        // Warn sends them unchanged
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["messages"][0]["content"], "Be brief. ".repeat(10));
+        assert_eq!(request["tools"][0]["function"]["parameters"], prompt.tools.as_ref().unwrap()[0].json_schema);
+        let oversized: Vec<_> = result.lossiness.items.iter()
+            .filter(|item| item.code == LossinessCode::Unsupported)
+            .collect();
+        assert_eq!(oversized.len(), 2);
+        assert!(oversized.iter().all(|item| item.after.is_none()));
+        assert!(!result.lossiness.items.iter().any(|item| item.code == LossinessCode::Clamp));
+
+        // Strict fails on either one
+        let mut short_system = prompt.clone();
+        short_system.messages[0] = Message::system("Be brief.");
+        assert!(translate(&short_system, &provider, "test-model", StrictMode::Strict).is_err());
+        let mut no_tools = prompt.clone();
+        no_tools.tools = None;
+        assert!(translate(&no_tools, &provider, "test-model", StrictMode::Strict).is_err());
+
+        // Grounding added to a system prompt within the limit is not cut
+        prompt.tools = None;
+        prompt.rag = Some(serde_json::from_value(serde_json::json!({
+            "documents": [{"id": "doc-1", "content": "Paris is the capital of France."}]
+        })).unwrap());
+        provider.models[0].constraints.limits.max_system_prompt_bytes = 100;
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Coerce).unwrap();
+        let system = result.provider_request_json["messages"][0]["content"].as_str().unwrap();
+        assert!(system.starts_with(&"Be brief. ".repeat(10)));
+        assert!(system.contains("Paris is the capital of France."));
+        assert!(!result.lossiness.items.iter().any(|item| item.path == "messages.system"));
+    }
+
+    #[test]
+    fn test_forbid_unknown_top_level_fields() {
+        let mut prompt = create_test_prompt();
+        prompt.advanced = Some(crate::AdvancedParams {
+            seed: Some(42),
+            ..Default::default()
+        });
+        let mut provider = create_test_provider();
+        provider.models[0].constraints.forbid_unknown_top_level_fields = true;
+
+        // Fields outside the parameters, mapping targets and flags are dropped
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert!(request.get("seed").is_none());
+        assert_eq!(request["model"], "test-model");
+        assert!(request.get("messages").is_some());
+        assert!(result.lossiness.items.iter().any(|item| item.code == LossinessCode::Drop && item.path == "seed"));
+
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+
+        // A mapping target makes the field known
+        provider.models[0].mappings.paths.insert("$.advanced.seed".to_string(), "$.seed".to_string());
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Strict).unwrap();
+        assert_eq!(result.provider_request_json["seed"], 42);
+    }
+
+    #[test]
+    fn test_tool_rendering() {
+        let mut prompt = create_test_prompt();
+        prompt.tools = Some(vec![Tool {
+            name: "get_weather".to_string(),
+            description: Some("Get the weather".to_string()),
+            json_schema: serde_json::json!({
+                "type": "object",
+                "properties": {"date": {"type": "string", "format": "date"}}
+            }),
+        }]);
+        prompt.tool_choice = Some(crate::ToolChoice::Specific { name: "get_weather".to_string() });
+        prompt.preferences = Some(Preferences { parallel_tool_calls: Some(false), ..Default::default() });
+        let mut provider = create_test_provider();
+
+        // OpenAI-style definitions; the model cannot disable parallel calls
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["tools"][0]["type"], "function");
+        assert_eq!(request["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(request["tools"][0]["function"]["parameters"]["properties"]["date"]["format"], "date");
+        assert_eq!(request["tool_choice"], serde_json::json!({"type": "function", "function": {"name": "get_weather"}}));
+        assert!(request.get("parallel_tool_calls").is_none());
+        assert!(result.lossiness.items.iter().any(|item| item.path == "preferences.parallel_tool_calls"));
+
+        provider.models[0].tooling.can_disable_parallel_tool_calls = true;
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        assert_eq!(result.provider_request_json["parallel_tool_calls"], false);
+
+        // Anthropic-style definitions, with the switch inside the tool choice
+        // and a schema keyword the model rejects
+        provider.models[0].tooling.rendering = Some(crate::ToolRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            unsupported_schema_keywords: vec!["format".to_string()],
+            ..Default::default()
+        });
+        prompt.tool_choice = Some(crate::ToolChoice::Required);
+        let result = translate(&prompt, &provider, "test-model", StrictMode::Warn).unwrap();
+        let request = &result.provider_request_json;
+        assert_eq!(request["tools"][0]["name"], "get_weather");
+        assert_eq!(request["tools"][0]["input_schema"], serde_json::json!({
+            "type": "object",
+            "properties": {"date": {"type": "string"}}
+        }));
+        assert_eq!(request["tool_choice"], serde_json::json!({"type": "any", "disable_parallel_tool_use": true}));
+        assert!(result.lossiness.items.iter().any(|item| item.code == LossinessCode::Drop && item.path == "tools[0].json_schema"));
+
+        assert!(translate(&prompt, &provider, "test-model", StrictMode::Strict).is_err());
+    }
 }
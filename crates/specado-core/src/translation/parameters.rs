@@ -0,0 +1,260 @@
+//! Spec-driven generation parameter constraints
+//!
+//! Checks the generation parameters in an assembled provider request, the
+//! fields written from the prompt's sampling, limits and advanced settings,
+//! against the model's declared `parameters`: values are clamped into the
+//! declared `minimum`/`maximum`, required parameters missing from the
+//! request get their declared `default`, arrays are cut to the declared
+//! `maxItems`, and parameters the model does not declare are dropped.
+//! Models that declare no parameters fall back to the generic OpenAI-style
+//! ranges.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Map, Value};
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::{Error, LossinessItem, Result, StrictMode};
+
+/// Ranges used when a model declares no parameters
+const FALLBACK_RANGES: &[(&str, f64, f64)] = &[
+    ("temperature", 0.0, 2.0),
+    ("top_p", 0.0, 1.0),
+    ("frequency_penalty", -2.0, 2.0),
+    ("presence_penalty", -2.0, 2.0),
+    ("max_tokens", 1.0, 1_000_000.0),
+    ("top_logprobs", 0.0, 20.0),
+];
+
+/// Applies a model's declared parameter constraints to a provider request
+pub struct ParameterConstraints<'a> {
+    parameters: Option<&'a Map<String, Value>>,
+    generation_fields: BTreeSet<String>,
+    provider_name: &'a str,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> ParameterConstraints<'a> {
+    /// Create constraints for the context's model; `generation_fields` are
+    /// the request fields holding generation parameters
+    pub fn new(
+        context: &'a TranslationContext,
+        generation_fields: impl IntoIterator<Item = String>,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let parameters = context.model_spec.parameters.as_object().filter(|p| !p.is_empty());
+        Self {
+            parameters,
+            generation_fields: generation_fields.into_iter().collect(),
+            provider_name: context.provider_name(),
+            policy,
+            tracker,
+        }
+    }
+
+    /// Clamp, drop and default the request's generation parameters
+    pub fn apply(&self, request: &mut Value) -> Result<()> {
+        let Some(fields) = request.as_object_mut() else {
+            return Ok(());
+        };
+
+        let Some(parameters) = self.parameters else {
+            for (name, min, max) in FALLBACK_RANGES {
+                if let Some(value) = fields.get(*name).cloned() {
+                    self.clamp(fields, name, value, *min, *max)?;
+                }
+            }
+            return Ok(());
+        };
+
+        for name in &self.generation_fields {
+            let Some(value) = fields.get(name).cloned() else {
+                continue;
+            };
+            match parameters.get(name) {
+                Some(declared) => self.check(fields, name, value, declared)?,
+                None => self.drop_field(
+                    fields,
+                    name,
+                    value,
+                    &format!("{} parameter", name),
+                    format!("{} does not declare the '{}' parameter; dropped", self.provider_name, name),
+                )?,
+            }
+        }
+
+        for (name, declared) in parameters {
+            let required = declared.get("required").and_then(Value::as_bool).unwrap_or(false);
+            if required && !fields.contains_key(name) {
+                self.apply_default(fields, name, declared)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a declared parameter's type, enum, range and item count
+    fn check(&self, fields: &mut Map<String, Value>, name: &str, value: Value, declared: &Value) -> Result<()> {
+        if let Some(allowed) = declared.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(&value) {
+                let message = format!(
+                    "{} does not accept {} for '{}'; dropped",
+                    self.provider_name, value, name
+                );
+                return self.drop_field(fields, name, value, &format!("{} value", name), message);
+            }
+        }
+
+        let value = match declared.get("type").and_then(Value::as_str) {
+            Some("integer") if value.as_f64().is_some_and(|n| n.fract() != 0.0) => {
+                let rounded = Value::from(value.as_f64().unwrap_or_default().round() as i64);
+                if let Ok(mut tracker) = self.tracker.lock() {
+                    tracker.track_coercion(
+                        name,
+                        Some(value),
+                        Some(rounded.clone()),
+                        "Rounded to the integer the model expects",
+                        Some(self.provider_name.to_string()),
+                    );
+                }
+                fields.insert(name.to_string(), rounded.clone());
+                rounded
+            }
+            _ => value,
+        };
+
+        let min = declared.get("minimum").and_then(Value::as_f64);
+        let max = declared.get("maximum").and_then(Value::as_f64);
+        if (min.is_some() || max.is_some()) && value.is_number() {
+            self.clamp(
+                fields,
+                name,
+                value,
+                min.unwrap_or(f64::NEG_INFINITY),
+                max.unwrap_or(f64::INFINITY),
+            )?;
+        } else if let Some(max_items) = declared.get("maxItems").and_then(Value::as_u64) {
+            self.truncate(fields, name, value, max_items as usize)?;
+        }
+        Ok(())
+    }
+
+    /// Cut an array parameter to the number of items the model accepts
+    fn truncate(&self, fields: &mut Map<String, Value>, name: &str, value: Value, max_items: usize) -> Result<()> {
+        let policy_result = self.policy.evaluate_item_limit(name, value, max_items, self.provider_name);
+        if let StrictnessAction::Coerce { adjusted_value, .. } = &policy_result.action {
+            fields.insert(name.to_string(), adjusted_value.clone());
+        }
+        self.apply_policy(policy_result)
+    }
+
+    /// Clamp a numeric parameter into its range
+    fn clamp(&self, fields: &mut Map<String, Value>, name: &str, value: Value, min: f64, max: f64) -> Result<()> {
+        let policy_result = self.policy.evaluate_value_clamping(name, value, min, max, self.provider_name);
+        if let StrictnessAction::Coerce { adjusted_value, .. } = &policy_result.action {
+            // Keep integer parameters integral after clamping
+            let adjusted_value = match adjusted_value.as_f64() {
+                Some(n) if n.fract() == 0.0 && fields.get(name).is_some_and(|v| v.is_i64() || v.is_u64()) => {
+                    Value::from(n as i64)
+                }
+                _ => adjusted_value.clone(),
+            };
+            fields.insert(name.to_string(), adjusted_value);
+        }
+        self.apply_policy(policy_result)
+    }
+
+    /// Drop a parameter the model does not accept, reporting `message`
+    fn drop_field(
+        &self,
+        fields: &mut Map<String, Value>,
+        name: &str,
+        value: Value,
+        feature: &str,
+        message: String,
+    ) -> Result<()> {
+        let mut policy_result = self.policy.evaluate_unsupported_feature(name, feature, Some(value));
+        if let Some(item) = policy_result.lossiness_item.as_mut() {
+            item.message = message;
+        }
+        self.apply_policy(policy_result)?;
+        fields.remove(name);
+        Ok(())
+    }
+
+    /// Inject the declared default for a missing required parameter
+    fn apply_default(&self, fields: &mut Map<String, Value>, name: &str, declared: &Value) -> Result<()> {
+        let Some(default) = declared.get("default") else {
+            let message = format!(
+                "Required parameter '{}' is missing and {} declares no default",
+                name, self.provider_name
+            );
+            if self.policy.mode() == StrictMode::Strict {
+                return Err(Error::Validation {
+                    field: name.to_string(),
+                    message,
+                    expected: declared.get("type").and_then(Value::as_str).map(str::to_string),
+                });
+            }
+            log::warn!("{}", message);
+            return Ok(());
+        };
+
+        if let Ok(mut tracker) = self.tracker.lock() {
+            tracker.track_default_applied(
+                name,
+                Some(default.clone()),
+                "Required parameter missing from the request",
+                Some(self.provider_name.to_string()),
+            );
+        }
+        fields.insert(name.to_string(), default.clone());
+        Ok(())
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Top-level fields of a request
+pub(crate) fn request_fields(request: &Value) -> BTreeSet<String> {
+    request.as_object()
+        .map(|fields| fields.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Top-level fields of a request that are not in `existing`
+pub(crate) fn new_fields(existing: &BTreeSet<String>, request: &Value) -> Vec<String> {
+    request_fields(request).into_iter()
+        .filter(|name| !existing.contains(name))
+        .collect()
+}
+
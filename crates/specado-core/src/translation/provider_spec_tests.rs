@@ -182,7 +182,7 @@ mod tests {
             assert_eq!(model.endpoints.chat_completion.path, "/v1/messages");
             
             // Check system prompt location constraint
-            assert_eq!(model.constraints.system_prompt_location, "top_level");
+            assert_eq!(model.constraints.system_prompt_location, "top_level_field");
             
             // Check Anthropic-specific constraints
             assert!(model.constraints.forbid_unknown_top_level_fields);
@@ -360,15 +360,21 @@ mod tests {
             messages: vec![
                 Message {
                     role: MessageRole::System,
-                    content: "You are a helpful assistant.".to_string(),
+                    content: "You are a helpful assistant.".into(),
                     name: None,
                     metadata: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    reasoning: None,
                 },
                 Message {
                     role: MessageRole::User,
-                    content: "Hello!".to_string(),
+                    content: "Hello!".into(),
                     name: None,
                     metadata: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    reasoning: None,
                 },
             ],
             tools: None,
@@ -379,6 +385,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         // Test translation with OpenAI spec
@@ -396,6 +405,190 @@ mod tests {
         assert!(result.is_ok(), "Translation with Anthropic spec should succeed");
     }
     
+    #[test]
+    fn test_shipped_specs_declare_mapping_targets() {
+        for path in [
+            "providers/openai/gpt-5.json",
+            "providers/openai/gpt-5-mini.json",
+            "providers/openai/gpt-5-nano.json",
+            "providers/anthropic/claude-opus-4.1.json",
+            "providers/anthropic/claude-sonnet-4.json",
+            "providers/anthropic/claude-4-sonnet.json",
+        ] {
+            let spec = load_provider_spec(path).expect("Failed to load provider spec");
+            for model in &spec.models {
+                for target in model.mappings.paths.values() {
+                    let field = target.trim_start_matches("$.").split(['.', '[']).next().unwrap();
+                    assert!(
+                        model.parameters.get(field).is_some(),
+                        "{} maps to '{}' without declaring it",
+                        model.id,
+                        field
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gpt5_keeps_written_parameters() {
+        let openai_spec = load_provider_spec("providers/openai/gpt-5.json")
+            .expect("Failed to load OpenAI spec");
+        let prompt_spec = PromptSpec {
+            messages: vec![Message::user("Hello")],
+            sampling: Some(crate::SamplingParams {
+                temperature: None,
+                top_p: None,
+                top_k: None,
+                frequency_penalty: Some(0.5),
+                presence_penalty: Some(-0.5),
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
+            }),
+            advanced: Some(crate::AdvancedParams {
+                verbosity: Some(crate::VerbosityLevel::Detailed),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let result = crate::translate(&prompt_spec, &openai_spec, "gpt-5", StrictMode::Strict)
+            .expect("Translation with OpenAI spec should succeed");
+        let request = &result.provider_request_json;
+        assert_eq!(request["frequency_penalty"], serde_json::json!(0.5));
+        assert_eq!(request["presence_penalty"], serde_json::json!(-0.5));
+        assert_eq!(request["verbosity"], "high");
+        assert!(result.lossiness.items.iter().all(|item| item.code != crate::LossinessCode::Drop));
+    }
+
+    #[test]
+    fn test_anthropic_thinking_fields() {
+        let anthropic_spec = load_provider_spec("providers/anthropic/claude-opus-4.1.json")
+            .expect("Failed to load Anthropic spec");
+        let mut prompt_spec = PromptSpec {
+            messages: vec![Message::user("Hello")],
+            advanced: Some(crate::AdvancedParams {
+                thinking: Some(false),
+                min_thinking_tokens: Some(2048),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // A disabled switch sends neither field
+        let result = crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Warn)
+            .expect("Translation with Anthropic spec should succeed");
+        let request = &result.provider_request_json;
+        assert!(request.get("thinking").is_none());
+        assert!(request.get("min_thinking_tokens").is_none());
+
+        // An enabled one is sent in Anthropic's shape
+        prompt_spec.advanced.as_mut().unwrap().thinking = Some(true);
+        let result = crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Strict)
+            .expect("Translation with Anthropic spec should succeed");
+        let request = &result.provider_request_json;
+        assert_eq!(request["thinking"], serde_json::json!({"type": "enabled", "budget_tokens": 2048}));
+        assert!(request.get("min_thinking_tokens").is_none());
+    }
+
+    #[test]
+    fn test_anthropic_thinking_drops_rejected_fields() {
+        let anthropic_spec = load_provider_spec("providers/anthropic/claude-opus-4.1.json")
+            .expect("Failed to load Anthropic spec");
+        let mut prompt_spec = PromptSpec {
+            messages: vec![Message::user("List three colors")],
+            tools: Some(vec![crate::Tool {
+                name: "get_colors".to_string(),
+                description: None,
+                json_schema: serde_json::json!({"type": "object"}),
+            }]),
+            tool_choice: Some(crate::ToolChoice::Required),
+            response_format: Some(crate::ResponseFormat::JsonObject),
+            sampling: Some(crate::SamplingParams {
+                temperature: Some(0.8),
+                top_p: None,
+                top_k: Some(40),
+                frequency_penalty: None,
+                presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
+            }),
+            advanced: Some(crate::AdvancedParams {
+                thinking: Some(true),
+                min_thinking_tokens: Some(2048),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert!(crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Strict).is_err());
+
+        // Temperature, top_k and forced tool use are dropped, and JSON mode
+        // is emulated without the assistant prefill
+        let result = crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Warn)
+            .expect("Translation with Anthropic spec should succeed");
+        let request = &result.provider_request_json;
+        assert_eq!(request["thinking"], serde_json::json!({"type": "enabled", "budget_tokens": 2048}));
+        assert!(request.get("temperature").is_none());
+        assert!(request.get("top_k").is_none());
+        assert_eq!(request["tool_choice"], serde_json::json!({"type": "auto"}));
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.last().unwrap()["role"], "user");
+        for path in ["sampling.temperature", "sampling.top_k", "tool_choice"] {
+            assert!(
+                result.lossiness.items.iter().any(|item| item.path == path && item.code == crate::LossinessCode::Drop),
+                "{} should be reported as dropped",
+                path
+            );
+        }
+
+        // Without thinking they are all sent
+        prompt_spec.advanced = None;
+        let result = crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Warn)
+            .expect("Translation with Anthropic spec should succeed");
+        let request = &result.provider_request_json;
+        assert_eq!(request["temperature"], serde_json::json!(0.4));
+        assert_eq!(request["tool_choice"], serde_json::json!({"type": "any"}));
+        assert_eq!(request["messages"].as_array().unwrap().last().unwrap()["role"], "assistant");
+    }
+
+    #[test]
+    fn test_anthropic_tool_rendering() {
+        let anthropic_spec = load_provider_spec("providers/anthropic/claude-opus-4.1.json")
+            .expect("Failed to load Anthropic spec");
+        let mut assistant = Message::assistant("");
+        assistant.tool_calls = Some(vec![crate::ToolCall {
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({"city": "Paris"}),
+            id: Some("toolu_1".to_string()),
+        }]);
+        let prompt_spec = PromptSpec {
+            messages: vec![Message::user("Weather in Paris?"), assistant, Message::tool("toolu_1", "Sunny")],
+            tools: Some(vec![crate::Tool {
+                name: "get_weather".to_string(),
+                description: Some("Current weather".to_string()),
+                json_schema: serde_json::json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+            }]),
+            tool_choice: Some(crate::ToolChoice::Auto),
+            ..Default::default()
+        };
+
+        // The spec's declared formats select Anthropic's shapes
+        let result = crate::translate(&prompt_spec, &anthropic_spec, "claude-opus-4.1", StrictMode::Strict)
+            .expect("Translation with Anthropic spec should succeed");
+        let request = &result.provider_request_json;
+        assert_eq!(request["tools"][0]["input_schema"]["type"], "object");
+        assert_eq!(request["tool_choice"], serde_json::json!({"type": "auto"}));
+        assert_eq!(request["messages"][1]["content"][0]["type"], "tool_use");
+        assert_eq!(request["messages"][2]["content"][0]["type"], "tool_result");
+    }
+
     #[test]
     #[ignore] // Requires provider spec files to be in the filesystem
     fn test_cross_provider_consistency() {
@@ -0,0 +1,209 @@
+//! Retrieval-augmented generation documents
+//!
+//! Models that declare a `document_param` in `extensions.rag_config` receive
+//! the prompt's RAG documents natively, along with connectors and search
+//! queries when they declare parameters for them. Other models get the
+//! documents rendered into a grounded context block in the system prompt and
+//! are asked to cite them by ID in square brackets, which the response side
+//! reads back as citations.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use super::lossiness::LossinessTracker;
+use super::rendering::append_system_text;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::{Citation, Message, RagConfig, RagConfiguration};
+use crate::{LossinessItem, Result};
+
+/// Uniform connector name replaced by the model's `web_search_connector`
+pub const WEB_SEARCH_CONNECTOR: &str = "web_search";
+
+/// Passes a prompt's RAG configuration to a provider request
+pub struct RagTranslator<'a> {
+    rag: Option<&'a RagConfig>,
+    config: Option<&'a RagConfiguration>,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> RagTranslator<'a> {
+    /// Create a translator for the context's prompt and model
+    pub fn new(
+        context: &'a TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        Self {
+            rag: context.prompt_spec.rag.as_ref(),
+            config: context.model_spec.extensions.as_ref().and_then(|e| e.rag_config.as_ref()),
+            policy,
+            tracker,
+        }
+    }
+
+    /// Ground the conversation in the documents when the model has no
+    /// document parameter
+    pub fn ground(&self, messages: &[Message]) -> Result<Vec<Message>> {
+        let mut messages = messages.to_vec();
+        let Some(rag) = self.rag.filter(|rag| !rag.documents.is_empty()) else {
+            return Ok(messages);
+        };
+        if self.config.is_some_and(|config| config.document_param.is_some()) {
+            return Ok(messages);
+        }
+
+        self.apply_policy(self.policy.evaluate_feature_emulation(
+            "rag.documents",
+            "RAG documents",
+            "grounded context in the system prompt",
+            Some(json!(rag.documents)),
+        ))?;
+        append_system_text(&mut messages, &grounding_context(rag));
+        Ok(messages)
+    }
+
+    /// Set the documents, connectors and search queries on the request
+    /// through the model's declared parameters
+    pub fn apply(&self, request: &mut Value) -> Result<()> {
+        let Some(rag) = self.rag else {
+            return Ok(());
+        };
+        let config = self.config.cloned().unwrap_or_default();
+
+        if let Some(param) = config.document_param.as_deref().filter(|_| !rag.documents.is_empty()) {
+            request[param] = json!(rag.documents);
+        }
+
+        if !rag.connectors.is_empty() {
+            match config.connector_param.as_deref() {
+                Some(param) => {
+                    let connectors: Vec<Value> = rag.connectors.iter()
+                        .map(|connector| match config.web_search_connector.as_deref() {
+                            Some(web_search) if connector == WEB_SEARCH_CONNECTOR => json!({ "id": web_search }),
+                            _ => json!({ "id": connector }),
+                        })
+                        .collect();
+                    request[param] = Value::Array(connectors);
+                }
+                None => self.apply_policy(self.policy.evaluate_unsupported_feature(
+                    "rag.connectors",
+                    "rag_connectors",
+                    Some(json!(rag.connectors)),
+                ))?,
+            }
+        }
+
+        if !rag.search_queries.is_empty() {
+            match config.search_queries_param.as_deref() {
+                Some(param) => request[param] = json!(rag.search_queries),
+                None => self.apply_policy(self.policy.evaluate_unsupported_feature(
+                    "rag.search_queries",
+                    "rag_search_queries",
+                    Some(json!(rag.search_queries)),
+                ))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Grounded context block listing the documents
+pub fn grounding_context(rag: &RagConfig) -> String {
+    let mut context = String::from("Answer using the documents below.");
+    if rag.citations_required {
+        context.push_str(" Cite each document you use by writing its ID in square brackets, e.g. [");
+        context.push_str(&rag.documents[0].id);
+        context.push_str("].");
+    }
+    context.push_str("\n\n<documents>");
+    for document in &rag.documents {
+        context.push_str(&format!("\n<document id=\"{}\">", document.id));
+        if let Some(metadata) = &document.metadata {
+            context.push_str(&format!("\nMetadata: {}", metadata));
+        }
+        context.push_str(&format!("\n{}\n</document>", document.content));
+    }
+    context.push_str("\n</documents>");
+    context
+}
+
+/// Citations written as document IDs in square brackets, in order of first
+/// appearance
+pub fn inline_citations(content: &str, document_ids: &[String]) -> Vec<Citation> {
+    let mut found: Vec<(usize, &String)> = document_ids.iter()
+        .filter_map(|id| content.find(&format!("[{}]", id)).map(|position| (position, id)))
+        .collect();
+    found.sort();
+    found.into_iter()
+        .map(|(_, id)| Citation { document_id: id.clone(), text: None })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RagDocument;
+
+    fn documents() -> RagConfig {
+        RagConfig {
+            documents: vec![
+                RagDocument { id: "doc-1".to_string(), content: "Paris is the capital of France.".to_string(), metadata: None },
+                RagDocument {
+                    id: "doc-2".to_string(),
+                    content: "Berlin is the capital of Germany.".to_string(),
+                    metadata: Some(json!({"title": "Germany"})),
+                },
+            ],
+            citations_required: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_grounding_context() {
+        let context = grounding_context(&documents());
+        assert!(context.starts_with("Answer using the documents below. Cite each document"));
+        assert!(context.contains("<document id=\"doc-1\">\nParis is the capital of France.\n</document>"));
+        assert!(context.contains("Metadata: {\"title\":\"Germany\"}"));
+        assert!(context.ends_with("</documents>"));
+    }
+
+    #[test]
+    fn test_inline_citations() {
+        let ids = vec!["doc-1".to_string(), "doc-2".to_string(), "doc-3".to_string()];
+        let citations = inline_citations("Berlin [doc-2] and Paris [doc-1] [doc-2].", &ids);
+        let cited: Vec<_> = citations.iter().map(|c| c.document_id.as_str()).collect();
+        assert_eq!(cited, vec!["doc-2", "doc-1"]);
+        assert!(inline_citations("No citations.", &ids).is_empty());
+    }
+}
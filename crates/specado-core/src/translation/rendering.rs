@@ -0,0 +1,556 @@
+//! Spec-driven message rendering
+//!
+//! Turns the uniform conversation into the request fields a provider
+//! expects, following the model's [`MessageRendering`] template: where the
+//! system prompt goes, role renames, merging of consecutive same-role
+//! turns, message field names and content blocks. Models that only accept a
+//! single prompt string get the conversation flattened into one field.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use super::content::ContentRenderer;
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::{ContentPart, LossinessItem, Message, MessageContent, MessageRendering, MessageRole};
+use crate::Result;
+
+/// Where a provider expects the system prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemPromptLocation {
+    /// System messages stay where they are in the conversation
+    InPlace,
+    /// System messages are moved to the start of the conversation
+    FirstMessage,
+    /// System text goes into a top-level request field
+    TopLevelField,
+    /// System text is prepended to the first user message
+    PrependToFirstUser,
+}
+
+impl SystemPromptLocation {
+    /// Parse `constraints.system_prompt_location`, accepting legacy names
+    pub fn parse(location: &str) -> Self {
+        match location {
+            "first_message" | "first" => Self::FirstMessage,
+            "top_level_field" | "top_level" | "system_parameter" => Self::TopLevelField,
+            "prepend_to_first_user" | "preamble" => Self::PrependToFirstUser,
+            _ => Self::InPlace,
+        }
+    }
+}
+
+/// Renders a conversation into provider request fields
+pub struct MessageRenderer<'a> {
+    rendering: MessageRendering,
+    location: SystemPromptLocation,
+    single_text: bool,
+    content: ContentRenderer<'a>,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> MessageRenderer<'a> {
+    /// Create a renderer for the context's model
+    pub fn new(
+        context: &TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let model_spec = &context.model_spec;
+        let rendering = model_spec.message_rendering.clone().unwrap_or_default();
+        let format = rendering.format.unwrap_or_default();
+
+        Self {
+            location: SystemPromptLocation::parse(context.system_prompt_location()),
+            single_text: !model_spec.input_modes.messages && model_spec.input_modes.single_text,
+            content: ContentRenderer::new(format, model_spec, policy, tracker),
+            rendering,
+            policy,
+            tracker,
+        }
+    }
+
+    /// Render the conversation into request fields
+    pub fn render(&self, messages: &[Message]) -> Result<Map<String, Value>> {
+        if self.single_text {
+            return self.render_single_text(messages);
+        }
+
+        let mut fields = Map::new();
+        let messages = match self.location {
+            SystemPromptLocation::InPlace => messages.to_vec(),
+            SystemPromptLocation::FirstMessage => self.move_system_first(messages),
+            SystemPromptLocation::TopLevelField => {
+                let (system, others) = self.extract_system(messages)?;
+                if let Some(system) = system {
+                    fields.insert(self.rendering.system_field.clone(), system);
+                }
+                others
+            }
+            SystemPromptLocation::PrependToFirstUser => self.prepend_system(messages)?,
+        };
+
+        let mut rendered = Vec::with_capacity(messages.len());
+        for (index, message) in messages.iter().enumerate() {
+            let mut message = self.content.render_message(index, message)?;
+            self.map_role(&mut message);
+            rendered.push(message);
+        }
+
+        if self.rendering.merge_consecutive_roles {
+            rendered = self.merge_consecutive(rendered);
+        }
+
+        let rendered = rendered.into_iter().map(|message| self.rename_fields(message)).collect();
+        fields.insert(self.rendering.messages_field.clone(), Value::Array(rendered));
+        Ok(fields)
+    }
+
+    /// Stable-move system messages to the start of the conversation
+    fn move_system_first(&self, messages: &[Message]) -> Vec<Message> {
+        let system_not_first = messages.first().is_some_and(|m| m.role != MessageRole::System);
+        let (system, others): (Vec<_>, Vec<_>) = messages.iter().cloned()
+            .partition(|m| m.role == MessageRole::System);
+
+        if system.is_empty() || !system_not_first {
+            return messages.to_vec();
+        }
+
+        let policy_result = self.policy.evaluate_field_relocation(
+            "messages",
+            "messages[0]",
+            json!(system.len()),
+        );
+        self.record(policy_result.lossiness_item);
+
+        system.into_iter().chain(others).collect()
+    }
+
+    /// Pull system messages out into the top-level system value
+    fn extract_system(&self, messages: &[Message]) -> Result<(Option<Value>, Vec<Message>)> {
+        let mut texts = Vec::new();
+        let mut others = Vec::new();
+
+        for (index, message) in messages.iter().enumerate() {
+            if message.role != MessageRole::System {
+                others.push(message.clone());
+                continue;
+            }
+
+            let text = self.system_text(index, message)?;
+            // Only a system message that followed other turns loses its position
+            if !others.is_empty() {
+                let policy_result = self.policy.evaluate_field_relocation(
+                    &format!("messages[{}]", index),
+                    &self.rendering.system_field,
+                    json!(text),
+                );
+                self.record(policy_result.lossiness_item);
+            }
+            texts.push(text);
+        }
+
+        if texts.is_empty() {
+            return Ok((None, others));
+        }
+
+        let text = texts.join("\n\n");
+        let system = match &self.rendering.system_template {
+            Some(template) => render_template(template, &Map::from_iter([("text".to_string(), json!(text))])),
+            None => Value::String(text),
+        };
+        Ok((Some(system), others))
+    }
+
+    /// Fold system text into the first user message
+    fn prepend_system(&self, messages: &[Message]) -> Result<Vec<Message>> {
+        let mut texts = Vec::new();
+        let mut first_system = None;
+        let mut others = Vec::new();
+
+        for (index, message) in messages.iter().enumerate() {
+            if message.role == MessageRole::System {
+                first_system.get_or_insert(index);
+                texts.push(self.system_text(index, message)?);
+            } else {
+                others.push(message.clone());
+            }
+        }
+
+        let Some(first_system) = first_system else {
+            return Ok(others);
+        };
+        let text = texts.join("\n\n");
+
+        let policy_result = self.policy.evaluate_feature_emulation(
+            &format!("messages[{}]", first_system),
+            "system_messages",
+            "prepending to the first user message",
+            Some(json!(text)),
+        );
+        self.apply(policy_result)?;
+
+        match others.iter_mut().find(|m| m.role == MessageRole::User) {
+            Some(user) => {
+                user.content = match std::mem::take(&mut user.content) {
+                    MessageContent::Text(user_text) => MessageContent::Text(format!("{}\n\n{}", text, user_text)),
+                    MessageContent::Parts(mut parts) => {
+                        parts.insert(0, ContentPart::text(text));
+                        MessageContent::Parts(parts)
+                    }
+                };
+            }
+            None => others.insert(0, Message::user(text)),
+        }
+        Ok(others)
+    }
+
+    /// Text of a system message; non-text parts cannot be carried
+    fn system_text(&self, index: usize, message: &Message) -> Result<String> {
+        if let MessageContent::Parts(parts) = &message.content {
+            for (part_index, part) in parts.iter().enumerate() {
+                if !matches!(part, ContentPart::Text { .. }) {
+                    let policy_result = self.policy.evaluate_unsupported_feature(
+                        &format!("messages[{}].content[{}]", index, part_index),
+                        "system_media",
+                        serde_json::to_value(part).ok(),
+                    );
+                    self.apply(policy_result)?;
+                }
+            }
+        }
+        Ok(message.content.text().into_owned())
+    }
+
+    /// Flatten the conversation into a single prompt string
+    fn render_single_text(&self, messages: &[Message]) -> Result<Map<String, Value>> {
+        let mut turns = Vec::with_capacity(messages.len());
+        for (index, message) in messages.iter().enumerate() {
+            let text = self.system_text(index, message)?;
+            if let Some(tool_calls) = message.tool_calls.as_ref().filter(|calls| !calls.is_empty()) {
+                let policy_result = self.policy.evaluate_unsupported_feature(
+                    &format!("messages[{}].tool_calls", index),
+                    "tool_calls",
+                    serde_json::to_value(tool_calls).ok(),
+                );
+                self.apply(policy_result)?;
+            }
+            turns.push((self.role_name(message.role), text));
+        }
+
+        // A lone user turn is sent as-is
+        let prompt = match (messages, turns.as_slice()) {
+            ([message], [(_, text)]) if message.role == MessageRole::User => text.clone(),
+            _ => {
+                let policy_result = self.policy.evaluate_feature_emulation(
+                    "messages",
+                    "chat_messages",
+                    "a single text prompt",
+                    None,
+                );
+                self.apply(policy_result)?;
+
+                turns.iter()
+                    .map(|(role, text)| {
+                        let vars = Map::from_iter([
+                            ("role".to_string(), json!(role)),
+                            ("text".to_string(), json!(text)),
+                        ]);
+                        interpolate(&self.rendering.turn_template, &vars)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&self.rendering.turn_separator)
+            }
+        };
+
+        Ok(Map::from_iter([(self.rendering.prompt_field.clone(), Value::String(prompt))]))
+    }
+
+    /// Provider name of a role after renames
+    fn role_name(&self, role: MessageRole) -> String {
+        let name = serde_json::to_value(role).ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        self.rendering.role_map.get(&name).cloned().unwrap_or(name)
+    }
+
+    /// Apply role renames to a rendered message
+    fn map_role(&self, message: &mut Value) {
+        if let Some(role) = message.get("role").and_then(Value::as_str) {
+            if let Some(mapped) = self.rendering.role_map.get(role) {
+                message["role"] = json!(mapped);
+            }
+        }
+    }
+
+    /// Merge consecutive plain turns that share a role
+    ///
+    /// Messages carrying anything besides role and content (e.g. OpenAI
+    /// tool calls) are left alone.
+    fn merge_consecutive(&self, messages: Vec<Value>) -> Vec<Value> {
+        let is_plain = |m: &Value| m.as_object().is_some_and(|o| o.keys().all(|k| k == "role" || k == "content"));
+
+        let mut merged: Vec<Value> = Vec::with_capacity(messages.len());
+        for message in messages {
+            match merged.last_mut() {
+                Some(previous) if is_plain(previous) && is_plain(&message) && previous["role"] == message["role"] => {
+                    let content = self.merge_content(previous["content"].take(), message["content"].clone());
+                    previous["content"] = content;
+                }
+                _ => merged.push(message),
+            }
+        }
+        merged
+    }
+
+    /// Join two rendered contents; text is joined, blocks are concatenated
+    fn merge_content(&self, first: Value, second: Value) -> Value {
+        match (first, second) {
+            (Value::String(a), Value::String(b)) => Value::String(format!("{}\n\n{}", a, b)),
+            (a, b) => {
+                let mut blocks = self.content_blocks(a);
+                blocks.extend(self.content_blocks(b));
+                Value::Array(blocks)
+            }
+        }
+    }
+
+    fn content_blocks(&self, content: Value) -> Vec<Value> {
+        match content {
+            Value::Array(blocks) => blocks,
+            Value::String(text) => self.content.text_block(&text).into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rename the role and content fields of a rendered message
+    fn rename_fields(&self, mut message: Value) -> Value {
+        if let Some(object) = message.as_object_mut() {
+            for (from, to) in [("role", &self.rendering.role_field), ("content", &self.rendering.content_field)] {
+                if from != to {
+                    if let Some(value) = object.remove(from) {
+                        object.insert(to.clone(), value);
+                    }
+                }
+            }
+        }
+        message
+    }
+
+    /// Record a lossiness item
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+
+    /// Record a policy result and act on it
+    fn apply(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+}
+
+/// Substitute `{{name}}` placeholders in a JSON template
+///
+/// A string that is exactly one placeholder is replaced by the variable's
+/// value, keeping its JSON type; an object entry whose placeholder has no
+/// value is omitted. Placeholders inside longer strings are interpolated.
+pub(crate) fn render_template(template: &Value, vars: &Map<String, Value>) -> Value {
+    match template {
+        Value::String(s) => match placeholder(s) {
+            Some(name) => vars.get(name).cloned().unwrap_or(Value::Null),
+            None => Value::String(interpolate(s, vars)),
+        },
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_template(item, vars)).collect()),
+        Value::Object(object) => Value::Object(
+            object.iter()
+                .filter(|(_, value)| {
+                    value.as_str().and_then(placeholder).is_none_or(|name| vars.contains_key(name))
+                })
+                .map(|(key, value)| (key.clone(), render_template(value, vars)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Append text to the first system message, or add it as a new leading
+/// system message
+///
+/// The renderer then places it according to the model's
+/// `system_prompt_location`.
+pub fn append_system_text(messages: &mut Vec<Message>, text: &str) {
+    match messages.iter_mut().find(|m| m.role == MessageRole::System) {
+        Some(system) => match &mut system.content {
+            MessageContent::Text(existing) if existing.trim().is_empty() => *existing = text.to_string(),
+            MessageContent::Text(existing) => {
+                existing.push_str("\n\n");
+                existing.push_str(text);
+            }
+            MessageContent::Parts(parts) => parts.push(ContentPart::text(text)),
+        },
+        None => messages.insert(0, Message::system(text)),
+    }
+}
+
+/// Name of the placeholder if `s` is exactly `{{name}}`
+fn placeholder(s: &str) -> Option<&str> {
+    let name = s.strip_prefix("{{")?.strip_suffix("}}")?;
+    (!name.contains("{{") && !name.contains("}}")).then_some(name.trim())
+}
+
+/// Replace `{{name}}` placeholders inside a string; unknown names become empty
+pub(crate) fn interpolate(template: &str, vars: &Map<String, Value>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else { break };
+        output.push_str(&rest[..start]);
+        match vars.get(rest[start + 2..start + end].trim()) {
+            Some(Value::String(value)) => output.push_str(value),
+            Some(value) => output.push_str(&value.to_string()),
+            None => {}
+        }
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Match a rendered value against a JSON template, recovering its variables
+///
+/// The inverse of [`render_template`]: literals must be equal, placeholders
+/// capture whatever is in their place and an omitted placeholder entry
+/// leaves its variable unset. Fields the template does not mention are
+/// ignored.
+pub(crate) fn match_template(template: &Value, value: &Value) -> Option<Map<String, Value>> {
+    let mut vars = Map::new();
+    match_into(template, value, &mut vars).then_some(vars)
+}
+
+fn match_into(template: &Value, value: &Value, vars: &mut Map<String, Value>) -> bool {
+    match template {
+        Value::String(s) => match placeholder(s) {
+            Some(name) => {
+                vars.insert(name.to_string(), value.clone());
+                true
+            }
+            None => value.as_str().is_some_and(|value| match_interpolated(s, value, vars)),
+        },
+        Value::Array(items) => value.as_array().is_some_and(|values| {
+            values.len() == items.len() && items.iter().zip(values).all(|(item, value)| match_into(item, value, vars))
+        }),
+        Value::Object(object) => value.as_object().is_some_and(|fields| {
+            object.iter().all(|(key, item)| match fields.get(key) {
+                Some(value) => match_into(item, value, vars),
+                None => item.as_str().and_then(placeholder).is_some(),
+            })
+        }),
+        other => other == value,
+    }
+}
+
+/// Match a string against an interpolated template such as `data:{{mime_type}};base64,{{data}}`
+///
+/// Each placeholder captures up to the next occurrence of the literal text
+/// that follows it.
+fn match_interpolated(template: &str, value: &str, vars: &mut Map<String, Value>) -> bool {
+    let mut template = template;
+    let mut value = value;
+    let mut captured = Vec::new();
+    while let Some(start) = template.find("{{") {
+        let Some(end) = template[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        let Some(rest) = value.strip_prefix(&template[..start]) else {
+            return false;
+        };
+        let name = template[start + 2..end].trim();
+        template = &template[end + 2..];
+
+        let literal = &template[..template.find("{{").unwrap_or(template.len())];
+        let length = match literal {
+            "" if !template.is_empty() => return false,
+            "" => rest.len(),
+            literal => match rest.find(literal) {
+                Some(length) => length,
+                None => return false,
+            },
+        };
+        captured.push((name, &rest[..length]));
+        value = &rest[length..];
+    }
+    if template != value {
+        return false;
+    }
+    for (name, text) in captured {
+        vars.insert(name.to_string(), Value::String(text.to_string()));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_prompt_location_parsing() {
+        assert_eq!(SystemPromptLocation::parse("first_message"), SystemPromptLocation::FirstMessage);
+        assert_eq!(SystemPromptLocation::parse("system_parameter"), SystemPromptLocation::TopLevelField);
+        assert_eq!(SystemPromptLocation::parse("prepend_to_first_user"), SystemPromptLocation::PrependToFirstUser);
+        assert_eq!(SystemPromptLocation::parse("message_role"), SystemPromptLocation::InPlace);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = json!({
+            "type": "image_url",
+            "image_url": { "url": "{{url}}", "detail": "{{detail}}" },
+            "label": "image at {{url}}",
+        });
+        let vars = Map::from_iter([("url".to_string(), json!("https://x/y.png"))]);
+
+        assert_eq!(render_template(&template, &vars), json!({
+            "type": "image_url",
+            "image_url": { "url": "https://x/y.png" },
+            "label": "image at https://x/y.png",
+        }));
+        assert_eq!(interpolate("{{role}}: {{text}}", &Map::from_iter([("role".to_string(), json!("user"))])), "user: ");
+    }
+
+    #[test]
+    fn test_match_template() {
+        let template = json!({
+            "type": "image_url",
+            "image_url": { "url": "data:{{mime_type}};base64,{{data}}", "detail": "{{detail}}" },
+        });
+        let block = json!({"type": "image_url", "image_url": {"url": "data:image/png;base64,aGVsbG8="}});
+
+        let vars = match_template(&template, &block).unwrap();
+        assert_eq!(vars.get("mime_type"), Some(&json!("image/png")));
+        assert_eq!(vars.get("data"), Some(&json!("aGVsbG8=")));
+        assert!(!vars.contains_key("detail"));
+
+        // Literals must match
+        assert!(match_template(&template, &json!({"type": "text", "text": "hi"})).is_none());
+        assert!(match_template(&template, &json!({"type": "image_url", "image_url": {"url": "https://x/y.png"}})).is_none());
+    }
+}
@@ -0,0 +1,524 @@
+//! Reverse translation of provider-native requests
+//!
+//! Reads a provider's native request back into uniform types: the
+//! conversation is parsed with the same [`MessageRendering`] template used
+//! to render it, and tools, tool choice and response format are recognized
+//! in the OpenAI and Anthropic native shapes as well as the uniform one.
+//! Anything without a uniform counterpart is reported as dropped.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Map, Value};
+
+use super::lossiness::LossinessTracker;
+use super::mapper::JSONPathMapper;
+use super::rendering::{match_template, SystemPromptLocation};
+use super::TranslationContext;
+use crate::types::{
+    AdvancedParams, ContentBlockTemplates, ContentPart, Limits, MediaConfig, MediaSource, Message,
+    MessageContent, MessageRendering, MessageRole, ReasoningBlock, ResponseFormat, SamplingParams,
+    Tool, ToolCall, ToolChoice,
+};
+use crate::Result;
+
+/// Parses a provider's rendered conversation back into uniform messages
+pub struct MessageParser<'a> {
+    rendering: MessageRendering,
+    blocks: ContentBlockTemplates,
+    location: SystemPromptLocation,
+    single_text: bool,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> MessageParser<'a> {
+    /// Create a parser for the context's model
+    pub fn new(context: &TranslationContext, tracker: &'a Arc<Mutex<LossinessTracker>>) -> Self {
+        let model_spec = &context.model_spec;
+        let rendering = model_spec.message_rendering.clone().unwrap_or_default();
+        let format = rendering.format.unwrap_or_default();
+
+        Self {
+            blocks: rendering.blocks.clone().unwrap_or_else(|| format.default_blocks()),
+            location: SystemPromptLocation::parse(context.system_prompt_location()),
+            single_text: !model_spec.input_modes.messages && model_spec.input_modes.single_text,
+            rendering,
+            tracker,
+        }
+    }
+
+    /// Take the conversation fields out of `request` and parse them
+    pub fn parse(&self, request: &mut Map<String, Value>) -> Vec<Message> {
+        if self.single_text {
+            // Turns flattened into one prompt cannot be told apart again
+            return match request.remove(&self.rendering.prompt_field) {
+                Some(Value::String(prompt)) => vec![Message::user(prompt)],
+                Some(other) => {
+                    self.drop_value(&self.rendering.prompt_field, "Prompt is not a string", other);
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+        }
+
+        let mut messages = Vec::new();
+        if self.location == SystemPromptLocation::TopLevelField {
+            if let Some(system) = request.remove(&self.rendering.system_field) {
+                match self.parse_system(&system) {
+                    Some(text) => messages.push(Message::system(text)),
+                    None => self.drop_value(&self.rendering.system_field, "System prompt does not match the rendering template", system),
+                }
+            }
+        }
+
+        match request.remove(&self.rendering.messages_field) {
+            Some(Value::Array(rendered)) => {
+                for (index, message) in rendered.into_iter().enumerate() {
+                    messages.extend(self.parse_message(index, message));
+                }
+            }
+            Some(other) => self.drop_value(&self.rendering.messages_field, "Conversation is not an array", other),
+            None => {}
+        }
+        messages
+    }
+
+    /// Text of the top-level system value
+    fn parse_system(&self, system: &Value) -> Option<String> {
+        if let Some(template) = &self.rendering.system_template {
+            return match_template(template, system)?.get("text")?.as_str().map(str::to_string);
+        }
+        match system {
+            Value::String(text) => Some(text.clone()),
+            Value::Array(blocks) => {
+                let texts = blocks.iter()
+                    .map(|block| self.blocks.text.as_ref()
+                        .and_then(|template| match_template(template, block))
+                        .and_then(|vars| vars.get("text")?.as_str().map(str::to_string)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(texts.join("\n\n"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse one rendered message; a turn carrying tool results yields one
+    /// tool message per result
+    fn parse_message(&self, index: usize, message: Value) -> Vec<Message> {
+        let path = format!("messages[{}]", index);
+        let Value::Object(mut fields) = message else {
+            self.drop_value(&path, "Message is not an object", message);
+            return Vec::new();
+        };
+
+        let role = fields.get(&self.rendering.role_field).and_then(Value::as_str).and_then(|role| self.parse_role(role));
+        let Some(role) = role else {
+            self.drop_value(&path, "Message role has no uniform equivalent", Value::Object(fields));
+            return Vec::new();
+        };
+        fields.remove(&self.rendering.role_field);
+        let content = fields.remove(&self.rendering.content_field).unwrap_or(Value::Null);
+
+        let mut parsed = ParsedContent::default();
+        let content = match content {
+            Value::Null => MessageContent::Text(String::new()),
+            Value::String(text) => MessageContent::Text(text),
+            Value::Array(blocks) => {
+                for (block_index, block) in blocks.into_iter().enumerate() {
+                    self.parse_block(&format!("{}.content[{}]", path, block_index), block, &mut parsed);
+                }
+                collapse(std::mem::take(&mut parsed.parts))
+            }
+            other => {
+                self.drop_value(&format!("{}.content", path), "Content is neither text nor blocks", other);
+                MessageContent::Text(String::new())
+            }
+        };
+
+        let mut message = match role {
+            MessageRole::Tool => Message::tool(
+                fields.remove("tool_call_id").and_then(|id| id.as_str().map(str::to_string)).unwrap_or_default(),
+                content,
+            ),
+            MessageRole::System => Message::system(content),
+            MessageRole::User => Message::user(content),
+            MessageRole::Assistant => Message::assistant(content),
+        };
+
+        let tool_calls_field = self.blocks.tool_calls_field.as_deref();
+        if let Some((field, tool_calls)) = tool_calls_field.and_then(|field| Some((field, fields.remove(field)?))) {
+            match tool_calls.as_array().map(|calls| calls.iter().map(|call| self.parse_tool_call(call)).collect::<Option<Vec<_>>>()) {
+                Some(Some(calls)) => parsed.tool_calls.extend(calls),
+                _ => self.drop_value(&format!("{}.{}", path, field), "Tool calls do not match the tool call template", tool_calls),
+            }
+        }
+        if let Some(name) = fields.remove("name").and_then(|name| name.as_str().map(str::to_string)) {
+            message.name = Some(name);
+        }
+        for (field, value) in fields {
+            self.drop_value(&format!("{}.{}", path, field), "Message field has no uniform equivalent", value);
+        }
+
+        if !parsed.tool_calls.is_empty() {
+            message.tool_calls = Some(parsed.tool_calls);
+        }
+        if !parsed.reasoning.is_empty() {
+            message.reasoning = Some(parsed.reasoning);
+        }
+
+        // Tool results precede the rest of the turn they were sent in
+        let keep_message = parsed.tool_results.is_empty() || !message.content.is_blank();
+        let mut messages = parsed.tool_results;
+        if keep_message {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// Uniform role of a provider role, undoing role renames
+    fn parse_role(&self, role: &str) -> Option<MessageRole> {
+        let role = self.rendering.role_map.iter()
+            .find(|(_, mapped)| mapped.as_str() == role)
+            .map(|(uniform, _)| uniform.as_str())
+            .unwrap_or(role);
+        match role {
+            "developer" => Some(MessageRole::System),
+            role => serde_json::from_value(Value::String(role.to_string())).ok(),
+        }
+    }
+
+    /// Parse one content block into the message being built
+    fn parse_block(&self, path: &str, block: Value, parsed: &mut ParsedContent) {
+        if self.blocks.tool_calls_field.is_none() {
+            if let Some(call) = self.parse_tool_call(&block) {
+                parsed.tool_calls.push(call);
+                return;
+            }
+        }
+        if let Some(result) = self.parse_tool_result(path, &block) {
+            parsed.tool_results.push(result);
+            return;
+        }
+
+        let templates = [
+            (&self.blocks.thinking, BlockKind::Thinking),
+            (&self.blocks.redacted_thinking, BlockKind::RedactedThinking),
+            (&self.blocks.text, BlockKind::Text),
+            (&self.blocks.image_base64, BlockKind::ImageBase64),
+            (&self.blocks.image_url, BlockKind::ImageUrl),
+            (&self.blocks.audio_base64, BlockKind::AudioBase64),
+            (&self.blocks.audio_url, BlockKind::AudioUrl),
+            (&self.blocks.file, BlockKind::File),
+        ];
+        for (template, kind) in templates {
+            let Some(vars) = template.as_ref().and_then(|template| match_template(template, &block)) else {
+                continue;
+            };
+            match kind {
+                BlockKind::Thinking => {
+                    if let Some(text) = string_var(&vars, "text") {
+                        parsed.reasoning.push(ReasoningBlock {
+                            text: Some(text),
+                            signature: string_var(&vars, "signature"),
+                            redacted: None,
+                        });
+                        return;
+                    }
+                }
+                BlockKind::RedactedThinking => {
+                    if let Some(data) = string_var(&vars, "data") {
+                        parsed.reasoning.push(ReasoningBlock { text: None, signature: None, redacted: Some(data) });
+                        return;
+                    }
+                }
+                kind => {
+                    if let Some(part) = kind.content_part(&vars) {
+                        parsed.parts.push(part);
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.drop_value(path, "Content block does not match any block template", block);
+    }
+
+    /// Parse a tool call with the tool call template; JSON-encoded
+    /// arguments are decoded
+    fn parse_tool_call(&self, call: &Value) -> Option<ToolCall> {
+        let vars = match_template(self.blocks.tool_call.as_ref()?, call)?;
+        let arguments = match (vars.get("arguments"), vars.get("arguments_json")) {
+            (Some(arguments), _) => arguments.clone(),
+            (None, Some(Value::String(args))) => {
+                serde_json::from_str(args).unwrap_or_else(|_| Value::String(args.clone()))
+            }
+            (None, Some(other)) => other.clone(),
+            (None, None) => Value::Object(Map::new()),
+        };
+        Some(ToolCall {
+            name: string_var(&vars, "name")?,
+            arguments,
+            id: string_var(&vars, "id"),
+        })
+    }
+
+    /// Parse a tool result block into a tool message
+    ///
+    /// Tool results rendered as blocks of a turn (a tool result template
+    /// whose content is a single block) are matched against that block.
+    fn parse_tool_result(&self, path: &str, block: &Value) -> Option<Message> {
+        let template = self.blocks.tool_result.as_ref()?.get("content")?.as_array()?;
+        let [template] = template.as_slice() else {
+            return None;
+        };
+        let vars = match_template(template, block)?;
+        let tool_call_id = string_var(&vars, "tool_call_id")?;
+        let content = match vars.get("content") {
+            None | Some(Value::Null) => MessageContent::Text(String::new()),
+            Some(Value::String(text)) => MessageContent::Text(text.clone()),
+            Some(Value::Array(blocks)) => {
+                let mut parsed = ParsedContent::default();
+                for (index, block) in blocks.iter().enumerate() {
+                    self.parse_block(&format!("{}.content[{}]", path, index), block.clone(), &mut parsed);
+                }
+                collapse(parsed.parts)
+            }
+            Some(_) => return None,
+        };
+        Some(Message::tool(tool_call_id, content))
+    }
+
+    /// Record a request value that could not be carried over
+    fn drop_value(&self, path: &str, message: &str, value: Value) {
+        if let Ok(mut tracker) = self.tracker.lock() {
+            tracker.add_dropped(path, message, Some(value));
+        }
+    }
+}
+
+/// Pieces of a message collected from its content blocks
+#[derive(Default)]
+struct ParsedContent {
+    parts: Vec<ContentPart>,
+    tool_calls: Vec<ToolCall>,
+    tool_results: Vec<Message>,
+    reasoning: Vec<ReasoningBlock>,
+}
+
+/// Block template a content block matched
+#[derive(Clone, Copy)]
+enum BlockKind {
+    Thinking,
+    RedactedThinking,
+    Text,
+    ImageBase64,
+    ImageUrl,
+    AudioBase64,
+    AudioUrl,
+    File,
+}
+
+impl BlockKind {
+    /// Content part from the matched template variables
+    fn content_part(self, vars: &Map<String, Value>) -> Option<ContentPart> {
+        match self {
+            BlockKind::Text => Some(ContentPart::text(string_var(vars, "text")?)),
+            BlockKind::ImageBase64 => {
+                let source = match (string_var(vars, "data"), string_var(vars, "mime_type")) {
+                    (Some(data), Some(mime_type)) => MediaSource::Base64 { data, mime_type },
+                    _ => parse_data_url(&string_var(vars, "data_url")?)?,
+                };
+                Some(ContentPart::Image { source, detail: string_var(vars, "detail") })
+            }
+            BlockKind::ImageUrl => {
+                let url = string_var(vars, "url")?;
+                let source = parse_data_url(&url).unwrap_or(MediaSource::Url { url });
+                Some(ContentPart::Image { source, detail: string_var(vars, "detail") })
+            }
+            BlockKind::AudioBase64 => {
+                let mime_type = string_var(vars, "mime_type").or_else(|| {
+                    string_var(vars, "format").map(|format| match format.as_str() {
+                        "mp3" => "audio/mpeg".to_string(),
+                        format => format!("audio/{}", format),
+                    })
+                })?;
+                Some(ContentPart::Audio { source: MediaSource::Base64 { data: string_var(vars, "data")?, mime_type } })
+            }
+            BlockKind::AudioUrl => Some(ContentPart::Audio { source: MediaSource::Url { url: string_var(vars, "url")? } }),
+            BlockKind::File => Some(ContentPart::File {
+                file_id: string_var(vars, "file_id")?,
+                filename: string_var(vars, "filename"),
+                mime_type: string_var(vars, "mime_type"),
+            }),
+            BlockKind::Thinking | BlockKind::RedactedThinking => None,
+        }
+    }
+}
+
+/// Parse provider tool definitions
+///
+/// Accepts the uniform shape, OpenAI `{"type": "function", "function": {...}}`
+/// and Anthropic `{"name", "input_schema"}` definitions; returns the tools
+/// and the indices of definitions that were not recognized.
+pub fn parse_tools(tools: &[Value]) -> (Vec<Tool>, Vec<usize>) {
+    let mut parsed = Vec::with_capacity(tools.len());
+    let mut unknown = Vec::new();
+    for (index, tool) in tools.iter().enumerate() {
+        let definition = tool.get("function").filter(|_| tool.get("type").and_then(Value::as_str) == Some("function"));
+        let (definition, schema_field) = match definition {
+            Some(function) => (function, "parameters"),
+            None if tool.get("input_schema").is_some() => (tool, "input_schema"),
+            None => (tool, "json_schema"),
+        };
+        let name = definition.get("name").and_then(Value::as_str);
+        match (name, definition.get(schema_field)) {
+            (Some(name), Some(schema)) => parsed.push(Tool {
+                name: name.to_string(),
+                description: definition.get("description").and_then(Value::as_str).map(str::to_string),
+                json_schema: schema.clone(),
+            }),
+            // Provider-hosted tools carry no schema
+            _ => unknown.push(index),
+        }
+    }
+    (parsed, unknown)
+}
+
+/// Parse a provider tool choice; `None` if it has no uniform equivalent
+pub fn parse_tool_choice(choice: &Value) -> Option<ToolChoice> {
+    let kind = choice.as_str().or_else(|| choice.get("type").and_then(Value::as_str));
+    match kind {
+        Some("auto") => Some(ToolChoice::Auto),
+        Some("required") | Some("any") => Some(ToolChoice::Required),
+        Some("function") => choice.get("function")?.get("name")?.as_str()
+            .map(|name| ToolChoice::Specific { name: name.to_string() }),
+        Some("tool") | None => choice.get("name")?.as_str()
+            .map(|name| ToolChoice::Specific { name: name.to_string() }),
+        _ => None,
+    }
+}
+
+/// Parse a provider response format; `None` if it is not recognized
+///
+/// Accepts OpenAI `{"type": "json_object"}` and `{"type": "json_schema",
+/// "json_schema": {...}}` as well as the uniform `{"json_schema": ...}` shape.
+pub fn parse_response_format(format: &Value) -> Option<ResponseFormat> {
+    match format.get("type").and_then(Value::as_str) {
+        Some("text") => Some(ResponseFormat::Text),
+        Some("json_object") => Some(ResponseFormat::JsonObject),
+        Some("json_schema") | None => {
+            let json_schema = format.get("json_schema")?.clone();
+            let strict = format.get("strict").or_else(|| json_schema.get("strict")).and_then(Value::as_bool);
+            Some(ResponseFormat::JsonSchema { json_schema, strict })
+        }
+        Some(_) => None,
+    }
+}
+
+/// Content from parsed parts; a lone text part becomes plain text
+fn collapse(parts: Vec<ContentPart>) -> MessageContent {
+    match <[ContentPart; 1]>::try_from(parts) {
+        Ok([ContentPart::Text { text }]) => MessageContent::Text(text),
+        Ok(part) => MessageContent::Parts(part.into()),
+        Err(parts) if parts.is_empty() => MessageContent::Text(String::new()),
+        Err(parts) => MessageContent::Parts(parts),
+    }
+}
+
+/// Media source of a `data:<mime>;base64,<data>` URL
+fn parse_data_url(url: &str) -> Option<MediaSource> {
+    let (mime_type, data) = url.strip_prefix("data:")?.split_once(";base64,")?;
+    Some(MediaSource::Base64 { data: data.to_string(), mime_type: mime_type.to_string() })
+}
+
+fn string_var(vars: &Map<String, Value>, name: &str) -> Option<String> {
+    vars.get(name)?.as_str().map(str::to_string)
+}
+
+/// Request fields `translate` sets directly, as `(uniform path, request path)`
+pub const DIRECT_FIELDS: &[(&str, &str)] = &[
+    ("$.sampling.temperature", "$.temperature"),
+    ("$.sampling.top_p", "$.top_p"),
+    ("$.sampling.top_k", "$.top_k"),
+    ("$.sampling.frequency_penalty", "$.frequency_penalty"),
+    ("$.sampling.presence_penalty", "$.presence_penalty"),
+    ("$.limits.max_output_tokens", "$.max_tokens"),
+    ("$.advanced.thinking", "$.thinking"),
+    ("$.advanced.min_thinking_tokens", "$.min_thinking_tokens"),
+    ("$.advanced.reasoning_effort", "$.reasoning_effort"),
+    ("$.advanced.seed", "$.seed"),
+    ("$.advanced.reasoning_mode", "$.reasoning_mode"),
+    ("$.advanced.thinking_budget", "$.thinking_budget"),
+    ("$.advanced.verbosity", "$.verbosity"),
+];
+
+/// Read uniform fields out of a provider request
+///
+/// Each `(uniform path, request path)` pair is read from the request and
+/// placed at its uniform path unless an earlier pair already set it. Fields
+/// that were read are removed from the request; values the uniform types
+/// cannot hold are left in place.
+pub fn read_fields<'p>(
+    mapper: &mut JSONPathMapper,
+    request: &mut Map<String, Value>,
+    paths: impl IntoIterator<Item = (&'p str, &'p str)>,
+) -> Result<Map<String, Value>> {
+    let source = Value::Object(request.clone());
+    let mut uniform = Value::Object(Map::new());
+    let mut read = Vec::new();
+
+    for (uniform_path, request_path) in paths {
+        let value = mapper.map_value(request_path, &source)?;
+        if value.is_null() || !accepts(uniform_path, &value) {
+            continue;
+        }
+        read.push(request_path);
+        if mapper.map_value(uniform_path, &uniform)?.is_null() {
+            mapper.set_value_at_path(uniform_path, value, &mut uniform)?;
+        }
+    }
+
+    for request_path in read {
+        remove_at_path(request, request_path);
+    }
+    Ok(match uniform {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    })
+}
+
+/// Whether the uniform type at `path` accepts `value`
+fn accepts(path: &str, value: &Value) -> bool {
+    let Some((section, field)) = path.trim_start_matches("$.").split_once('.') else {
+        return true;
+    };
+    let probe = Value::Object(Map::from_iter([(field.to_string(), value.clone())]));
+    match section {
+        _ if field.contains('.') => true,
+        "sampling" => serde_json::from_value::<SamplingParams>(probe).is_ok(),
+        "limits" => serde_json::from_value::<Limits>(probe).is_ok(),
+        "advanced" => serde_json::from_value::<AdvancedParams>(probe).is_ok(),
+        "media" => serde_json::from_value::<MediaConfig>(probe).is_ok(),
+        _ => true,
+    }
+}
+
+/// Remove a dot-notation path, pruning parents left empty
+fn remove_at_path(object: &mut Map<String, Value>, path: &str) {
+    let path = path.trim_start_matches("$.");
+    match path.split_once('.') {
+        None => {
+            object.remove(path);
+        }
+        Some((parent, rest)) => {
+            if let Some(Value::Object(child)) = object.get_mut(parent) {
+                remove_at_path(child, rest);
+                if child.is_empty() {
+                    object.remove(parent);
+                }
+            }
+        }
+    }
+}
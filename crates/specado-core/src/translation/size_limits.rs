@@ -0,0 +1,343 @@
+//! System prompt and tool schema size limits
+//!
+//! `constraints.limits` caps the bytes of a model's system prompt and of its
+//! tool definitions. In coerce mode an oversized system prompt is cut at the
+//! limit, and tool schemas are minified: descriptions are stripped and
+//! `$defs` references inlined, then the tools' own descriptions go if that
+//! is not enough.
+//!
+//! The system prompt is measured as the caller wrote it, before RAG
+//! grounding and JSON mode instructions are added to it.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::{ConstraintLimits, Message, MessageContent, MessageRole, Tool};
+use crate::{LossinessItem, Result};
+
+/// Fits the system prompt and tool definitions into the model's limits
+pub struct SizeLimits<'a> {
+    limits: &'a ConstraintLimits,
+    provider_name: &'a str,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> SizeLimits<'a> {
+    /// Create a checker for the context's model
+    pub fn new(
+        context: &'a TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        Self {
+            limits: &context.model_spec.constraints.limits,
+            provider_name: context.provider_name(),
+            policy,
+            tracker,
+        }
+    }
+
+    /// Messages with the system prompt within `max_system_prompt_bytes`
+    pub fn system_prompt(&self, messages: Vec<Message>) -> Result<Vec<Message>> {
+        let max_bytes = self.limits.max_system_prompt_bytes as usize;
+        let size = system_prompt_bytes(&messages);
+        if size <= max_bytes {
+            return Ok(messages);
+        }
+
+        let trimmed = trim_system_prompt(&messages, max_bytes);
+        let coerce = self.apply_policy(self.policy.evaluate_size_limit(
+            "messages.system",
+            json!(system_prompt(&messages)),
+            json!(system_prompt(&trimmed)),
+            size,
+            max_bytes,
+            self.provider_name,
+        ))?;
+        Ok(if coerce { trimmed } else { messages })
+    }
+
+    /// Tool definitions within `max_tool_schema_bytes`
+    pub fn tools(&self, tools: &[Tool]) -> Result<Vec<Tool>> {
+        let max_bytes = self.limits.max_tool_schema_bytes as usize;
+        let size = tool_schema_bytes(tools);
+        if size <= max_bytes {
+            return Ok(tools.to_vec());
+        }
+
+        let mut minified: Vec<Tool> = tools.iter()
+            .map(|tool| Tool { json_schema: minify_schema(&tool.json_schema), ..tool.clone() })
+            .collect();
+        if tool_schema_bytes(&minified) > max_bytes {
+            for tool in &mut minified {
+                tool.description = None;
+            }
+        }
+
+        let coerce = self.apply_policy(self.policy.evaluate_size_limit(
+            "tools",
+            json!(tools),
+            json!(minified),
+            size,
+            max_bytes,
+            self.provider_name,
+        ))?;
+        if coerce && tool_schema_bytes(&minified) > max_bytes {
+            log::warn!(
+                "Minified tool definitions still exceed {}'s limit of {} bytes",
+                self.provider_name,
+                max_bytes
+            );
+        }
+        Ok(if coerce { minified } else { tools.to_vec() })
+    }
+
+    /// Record a policy result and act on it; true when the reduced value
+    /// should be sent
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<bool> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(false)
+            }
+            StrictnessAction::Proceed => Ok(false),
+            StrictnessAction::Coerce { .. } => Ok(true),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Bytes of the system messages' text
+pub fn system_prompt_bytes(messages: &[Message]) -> usize {
+    messages.iter()
+        .filter(|m| m.role == MessageRole::System)
+        .map(|m| m.content.text().len())
+        .sum()
+}
+
+/// Bytes of the serialized tool definitions
+pub fn tool_schema_bytes(tools: &[Tool]) -> usize {
+    serde_json::to_string(tools).map_or(0, |tools| tools.len())
+}
+
+fn system_prompt(messages: &[Message]) -> String {
+    messages.iter()
+        .filter(|m| m.role == MessageRole::System)
+        .map(|m| m.content.text())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Cut the system messages at `max_bytes`, keeping their start; system
+/// messages left empty are removed
+fn trim_system_prompt(messages: &[Message], max_bytes: usize) -> Vec<Message> {
+    let mut remaining = max_bytes;
+    messages.iter()
+        .filter_map(|message| {
+            if message.role != MessageRole::System {
+                return Some(message.clone());
+            }
+            let text = message.content.text();
+            let mut end = remaining.min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            remaining -= end;
+            (end > 0).then(|| Message {
+                content: MessageContent::Text(text[..end].to_string()),
+                ..message.clone()
+            })
+        })
+        .collect()
+}
+
+/// Keywords whose value is a schema or an array of schemas
+const SUBSCHEMA_KEYWORDS: &[&str] = &[
+    "items", "additionalItems", "prefixItems", "contains", "additionalProperties", "propertyNames",
+    "unevaluatedItems", "unevaluatedProperties", "not", "if", "then", "else", "allOf", "anyOf", "oneOf",
+];
+
+/// Keywords whose value maps names to schemas
+const SCHEMA_MAP_KEYWORDS: &[&str] = &["properties", "patternProperties", "dependentSchemas", "$defs", "definitions"];
+
+/// A JSON Schema without descriptions and with its `$defs` inlined
+///
+/// Only `description` keywords go; values such as `default`, `enum` and
+/// `examples` are kept as they are. Recursive definitions cannot be
+/// inlined, so such schemas only lose their descriptions.
+pub fn minify_schema(schema: &Value) -> Value {
+    let definitions = schema.get("$defs")
+        .or_else(|| schema.get("definitions"))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut recursive = false;
+    let mut inlined = minify(schema, Some(&definitions), &mut Vec::new(), &mut recursive);
+    if recursive {
+        return minify(schema, None, &mut Vec::new(), &mut recursive);
+    }
+
+    // Every reference to the root definitions has been inlined
+    if let Value::Object(object) = &mut inlined {
+        object.remove("$defs");
+        object.remove("definitions");
+    }
+    inlined
+}
+
+/// Strip the descriptions of a schema and its subschemas, and inline
+/// references to `definitions` when given
+fn minify(schema: &Value, definitions: Option<&Map<String, Value>>, expanding: &mut Vec<String>, recursive: &mut bool) -> Value {
+    let Value::Object(object) = schema else {
+        return schema.clone();
+    };
+
+    if let Some(definitions) = definitions {
+        let reference = object.get("$ref").and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix("#/$defs/").or_else(|| r.strip_prefix("#/definitions/")));
+        if let Some((name, definition)) = reference.and_then(|name| definitions.get_key_value(name)) {
+            if expanding.contains(name) {
+                *recursive = true;
+                return schema.clone();
+            }
+            expanding.push(name.clone());
+            let inlined = minify(definition, Some(definitions), expanding, recursive);
+            expanding.pop();
+            return inlined;
+        }
+    }
+
+    let mut minify_subschema = |value: &Value| minify(value, definitions, expanding, recursive);
+    let minified = object.iter()
+        .filter(|(key, _)| key.as_str() != "description")
+        .map(|(key, value)| {
+            let keyword = key.as_str();
+            let value = match value {
+                Value::Array(schemas) if SUBSCHEMA_KEYWORDS.contains(&keyword) => {
+                    Value::Array(schemas.iter().map(&mut minify_subschema).collect())
+                }
+                schema if SUBSCHEMA_KEYWORDS.contains(&keyword) => minify_subschema(schema),
+                // Property and definition names are not schema keywords
+                Value::Object(schemas) if SCHEMA_MAP_KEYWORDS.contains(&keyword) => Value::Object(
+                    schemas.iter().map(|(name, schema)| (name.clone(), minify_subschema(schema))).collect(),
+                ),
+                // Anything else, such as `default` or `enum`, is data
+                _ => value.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect();
+    Value::Object(minified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_schema() {
+        let schema = json!({
+            "type": "object",
+            "description": "Weather query",
+            "properties": {
+                "description": {"type": "string", "description": "Free text"},
+                "location": {"$ref": "#/$defs/location"}
+            },
+            "$defs": {
+                "location": {"type": "string", "description": "City name"}
+            }
+        });
+        assert_eq!(minify_schema(&schema), json!({
+            "type": "object",
+            "properties": {
+                "description": {"type": "string"},
+                "location": {"type": "string"}
+            }
+        }));
+
+        // Data values keep their description keys, and nested definitions
+        // that were never inlined stay
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "item": {
+                    "type": "object",
+                    "default": {"description": "Widget"},
+                    "enum": [{"description": "Widget"}],
+                    "examples": [{"description": "Gadget"}],
+                    "properties": {"part": {"$ref": "#/properties/item/$defs/part"}},
+                    "$defs": {"part": {"type": "string", "description": "Part name"}}
+                }
+            }
+        });
+        assert_eq!(minify_schema(&schema), json!({
+            "type": "object",
+            "properties": {
+                "item": {
+                    "type": "object",
+                    "default": {"description": "Widget"},
+                    "enum": [{"description": "Widget"}],
+                    "examples": [{"description": "Gadget"}],
+                    "properties": {"part": {"$ref": "#/properties/item/$defs/part"}},
+                    "$defs": {"part": {"type": "string"}}
+                }
+            }
+        }));
+
+        // Recursive definitions keep their references
+        let schema = json!({
+            "$ref": "#/$defs/node",
+            "$defs": {
+                "node": {"type": "object", "description": "Tree node", "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/node"}}}}
+            }
+        });
+        assert_eq!(minify_schema(&schema), json!({
+            "$ref": "#/$defs/node",
+            "$defs": {
+                "node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/node"}}}}
+            }
+        }));
+    }
+
+    #[test]
+    fn test_trim_system_prompt() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::system("Answer in French."),
+            Message::user("Hello"),
+        ];
+        let trimmed = trim_system_prompt(&messages, 12);
+        assert_eq!(trimmed.len(), 3);
+        assert_eq!(trimmed[0].content, "Be brief.");
+        assert_eq!(trimmed[1].content, "Ans");
+        assert_eq!(system_prompt_bytes(&trimmed), 12);
+
+        let trimmed = trim_system_prompt(&messages, 5);
+        assert_eq!(trimmed.len(), 2);
+        assert_eq!(trimmed[1].content, "Hello");
+
+        // Cuts fall on character boundaries
+        let trimmed = trim_system_prompt(&[Message::system("héllo")], 2);
+        assert_eq!(trimmed[0].content, "h");
+    }
+}
@@ -256,6 +256,237 @@ impl StrictnessPolicy {
         }
     }
 
+    /// Evaluate policy for an array with more items than the provider accepts
+    ///
+    /// Like an unsupported feature, the surplus items fail strict mode and
+    /// are dropped otherwise.
+    pub fn evaluate_item_limit(
+        &self,
+        path: &str,
+        original_value: Value,
+        max_items: usize,
+        provider_name: &str,
+    ) -> PolicyResult {
+        let effective_mode = self.policy_overrides.get(path).unwrap_or(&self.mode);
+
+        let Some(items) = original_value.as_array().filter(|items| items.len() > max_items) else {
+            return PolicyResult {
+                action: StrictnessAction::Proceed,
+                lossiness_item: None,
+            };
+        };
+        let truncated = Value::Array(items[..max_items].to_vec());
+        let message = format!(
+            "{} items exceed {}'s limit of {}",
+            items.len(),
+            provider_name,
+            max_items
+        );
+
+        match effective_mode {
+            StrictMode::Strict => PolicyResult {
+                action: StrictnessAction::Fail {
+                    error: Error::StrictnessViolation {
+                        message: format!("{} at '{}'", message, path),
+                        mode: *effective_mode,
+                        severity: Severity::Error,
+                    },
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message,
+                    severity: Severity::Error,
+                    before: Some(original_value),
+                    after: None,
+                }),
+            },
+            StrictMode::Warn | StrictMode::Coerce => PolicyResult {
+                action: StrictnessAction::Coerce {
+                    adjusted_value: truncated.clone(),
+                    reason: message.clone(),
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message: format!("{}; kept the first {}", message, max_items),
+                    severity: Severity::Warning,
+                    before: Some(original_value),
+                    after: Some(truncated),
+                }),
+            },
+        }
+    }
+
+    /// Evaluate policy for prompt content cut to fit its token budget
+    ///
+    /// Like surplus items, the content fails strict mode and is cut
+    /// otherwise; `truncated_value` is what remains of it, if anything.
+    pub fn evaluate_prompt_truncation(
+        &self,
+        path: &str,
+        original_value: Value,
+        truncated_value: Option<Value>,
+        message: &str,
+    ) -> PolicyResult {
+        let effective_mode = self.policy_overrides.get(path).unwrap_or(&self.mode);
+
+        match effective_mode {
+            StrictMode::Strict => PolicyResult {
+                action: StrictnessAction::Fail {
+                    error: Error::StrictnessViolation {
+                        message: format!("{} at '{}'", message, path),
+                        mode: *effective_mode,
+                        severity: Severity::Error,
+                    },
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message: message.to_string(),
+                    severity: Severity::Error,
+                    before: Some(original_value),
+                    after: None,
+                }),
+            },
+            StrictMode::Warn | StrictMode::Coerce => PolicyResult {
+                action: StrictnessAction::Coerce {
+                    adjusted_value: truncated_value.clone().unwrap_or(Value::Null),
+                    reason: message.to_string(),
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message: message.to_string(),
+                    severity: Severity::Warning,
+                    before: Some(original_value),
+                    after: truncated_value,
+                }),
+            },
+        }
+    }
+
+    /// Evaluate policy for a value the provider cannot take as given
+    ///
+    /// Strict mode fails; otherwise `replacement` is sent in its place and
+    /// the original value is reported as dropped.
+    pub fn evaluate_value_replacement(
+        &self,
+        path: &str,
+        original_value: Value,
+        replacement: Value,
+        message: &str,
+    ) -> PolicyResult {
+        let effective_mode = self.policy_overrides.get(path).unwrap_or(&self.mode);
+
+        match effective_mode {
+            StrictMode::Strict => PolicyResult {
+                action: StrictnessAction::Fail {
+                    error: Error::StrictnessViolation {
+                        message: format!("{} at '{}'", message, path),
+                        mode: *effective_mode,
+                        severity: Severity::Error,
+                    },
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message: message.to_string(),
+                    severity: Severity::Error,
+                    before: Some(original_value),
+                    after: None,
+                }),
+            },
+            StrictMode::Warn | StrictMode::Coerce => PolicyResult {
+                action: StrictnessAction::Coerce {
+                    adjusted_value: replacement.clone(),
+                    reason: message.to_string(),
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Drop,
+                    path: path.to_string(),
+                    message: message.to_string(),
+                    severity: Severity::Warning,
+                    before: Some(original_value),
+                    after: Some(replacement),
+                }),
+            },
+        }
+    }
+
+    /// Evaluate policy for a value larger than the provider accepts
+    ///
+    /// Strict mode fails, warn mode sends the value unchanged, and coerce
+    /// mode sends the caller's reduced value; only the reduced value is
+    /// reported as clamped.
+    pub fn evaluate_size_limit(
+        &self,
+        path: &str,
+        original_value: Value,
+        reduced_value: Value,
+        size: usize,
+        max_bytes: usize,
+        provider_name: &str,
+    ) -> PolicyResult {
+        let effective_mode = self.policy_overrides.get(path).unwrap_or(&self.mode);
+
+        if size <= max_bytes {
+            return PolicyResult {
+                action: StrictnessAction::Proceed,
+                lossiness_item: None,
+            };
+        }
+        let message = format!(
+            "{} bytes exceed {}'s limit of {} bytes",
+            size, provider_name, max_bytes
+        );
+
+        match effective_mode {
+            StrictMode::Strict => PolicyResult {
+                action: StrictnessAction::Fail {
+                    error: Error::StrictnessViolation {
+                        message: format!("{} at '{}'", message, path),
+                        mode: *effective_mode,
+                        severity: Severity::Error,
+                    },
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Unsupported,
+                    path: path.to_string(),
+                    message,
+                    severity: Severity::Error,
+                    before: Some(original_value),
+                    after: None,
+                }),
+            },
+            StrictMode::Warn => PolicyResult {
+                action: StrictnessAction::Warn { message: message.clone() },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Unsupported,
+                    path: path.to_string(),
+                    message: format!("{}; sent unchanged", message),
+                    severity: Severity::Warning,
+                    before: Some(original_value),
+                    after: None,
+                }),
+            },
+            StrictMode::Coerce => PolicyResult {
+                action: StrictnessAction::Coerce {
+                    adjusted_value: reduced_value.clone(),
+                    reason: message.clone(),
+                },
+                lossiness_item: Some(LossinessItem {
+                    code: LossinessCode::Clamp,
+                    path: path.to_string(),
+                    message: format!("{}; reduced to fit", message),
+                    severity: Severity::Warning,
+                    before: Some(original_value),
+                    after: Some(reduced_value),
+                }),
+            },
+        }
+    }
+
     /// Evaluate policy for conflicting field values
     pub fn evaluate_field_conflict(
         &self,
@@ -472,9 +703,10 @@ impl StrictnessPolicy {
 mod tests {
     use super::*;
     use crate::{
-        Constraints, ConstraintLimits, EndpointConfig, Endpoints, InputModes, JsonOutputConfig,
-        Mappings, Message, MessageRole, ModelSpec, PromptSpec, ProviderInfo, ProviderSpec,
-        ResponseNormalization, SamplingParams, StreamNormalization, SyncNormalization, ToolingConfig,
+        ArgsEncoding, Constraints, ConstraintLimits, EndpointConfig, Endpoints, InputModes,
+        JsonOutputConfig, Mappings, Message, MessageRole, ModelSpec, PromptSpec, ProviderInfo,
+        ProviderSpec, ResponseNormalization, SamplingParams, StreamNormalization, SyncNormalization,
+        ToolingConfig,
     };
     use std::collections::HashMap;
 
@@ -483,9 +715,12 @@ mod tests {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test".to_string(),
+                content: "Test".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: None,
             tool_choice: None,
@@ -495,6 +730,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let provider_spec = ProviderSpec {
@@ -537,12 +775,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: false,
                 strategy: "system_prompt".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: serde_json::json!({}),
             constraints: Constraints {
                 system_prompt_location: "first".to_string(),
@@ -552,7 +795,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: HashMap::new(),
@@ -563,6 +808,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish".to_string(),
                     finish_reason_map: HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -570,8 +835,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         };
 
         TranslationContext::new(prompt_spec, provider_spec, model_spec, strict_mode)
@@ -735,6 +1002,127 @@ mod tests {
         assert!(result.lossiness_item.is_none());
     }
 
+    #[test]
+    fn test_evaluate_item_limit() {
+        let stop = serde_json::json!(["a", "b", "c"]);
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Warn));
+        let result = policy.evaluate_item_limit("stop", stop.clone(), 2, "test-provider");
+        match result.action {
+            StrictnessAction::Coerce { adjusted_value, .. } => {
+                assert_eq!(adjusted_value, serde_json::json!(["a", "b"]));
+            }
+            _ => panic!("Expected Coerce action for surplus items"),
+        }
+        assert_eq!(result.lossiness_item.unwrap().code, LossinessCode::Drop);
+
+        let result = policy.evaluate_item_limit("stop", stop.clone(), 4, "test-provider");
+        assert!(matches!(result.action, StrictnessAction::Proceed));
+        assert!(result.lossiness_item.is_none());
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Strict));
+        let result = policy.evaluate_item_limit("stop", stop, 2, "test-provider");
+        assert!(matches!(result.action, StrictnessAction::Fail { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_prompt_truncation() {
+        let turn = serde_json::json!({"role": "user", "content": "Hello"});
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Warn));
+        let result =
+            policy.evaluate_prompt_truncation("messages[1]", turn.clone(), None, "Dropped turn 1");
+        assert!(matches!(result.action, StrictnessAction::Coerce { .. }));
+        let item = result.lossiness_item.unwrap();
+        assert_eq!(item.code, LossinessCode::Drop);
+        assert_eq!(item.severity, Severity::Warning);
+        assert!(item.after.is_none());
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Strict));
+        let result = policy.evaluate_prompt_truncation("messages[1]", turn, None, "Dropped turn 1");
+        assert!(matches!(result.action, StrictnessAction::Fail { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_value_replacement() {
+        let arguments = serde_json::json!("{\"city\": ");
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Coerce));
+        let result = policy.evaluate_value_replacement(
+            "messages[1].tool_calls[0].arguments",
+            arguments.clone(),
+            serde_json::json!({}),
+            "Tool call arguments are not valid JSON",
+        );
+        match result.action {
+            StrictnessAction::Coerce { adjusted_value, .. } => {
+                assert_eq!(adjusted_value, serde_json::json!({}))
+            }
+            _ => panic!("Expected Coerce action for a replaced value"),
+        }
+        let item = result.lossiness_item.unwrap();
+        assert_eq!(item.code, LossinessCode::Drop);
+        assert_eq!(item.before, Some(arguments.clone()));
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Strict));
+        let result = policy.evaluate_value_replacement(
+            "messages[1].tool_calls[0].arguments",
+            arguments,
+            serde_json::json!({}),
+            "Tool call arguments are not valid JSON",
+        );
+        assert!(matches!(result.action, StrictnessAction::Fail { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_size_limit() {
+        let original = serde_json::json!("a long system prompt");
+        let reduced = serde_json::json!("a long");
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Coerce));
+        let result = policy.evaluate_size_limit(
+            "system",
+            original.clone(),
+            reduced.clone(),
+            20,
+            6,
+            "test-provider",
+        );
+        match result.action {
+            StrictnessAction::Coerce { adjusted_value, .. } => assert_eq!(adjusted_value, reduced),
+            _ => panic!("Expected Coerce action for an oversized value"),
+        }
+        assert_eq!(result.lossiness_item.unwrap().code, LossinessCode::Clamp);
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Warn));
+        let result = policy.evaluate_size_limit(
+            "system",
+            original.clone(),
+            reduced.clone(),
+            20,
+            6,
+            "test-provider",
+        );
+        assert!(matches!(result.action, StrictnessAction::Warn { .. }));
+        let item = result.lossiness_item.unwrap();
+        assert_eq!(item.code, LossinessCode::Unsupported);
+        assert!(item.after.is_none());
+        let result = policy.evaluate_size_limit(
+            "system",
+            original.clone(),
+            reduced.clone(),
+            20,
+            32,
+            "test-provider",
+        );
+        assert!(matches!(result.action, StrictnessAction::Proceed));
+
+        let policy = StrictnessPolicy::new(create_test_context(StrictMode::Strict));
+        let result =
+            policy.evaluate_size_limit("system", original, reduced, 20, 6, "test-provider");
+        assert!(matches!(result.action, StrictnessAction::Fail { .. }));
+    }
+
     #[test]
     fn test_evaluate_field_conflict() {
         let context = create_test_context(StrictMode::Warn);
@@ -849,9 +1237,12 @@ mod tests {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test message".to_string(),
+                content: "Test message".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: Some(vec![]), // Unsupported tools
             tool_choice: None,
@@ -862,11 +1253,19 @@ mod tests {
                 top_k: None,
                 frequency_penalty: None,
                 presence_penalty: None,
+                candidate_count: None,
+                stop: None,
+                logprobs: None,
+                top_logprobs: None,
+                logit_bias: None,
             }),
             limits: None,
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let provider_spec = ProviderSpec {
@@ -906,12 +1305,17 @@ mod tests {
                     parallel_tool_calls_default: false,
                     can_disable_parallel_tool_calls: false,
                     disable_switch: None,
+                    rendering: None,
                 },
                 json_output: JsonOutputConfig {
                     native_param: false,
                     strategy: "system_prompt".to_string(),
+                    instruction: None,
+                    prefill: false,
                 },
                 capabilities: None,
+                transformations: Vec::new(),
+                message_rendering: None,
                 parameters: serde_json::json!({}),
                 constraints: Constraints {
                     system_prompt_location: "first".to_string(),
@@ -921,7 +1325,9 @@ mod tests {
                     limits: ConstraintLimits {
                         max_tool_schema_bytes: 100000,
                         max_system_prompt_bytes: 10000,
+                        context_window: None,
                     },
+                    extensions: None,
                 },
                 mappings: Mappings {
                     paths: HashMap::new(),
@@ -932,6 +1338,26 @@ mod tests {
                         content_path: "content".to_string(),
                         finish_reason_path: "finish".to_string(),
                         finish_reason_map: HashMap::new(),
+                        usage_path: None,
+                        usage: None,
+                        tool_calls_path: None,
+                        tool_name_path: None,
+                        tool_args_path: None,
+                        tool_id_path: None,
+                        args_encoding: ArgsEncoding::default(),
+                        reasoning_path: None,
+                        reasoning_text_path: None,
+                        reasoning_signature_path: None,
+                        reasoning_redacted_path: None,
+                        choices_path: None,
+                        logprobs_path: None,
+                        logprob_token_path: None,
+                        logprob_value_path: None,
+                        top_logprobs_path: None,
+                        citations_path: None,
+                        citation_document_path: None,
+                        citation_text_path: None,
+                        response_id_path: None,
                     },
                     stream: StreamNormalization {
                         protocol: "sse".to_string(),
@@ -939,8 +1365,10 @@ mod tests {
                             type_path: "type".to_string(),
                             routes: vec![],
                         },
+                        usage: None,
                     },
                 },
+                extensions: None,
             }],
         };
 
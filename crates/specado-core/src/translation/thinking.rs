@@ -0,0 +1,111 @@
+//! Extended thinking request constraints
+//!
+//! Models declaring `thinking` as an object parameter take extended
+//! thinking in Anthropic's `{"type": "enabled", "budget_tokens": N}` shape.
+//! While it is enabled such a model rejects a changed temperature or top_k
+//! and forced tool use, so those are dropped under the strictness policy,
+//! and JSON mode is emulated without prefilling the assistant turn.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::ToolChoice;
+use crate::{LossinessItem, Result};
+
+/// Sampling fields a model rejects while extended thinking is enabled
+const REJECTED_SAMPLING_FIELDS: &[&str] = &["temperature", "top_k"];
+
+/// Keeps a request compatible with extended thinking
+pub struct ExtendedThinking<'a> {
+    enabled: bool,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> ExtendedThinking<'a> {
+    /// Create a checker for the context's prompt and model
+    pub fn new(
+        context: &TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let switched_on = context.prompt_spec.advanced.as_ref().and_then(|advanced| advanced.thinking) == Some(true);
+        let declared = context.model_spec.parameters.get("thinking");
+        let object_shape = declared.and_then(|declared| declared.get("type")).and_then(Value::as_str) == Some("object");
+
+        Self {
+            enabled: switched_on && object_shape,
+            policy,
+            tracker,
+        }
+    }
+
+    /// Whether extended thinking is switched on for the request
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Tool choice to render; forced tool use falls back to `auto`
+    pub fn tool_choice(&self, tool_choice: Option<&ToolChoice>) -> Result<Option<ToolChoice>> {
+        match tool_choice {
+            Some(forced @ (ToolChoice::Required | ToolChoice::Specific { .. })) if self.enabled => {
+                self.apply_policy(self.policy.evaluate_unsupported_feature(
+                    "tool_choice",
+                    "forced tool use with extended thinking",
+                    serde_json::to_value(forced).ok(),
+                ))?;
+                Ok(Some(ToolChoice::Auto))
+            }
+            other => Ok(other.cloned()),
+        }
+    }
+
+    /// Drop the sampling fields the model rejects with extended thinking
+    pub fn sampling(&self, request: &mut Value) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        for field in REJECTED_SAMPLING_FIELDS {
+            let Some(value) = request.as_object_mut().and_then(|request| request.remove(*field)) else {
+                continue;
+            };
+            self.apply_policy(self.policy.evaluate_unsupported_feature(
+                &format!("sampling.{}", field),
+                &format!("{} with extended thinking", field),
+                Some(value),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Track the lossiness item of a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Spec-driven tool rendering
+//!
+//! Renders uniform tool definitions and tool choice into the provider's
+//! native shape, following the model's [`ToolRendering`] templates or the
+//! defaults of its block format: OpenAI `{"type": "function", "function":
+//! {...}}` definitions and Anthropic `input_schema` definitions with
+//! `{"type": "any"}` choices. Schema keywords the provider rejects are
+//! removed, tool choice modes it lacks are dropped, and a preference against
+//! parallel tool calls is sent through the model's `disable_switch`.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use super::lossiness::LossinessTracker;
+use super::rendering::render_template;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::types::{ContentBlockFormat, Tool, ToolChoice, ToolChoiceTemplates, ToolRendering, ToolingConfig};
+use crate::{LossinessItem, Result};
+
+impl ContentBlockFormat {
+    /// Built-in tool definition template for this format
+    pub fn default_tool_template(&self) -> Value {
+        match self {
+            ContentBlockFormat::OpenAi => json!({
+                "type": "function",
+                "function": { "name": "{{name}}", "description": "{{description}}", "parameters": "{{schema}}" },
+            }),
+            ContentBlockFormat::Anthropic => json!({
+                "name": "{{name}}",
+                "description": "{{description}}",
+                "input_schema": "{{schema}}",
+            }),
+        }
+    }
+
+    /// Built-in tool choice templates for this format
+    pub fn default_tool_choice(&self) -> ToolChoiceTemplates {
+        match self {
+            ContentBlockFormat::OpenAi => ToolChoiceTemplates {
+                auto: Some(json!("auto")),
+                required: Some(json!("required")),
+                specific: Some(json!({ "type": "function", "function": { "name": "{{name}}" } })),
+            },
+            ContentBlockFormat::Anthropic => ToolChoiceTemplates {
+                auto: Some(json!({ "type": "auto" })),
+                required: Some(json!({ "type": "any" })),
+                specific: Some(json!({ "type": "tool", "name": "{{name}}" })),
+            },
+        }
+    }
+
+    /// Built-in switch disabling parallel tool calls for this format
+    pub fn default_disable_switch(&self) -> Value {
+        match self {
+            ContentBlockFormat::OpenAi => json!({ "path": "$.parallel_tool_calls", "value": false }),
+            ContentBlockFormat::Anthropic => json!({ "path": "$.tool_choice.disable_parallel_tool_use", "value": true }),
+        }
+    }
+}
+
+/// Renders tools and tool choice into provider request fields
+pub struct ToolRenderer<'a> {
+    tool_template: Value,
+    tool_choice: ToolChoiceTemplates,
+    unsupported_keywords: Vec<String>,
+    disable_switch: Option<(String, Value)>,
+    tooling: &'a ToolingConfig,
+    parallel_tool_calls: Option<bool>,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> ToolRenderer<'a> {
+    /// Create a renderer for the context's model
+    pub fn new(
+        context: &'a TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let tooling = &context.model_spec.tooling;
+        let rendering = tooling.rendering.clone().unwrap_or_default();
+        let format = rendering.format.unwrap_or_default();
+
+        let ToolRendering { tool, tool_choice, unsupported_schema_keywords, .. } = rendering;
+
+        Self {
+            tool_template: tool.unwrap_or_else(|| format.default_tool_template()),
+            tool_choice: tool_choice.unwrap_or_else(|| format.default_tool_choice()),
+            unsupported_keywords: unsupported_schema_keywords,
+            disable_switch: disable_switch(context),
+            tooling,
+            parallel_tool_calls: context.prompt_spec.preferences.as_ref()
+                .and_then(|preferences| preferences.parallel_tool_calls),
+            policy,
+            tracker,
+        }
+    }
+
+    /// Render the tools, tool choice and parallel tool call switch into the
+    /// request
+    pub fn apply(&self, request: &mut Value, tools: &[Tool], tool_choice: Option<&ToolChoice>) -> Result<()> {
+        let mut rendered = Vec::with_capacity(tools.len());
+        for (index, tool) in tools.iter().enumerate() {
+            rendered.push(self.render_tool(index, tool)?);
+        }
+        request["tools"] = Value::Array(rendered);
+
+        if let Some(tool_choice) = tool_choice {
+            if let Some(choice) = self.render_tool_choice(tool_choice)? {
+                request["tool_choice"] = choice;
+            }
+        }
+
+        self.apply_parallel_tool_calls(request)
+    }
+
+    /// Render one tool definition
+    fn render_tool(&self, index: usize, tool: &Tool) -> Result<Value> {
+        let mut vars = Map::new();
+        vars.insert("name".to_string(), json!(tool.name));
+        if let Some(description) = &tool.description {
+            vars.insert("description".to_string(), json!(description));
+        }
+        vars.insert("schema".to_string(), self.parameters_schema(index, &tool.json_schema)?);
+        Ok(render_template(&self.tool_template, &vars))
+    }
+
+    /// Tool parameters the provider accepts
+    ///
+    /// Providers expect an object schema: a boolean schema becomes an empty
+    /// object, and keywords the provider rejects are removed.
+    fn parameters_schema(&self, index: usize, schema: &Value) -> Result<Value> {
+        let path = format!("tools[{}].json_schema", index);
+        if !schema.is_object() {
+            if schema != &Value::Bool(true) {
+                self.apply_policy(self.policy.evaluate_unsupported_feature(
+                    &path,
+                    "non-object tool parameter schema",
+                    Some(schema.clone()),
+                ))?;
+            }
+            return Ok(json!({ "type": "object", "properties": {} }));
+        }
+
+        let mut removed = Vec::new();
+        let stripped = strip_keywords(schema, &self.unsupported_keywords, &mut removed);
+        if !removed.is_empty() {
+            removed.sort();
+            removed.dedup();
+            self.apply_policy(self.policy.evaluate_unsupported_feature(
+                &path,
+                &format!("tool schema keywords {}", removed.join(", ")),
+                Some(schema.clone()),
+            ))?;
+        }
+        Ok(stripped)
+    }
+
+    /// Render the tool choice; `None` if the provider lacks its mode
+    fn render_tool_choice(&self, tool_choice: &ToolChoice) -> Result<Option<Value>> {
+        let (template, mode) = match tool_choice {
+            ToolChoice::Auto => (&self.tool_choice.auto, "auto"),
+            ToolChoice::Required => (&self.tool_choice.required, "required"),
+            ToolChoice::Specific { .. } => (&self.tool_choice.specific, "specific"),
+        };
+        let Some(template) = template else {
+            self.apply_policy(self.policy.evaluate_unsupported_feature(
+                "tool_choice",
+                &format!("tool choice '{}'", mode),
+                Some(json!(tool_choice)),
+            ))?;
+            return Ok(None);
+        };
+
+        let mut vars = Map::new();
+        if let ToolChoice::Specific { name } = tool_choice {
+            vars.insert("name".to_string(), json!(name));
+        }
+        Ok(Some(render_template(template, &vars)))
+    }
+
+    /// Send a parallel tool call preference that differs from the model's
+    /// default
+    fn apply_parallel_tool_calls(&self, request: &mut Value) -> Result<()> {
+        let Some(parallel) = self.parallel_tool_calls else {
+            return Ok(());
+        };
+        if parallel == self.tooling.parallel_tool_calls_default {
+            return Ok(());
+        }
+
+        // The switch can only disable parallel calls
+        let switch = self.disable_switch.as_ref()
+            .filter(|_| !parallel && self.tooling.can_disable_parallel_tool_calls);
+        let Some((path, value)) = switch else {
+            return self.apply_policy(self.policy.evaluate_unsupported_feature(
+                "preferences.parallel_tool_calls",
+                "parallel_tool_calls",
+                Some(json!(parallel)),
+            ));
+        };
+
+        // A switch inside the tool choice needs a choice to live in
+        let path = path.trim_start_matches("$.");
+        if path.starts_with("tool_choice.") && request.get("tool_choice").is_none() {
+            if let Some(auto) = &self.tool_choice.auto {
+                request["tool_choice"] = render_template(auto, &Map::new());
+            }
+        }
+        set_path(request, path, value.clone());
+        Ok(())
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Request path and value disabling parallel tool calls for the context's
+/// model: its `disable_switch`, or the default of its block format
+pub fn disable_switch(context: &TranslationContext) -> Option<(String, Value)> {
+    let tooling = &context.model_spec.tooling;
+    let switch = tooling.disable_switch.clone().unwrap_or_else(|| {
+        tooling.rendering.as_ref()
+            .and_then(|rendering| rendering.format)
+            .unwrap_or_default()
+            .default_disable_switch()
+    });
+    let path = switch.get("path")?.as_str()?;
+    Some((path.to_string(), switch.get("value").cloned().unwrap_or(Value::Null)))
+}
+
+/// A JSON Schema without `keywords`, collecting the keywords it had
+fn strip_keywords(schema: &Value, keywords: &[String], removed: &mut Vec<String>) -> Value {
+    match schema {
+        Value::Object(object) => {
+            let mut stripped = Map::new();
+            for (key, value) in object {
+                if keywords.contains(key) {
+                    removed.push(key.clone());
+                    continue;
+                }
+                // Property names are not schema keywords
+                let value = match (key.as_str(), value) {
+                    ("properties", Value::Object(properties)) => Value::Object(
+                        properties.iter()
+                            .map(|(name, property)| (name.clone(), strip_keywords(property, keywords, removed)))
+                            .collect(),
+                    ),
+                    _ => strip_keywords(value, keywords, removed),
+                };
+                stripped.insert(key.clone(), value);
+            }
+            Value::Object(stripped)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| strip_keywords(item, keywords, removed)).collect()),
+        _ => schema.clone(),
+    }
+}
+
+/// Set a dotted request path, creating objects along the way
+fn set_path(request: &mut Value, path: &str, value: Value) {
+    let mut current = request;
+    let mut parts = path.split('.').peekable();
+    while let Some(part) = parts.next() {
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        if parts.peek().is_none() {
+            current[part] = value;
+            return;
+        }
+        current = &mut current[part];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "format": {"type": "string", "format": "date"},
+                "count": {"type": "integer", "minimum": 1}
+            }
+        });
+        let mut removed = Vec::new();
+        let stripped = strip_keywords(&schema, &["format".to_string(), "minimum".to_string()], &mut removed);
+        assert_eq!(stripped, json!({
+            "type": "object",
+            "properties": {
+                "format": {"type": "string"},
+                "count": {"type": "integer"}
+            }
+        }));
+        removed.sort();
+        assert_eq!(removed, vec!["format", "minimum"]);
+    }
+
+    #[test]
+    fn test_set_path() {
+        let mut request = json!({"tool_choice": {"type": "auto"}});
+        set_path(&mut request, "tool_choice.disable_parallel_tool_use", json!(true));
+        set_path(&mut request, "parallel_tool_calls", json!(false));
+        assert_eq!(request, json!({
+            "tool_choice": {"type": "auto", "disable_parallel_tool_use": true},
+            "parallel_tool_calls": false
+        }));
+    }
+}
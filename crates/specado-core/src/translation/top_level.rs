@@ -0,0 +1,158 @@
+//! Unknown top-level request fields
+//!
+//! Models with `constraints.forbid_unknown_top_level_fields` reject requests
+//! carrying fields they do not know. Once the request is assembled, each
+//! top-level field is checked against the fields the model accepts: its
+//! declared `parameters`, the targets of its mappings and transformations,
+//! its mapping flags, the rendered message fields, and the tool, response
+//! format, RAG and conversation fields it supports. Other fields are
+//! dropped, or fail the translation in strict mode.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::tools::disable_switch;
+use super::TranslationContext;
+use crate::{LossinessItem, Result};
+
+/// Drops the request fields a model does not accept
+pub struct TopLevelFields<'a> {
+    allowed: Option<BTreeSet<String>>,
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> TopLevelFields<'a> {
+    /// Create a check for the context's model; `rendered_fields` are the
+    /// fields the message renderer produced
+    pub fn new<'f>(
+        context: &TranslationContext,
+        rendered_fields: impl IntoIterator<Item = &'f String>,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let allowed = context.model_spec.constraints.forbid_unknown_top_level_fields.then(|| {
+            let mut allowed = allowed_fields(context);
+            allowed.extend(rendered_fields.into_iter().cloned());
+            allowed
+        });
+        Self { allowed, policy, tracker }
+    }
+
+    /// Drop the request's unknown top-level fields
+    pub fn apply(&self, request: &mut Value) -> Result<()> {
+        let (Some(allowed), Some(fields)) = (&self.allowed, request.as_object_mut()) else {
+            return Ok(());
+        };
+
+        let unknown: Vec<String> = fields.keys()
+            .filter(|name| !allowed.contains(*name))
+            .cloned()
+            .collect();
+        for name in unknown {
+            let value = fields.remove(&name);
+            self.apply_policy(self.policy.evaluate_unsupported_feature(&name, &name, value))?;
+        }
+        Ok(())
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        self.record(lossiness_item);
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+
+    fn record(&self, lossiness_item: Option<LossinessItem>) {
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+    }
+}
+
+/// Top-level fields the context's model accepts, besides its rendered
+/// message fields
+pub fn allowed_fields(context: &TranslationContext) -> BTreeSet<String> {
+    let model_spec = &context.model_spec;
+    let mut allowed = BTreeSet::from(["model".to_string()]);
+
+    if let Some(parameters) = model_spec.parameters.as_object() {
+        allowed.extend(parameters.keys().cloned());
+    }
+    allowed.extend(model_spec.mappings.flags.keys().cloned());
+    allowed.extend(
+        model_spec.mappings.paths.values()
+            .chain(model_spec.transformations.iter().filter_map(|rule| rule.target_path.as_ref()))
+            .filter_map(|path| top_level_field(path))
+            .map(str::to_string),
+    );
+
+    if model_spec.tooling.tools_supported {
+        allowed.extend(["tools".to_string(), "tool_choice".to_string()]);
+        if model_spec.tooling.can_disable_parallel_tool_calls {
+            allowed.extend(disable_switch(context).and_then(|(path, _)| top_level_field(&path).map(str::to_string)));
+        }
+    }
+    if model_spec.json_output.native_param {
+        allowed.insert("response_format".to_string());
+    }
+
+    if let Some(extensions) = &model_spec.extensions {
+        if let Some(rag) = &extensions.rag_config {
+            allowed.extend(
+                [&rag.connector_param, &rag.document_param, &rag.search_queries_param]
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+        if let Some(conversation) = &extensions.conversation_management {
+            allowed.extend(
+                [&conversation.conversation_id_param, &conversation.parent_message_param]
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+    }
+    allowed
+}
+
+/// First field of a JSONPath, e.g. `reasoning` for `$.reasoning.effort`
+fn top_level_field(path: &str) -> Option<&str> {
+    let path = path.strip_prefix('$').unwrap_or(path).trim_start_matches('.');
+    let end = path.find(['.', '[']).unwrap_or(path.len());
+    Some(&path[..end]).filter(|field| !field.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_level_field() {
+        assert_eq!(top_level_field("$.max_tokens"), Some("max_tokens"));
+        assert_eq!(top_level_field("$.reasoning.effort"), Some("reasoning"));
+        assert_eq!(top_level_field("$.input[0]"), Some("input"));
+        assert_eq!(top_level_field("system"), Some("system"));
+        assert_eq!(top_level_field("$"), None);
+        assert_eq!(top_level_field("$.[0]"), None);
+    }
+}
@@ -164,11 +164,20 @@ impl TransformationPipeline {
                 }
             }
 
-            // Determine target path
-            let target_path = rule.target_path.as_ref().unwrap_or(&rule.source_path);
+            // Determine target path; a rename without one renames the field in place
+            let target_path = match (&rule.target_path, &rule.transformation) {
+                (Some(path), _) => path.clone(),
+                (None, TransformationType::FieldRename { new_name }) => sibling_path(&rule.source_path, new_name),
+                (None, _) => rule.source_path.clone(),
+            };
             
             // Set transformed value at target path
-            self.set_value_at_path(target_path, transformed_value.clone(), data)?;
+            self.set_value_at_path(&target_path, transformed_value.clone(), data)?;
+
+            // A renamed field no longer exists under its old name
+            if matches!(rule.transformation, TransformationType::FieldRename { .. }) && target_path != rule.source_path {
+                self.remove_value_at_path(&rule.source_path, data);
+            }
         }
 
         Ok(())
@@ -564,6 +573,26 @@ impl TransformationPipeline {
         Ok(())
     }
 
+    /// Remove the value at a dot-notation JSONPath location
+    fn remove_value_at_path(&self, path: &str, data: &mut Value) {
+        let path = path.trim_start_matches("$.");
+        let (parent, field) = match path.rsplit_once('.') {
+            Some((parent, field)) => (parent.split('.').collect::<Vec<_>>(), field),
+            None => (Vec::new(), path),
+        };
+
+        let mut current = data;
+        for part in parent {
+            match current.get_mut(part) {
+                Some(next) => current = next,
+                None => return,
+            }
+        }
+        if let Some(obj) = current.as_object_mut() {
+            obj.remove(field);
+        }
+    }
+
     /// Check if a rule applies to the given direction
     fn rule_applies_to_direction(&self, rule: &TransformationRule, direction: &TransformationDirection) -> bool {
         matches!((&rule.direction, direction), 
@@ -693,4 +722,12 @@ impl Default for TransformationPipeline {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Path of a sibling field, e.g. (`$.a.b`, `c`) -> `$.a.c`
+pub(super) fn sibling_path(path: &str, name: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((parent, _)) => format!("{}.{}", parent, name),
+        None => format!("$.{}", name),
+    }
+}
@@ -34,6 +34,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let provider_spec = ProviderSpec {
@@ -76,12 +79,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: crate::JsonOutputConfig {
                 native_param: true,
                 strategy: "native".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: json!({}),
             constraints: crate::Constraints {
                 system_prompt_location: "first".to_string(),
@@ -91,7 +99,9 @@ mod tests {
                 limits: crate::ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: crate::Mappings {
                 paths: std::collections::HashMap::new(),
@@ -102,6 +112,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish".to_string(),
                     finish_reason_map: std::collections::HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: crate::ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: crate::StreamNormalization {
                     protocol: "sse".to_string(),
@@ -109,8 +139,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         };
 
         TranslationContext::new(prompt_spec, provider_spec, model_spec, StrictMode::Warn)
@@ -595,11 +627,9 @@ mod tests {
         assert_eq!(result["max_tokens"], json!(1000.0));
         assert_eq!(result["conversation"], json!([{"role": "user", "content": "Hello"}]));
         
-        // Note: The current rename implementation copies to target path but doesn't remove source
-        // Both fields should exist - this is copy behavior, not move behavior
-        assert!(result.get("messages").is_some());
+        // Renames move the field, so the source no longer exists
+        assert!(result.get("messages").is_none());
         assert!(result.get("conversation").is_some());
-        assert_eq!(result["messages"], result["conversation"]);
     }
     
     #[test]
@@ -750,4 +780,135 @@ mod tests {
         let reverse_result = pipeline.transform(&input, TransformationDirection::Reverse, &context).unwrap();
         assert_eq!(reverse_result["temperature"], json!(0.5));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_declared_rules() {
+        let rules: Vec<super::super::TransformationRule> = serde_json::from_value(json!([
+            {
+                "id": "effort",
+                "source_path": "$.reasoning_effort",
+                "type": "enum_mapping",
+                "mappings": {"minimal": "low"},
+                "default": "medium",
+                "priority": 5
+            },
+            {
+                "id": "max_tokens_rename",
+                "source_path": "$.max_tokens",
+                "type": "field_rename",
+                "new_name": "max_output_tokens"
+            },
+            {
+                "id": "stream_default",
+                "source_path": "$.stream",
+                "type": "default_value",
+                "value": false
+            },
+            {
+                "id": "seed_to_string",
+                "source_path": "$.seed",
+                "type": "conditional",
+                "condition": {"and": [{"exists": {"path": "$.seed"}}, {"not": {"equals": {"path": "$.model", "value": "m"}}}]},
+                "if_true": {"type": "type_conversion", "from": "number", "to": "string"}
+            },
+            {
+                "id": "temperature_scale",
+                "source_path": "$.temperature",
+                "type": "unit_conversion",
+                "from_unit": "openai_range",
+                "to_unit": "anthropic_range",
+                "formula": {"linear": {"scale": 0.5}},
+                "direction": "bidirectional",
+                "optional": true
+            }
+        ])).unwrap();
+
+        assert_eq!(rules[0].direction, TransformationDirection::Forward);
+        assert_eq!(rules[4].transformation, TransformationType::UnitConversion {
+            from_unit: "openai_range".to_string(),
+            to_unit: "anthropic_range".to_string(),
+            formula: super::super::ConversionFormula::Linear { scale: 0.5, offset: 0.0 },
+        });
+
+        let mut pipeline = TransformationPipeline::new().add_rules(rules);
+        let input = json!({
+            "model": "other",
+            "reasoning_effort": "minimal",
+            "max_tokens": 100,
+            "seed": 7,
+            "temperature": 1.0
+        });
+
+        let result = pipeline.transform(&input, TransformationDirection::Forward, &create_test_context()).unwrap();
+        assert_eq!(result, json!({
+            "model": "other",
+            "reasoning_effort": "low",
+            "max_output_tokens": 100,
+            "stream": false,
+            "seed": "7",
+            "temperature": 0.5
+        }));
+    }
+
+    #[test]
+    fn test_custom_rules_cannot_be_declared() {
+        let result = serde_json::from_value::<super::super::TransformationRule>(json!({
+            "id": "custom",
+            "source_path": "$.x",
+            "type": "custom",
+            "name": "uppercase"
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rule_inverse() {
+        let rules: Vec<super::super::TransformationRule> = serde_json::from_value(json!([
+            {
+                "id": "temperature_scale",
+                "source_path": "$.temperature",
+                "type": "unit_conversion",
+                "from_unit": "openai_range",
+                "to_unit": "anthropic_range",
+                "formula": {"linear": {"scale": 0.5, "offset": 0.1}},
+                "priority": 10
+            },
+            {
+                "id": "max_tokens_rename",
+                "source_path": "$.max_tokens",
+                "type": "field_rename",
+                "new_name": "max_output_tokens"
+            },
+            {
+                "id": "effort",
+                "source_path": "$.reasoning_effort",
+                "type": "enum_mapping",
+                "mappings": {"minimal": "low"}
+            },
+            {
+                "id": "stream_default",
+                "source_path": "$.stream",
+                "type": "default_value",
+                "value": false
+            }
+        ])).unwrap();
+
+        let inverses: Vec<_> = rules.iter().filter_map(|rule| rule.inverse()).collect();
+        assert_eq!(inverses.len(), 3);
+        assert!(inverses.iter().all(|rule| rule.direction == TransformationDirection::Reverse && rule.optional));
+        assert_eq!(inverses[1].source_path, "$.max_output_tokens");
+
+        let context = create_test_context();
+        let input = json!({"temperature": 1.0, "max_tokens": 100, "reasoning_effort": "minimal"});
+        let forward = TransformationPipeline::new().add_rules(rules)
+            .transform(&input, TransformationDirection::Forward, &context).unwrap();
+        let reverse = TransformationPipeline::new().add_rules(inverses)
+            .transform(&forward, TransformationDirection::Reverse, &context).unwrap();
+
+        assert_eq!(reverse["max_tokens"], json!(100));
+        assert_eq!(reverse["reasoning_effort"], json!("minimal"));
+        assert!((reverse["temperature"].as_f64().unwrap() - 1.0).abs() < 1e-9);
+        // Defaults cannot be told apart from values the caller set
+        assert_eq!(reverse["stream"], json!(false));
+    }
+}
@@ -9,6 +9,7 @@
 use crate::Result;
 use super::super::jsonpath::JSONPathError;
 use super::super::TranslationContext;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -75,7 +76,8 @@ impl From<TransformationError> for crate::Error {
 }
 
 /// Supported value types for conversions
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ValueType {
     String,
     Number,
@@ -86,16 +88,22 @@ pub enum ValueType {
 }
 
 /// Conversion formulas for unit transformations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ConversionFormula {
     /// Linear formula: output = input * scale + offset
-    Linear { scale: f64, offset: f64 },
+    Linear {
+        scale: f64,
+        #[serde(default)]
+        offset: f64,
+    },
     /// Custom function for complex conversions
     Custom(String),
 }
 
 /// Condition for conditional transformations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Condition {
     /// Check if field equals a value
     Equals { path: String, value: Value },
@@ -115,7 +123,12 @@ pub enum Condition {
 pub type TransformerFunction = fn(&Value, &TransformationContext) -> Result<Value>;
 
 /// Type of transformation to apply
-#[derive(Debug, Clone)]
+///
+/// Declared in provider specs with a `type` tag, e.g.
+/// `{"type": "enum_mapping", "mappings": {...}}`. `Custom` transformations
+/// hold a function and can only be built in code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransformationType {
     /// Convert between primitive types (string, number, boolean)
     TypeConversion {
@@ -148,6 +161,7 @@ pub enum TransformationType {
         if_false: Option<Box<TransformationType>>,
     },
     /// Custom transformation using a closure
+    #[serde(skip)]
     Custom {
         name: String,
         transformer: TransformerFunction,
@@ -202,9 +216,11 @@ pub struct TransformationContext {
 }
 
 /// Direction of transformation
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TransformationDirection {
     /// Uniform to provider (forward)
+    #[default]
     Forward,
     /// Provider to uniform (reverse)
     Reverse,
@@ -213,20 +229,91 @@ pub enum TransformationDirection {
 }
 
 /// A single transformation rule
-#[derive(Debug, Clone)]
+///
+/// Rules can be declared in a model's `transformations`, with the
+/// transformation's `type` and fields inline:
+///
+/// ```json
+/// {"id": "temperature_scale", "source_path": "$.temperature", "type": "unit_conversion",
+///  "from_unit": "openai_range", "to_unit": "anthropic_range",
+///  "formula": {"linear": {"scale": 0.5}}, "optional": true}
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformationRule {
     /// Unique identifier for this rule
     pub id: String,
     /// JSONPath to select source fields
     pub source_path: String,
     /// JSONPath to place transformed fields (optional, defaults to source_path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub target_path: Option<String>,
     /// Type of transformation to apply
+    #[serde(flatten)]
     pub transformation: TransformationType,
     /// Direction of transformation (forward, reverse, or both)
+    #[serde(default)]
     pub direction: TransformationDirection,
     /// Priority for rule ordering (higher numbers execute first)
+    #[serde(default)]
     pub priority: i32,
     /// Whether this rule is optional (continues on error)
+    #[serde(default)]
     pub optional: bool,
-}
\ No newline at end of file
+}
+impl TransformationRule {
+    /// Rule undoing this one, applied when reading provider requests back
+    ///
+    /// Renames, type conversions, linear unit conversions and one-to-one enum
+    /// mappings can be inverted. Defaults, conditionals and custom
+    /// transformations do not keep the original value and have no inverse.
+    /// Inverse rules run in reverse order and are optional, since a request
+    /// may not have been produced by the forward rule.
+    pub fn inverse(&self) -> Option<Self> {
+        let forward_target = match (&self.target_path, &self.transformation) {
+            (Some(path), _) => path.clone(),
+            (None, TransformationType::FieldRename { new_name }) => super::pipeline::sibling_path(&self.source_path, new_name),
+            (None, _) => self.source_path.clone(),
+        };
+
+        let transformation = match &self.transformation {
+            TransformationType::TypeConversion { from, to } => TransformationType::TypeConversion {
+                from: to.clone(),
+                to: from.clone(),
+            },
+            TransformationType::EnumMapping { mappings, .. } => {
+                let inverted: HashMap<String, String> = mappings.iter()
+                    .map(|(from, to)| (to.clone(), from.clone()))
+                    .collect();
+                if inverted.len() != mappings.len() {
+                    return None;
+                }
+                TransformationType::EnumMapping { mappings: inverted, default: None }
+            }
+            TransformationType::UnitConversion { from_unit, to_unit, formula: ConversionFormula::Linear { scale, offset } }
+                if *scale != 0.0 =>
+            {
+                TransformationType::UnitConversion {
+                    from_unit: to_unit.clone(),
+                    to_unit: from_unit.clone(),
+                    formula: ConversionFormula::Linear { scale: 1.0 / scale, offset: -offset / scale },
+                }
+            }
+            TransformationType::FieldRename { .. } => {
+                let original_name = self.source_path.rsplit('.').next().unwrap_or(&self.source_path);
+                TransformationType::FieldRename { new_name: original_name.to_string() }
+            }
+            _ => return None,
+        };
+
+        let target_path = (forward_target != self.source_path).then(|| self.source_path.clone());
+        Some(Self {
+            id: format!("{}_inverse", self.id),
+            source_path: forward_target,
+            target_path,
+            transformation,
+            direction: TransformationDirection::Reverse,
+            priority: -self.priority,
+            optional: true,
+        })
+    }
+}
@@ -0,0 +1,576 @@
+//! Prompt truncation
+//!
+//! A prompt whose size, counted with the model family's tokenizer, exceeds
+//! its token budget, the smaller of `limits.max_prompt_tokens` and the
+//! model's context window less `limits.max_output_tokens`, loses its oldest
+//! turns until it fits. System
+//! messages and the newest turn are always kept, and an assistant message's
+//! tool calls go with the tool results answering them. When part of the
+//! oldest turn is enough, its text is trimmed from the start instead.
+//!
+//! `preferences.prompt_truncation` selects the mode, falling back to the
+//! model's `constraints.extensions.prompt_truncation.default_mode` and then
+//! `AUTO`, which also keeps the first user turn since it usually states the
+//! task. `END` works from the other end: the newest turns go first, the
+//! oldest is kept, and a turn's text is trimmed from its end.
+//!
+//! Every dropped turn and trimmed text goes through the strictness policy,
+//! so strict mode fails instead of truncating.
+//!
+//! Copyright (c) 2025 Specado Team
+//! Licensed under the Apache-2.0 license
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+
+use super::lossiness::LossinessTracker;
+use super::strictness::{PolicyResult, StrictnessAction, StrictnessPolicy};
+use super::TranslationContext;
+use crate::tokenizer::{count_message_tokens, count_prompt_tokens, tokenizer_for, Tokenizer};
+use crate::types::{Message, MessageContent, MessageRole, TruncationMode};
+use crate::Result;
+
+/// Fits a prompt's messages into its token budget
+pub struct PromptTruncator<'a> {
+    mode: TruncationMode,
+    budget: Option<u32>,
+    tokenizer: Arc<dyn Tokenizer>,
+    prompt_messages: &'a [Message],
+    policy: &'a StrictnessPolicy,
+    tracker: &'a Arc<Mutex<LossinessTracker>>,
+}
+
+impl<'a> PromptTruncator<'a> {
+    /// Create a truncator for the context's prompt and model
+    pub fn new(
+        context: &'a TranslationContext,
+        policy: &'a StrictnessPolicy,
+        tracker: &'a Arc<Mutex<LossinessTracker>>,
+    ) -> Self {
+        let prompt = &context.prompt_spec;
+        let model = &context.model_spec;
+
+        let limits = prompt.limits.as_ref();
+        let max_output_tokens = limits.and_then(|limits| limits.max_output_tokens).unwrap_or(0);
+        let budget = [
+            limits.and_then(|limits| limits.max_prompt_tokens),
+            model.constraints.limits.context_window.map(|window| window.saturating_sub(max_output_tokens)),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        Self {
+            mode: truncation_mode(context),
+            budget,
+            tokenizer: tokenizer_for(&model.family),
+            prompt_messages: &prompt.messages,
+            policy,
+            tracker,
+        }
+    }
+
+    /// Messages fitting the budget
+    ///
+    /// The messages are the prompt's, possibly without the turns a provider
+    /// already holds and with additions to the system prompt; dropped turns
+    /// are reported by their index in the prompt.
+    pub fn truncate(&self, messages: &[Message]) -> Result<Vec<Message>> {
+        let (keep_first_user, from_end) = match self.mode {
+            TruncationMode::Off => return Ok(messages.to_vec()),
+            TruncationMode::Auto => (true, false),
+            TruncationMode::AutoPreserveOrder | TruncationMode::Start => (false, false),
+            TruncationMode::End => (false, true),
+        };
+        let Some(budget) = self.budget else {
+            return Ok(messages.to_vec());
+        };
+        let mut excess = count_prompt_tokens(&*self.tokenizer, messages).saturating_sub(budget);
+        if excess == 0 {
+            return Ok(messages.to_vec());
+        }
+
+        // Turns in the order they are dropped, without the kept ones
+        let mut turns = turns(messages);
+        if from_end {
+            if !turns.is_empty() {
+                turns.remove(0);
+            }
+            turns.reverse();
+        } else {
+            turns.pop();
+        }
+        if keep_first_user {
+            if let Some(first_user) = turns.iter().position(|turn| messages[turn[0]].role == MessageRole::User) {
+                turns.remove(first_user);
+            }
+        }
+
+        let prompt_indexes = self.prompt_indexes(messages);
+        let mut messages = messages.to_vec();
+        let mut removed = Vec::new();
+        for turn in turns {
+            if excess == 0 {
+                break;
+            }
+            let tokens: u32 = turn.iter().map(|&position| count_message_tokens(&*self.tokenizer, &messages[position])).sum();
+
+            // Trim a text turn when part of it is enough, cutting characters
+            // in proportion to the tokens
+            if let [position] = turn[..] {
+                let trimmed = match &messages[position].content {
+                    MessageContent::Text(text) if tokens > excess => {
+                        let chars = text.chars().count();
+                        let cut = (excess as usize * chars).div_ceil(self.tokenizer.count_tokens(text).max(1) as usize);
+                        let trimmed: String = if from_end {
+                            text.chars().take(chars.saturating_sub(cut)).collect()
+                        } else {
+                            text.chars().skip(cut).collect()
+                        };
+                        Some((cut, trimmed)).filter(|(_, trimmed)| !trimmed.is_empty())
+                    }
+                    _ => None,
+                };
+                if let Some((cut, trimmed)) = trimmed {
+                    self.apply_policy(self.policy.evaluate_prompt_truncation(
+                        &format!("messages[{}].content", prompt_indexes[position]),
+                        json!(messages[position].content),
+                        Some(json!(trimmed)),
+                        &format!(
+                            "Trimmed {} characters from the {} of turn {} to fit the prompt into {} tokens",
+                            cut,
+                            if from_end { "end" } else { "start" },
+                            prompt_indexes[position],
+                            budget
+                        ),
+                    ))?;
+                    messages[position].content = MessageContent::Text(trimmed);
+                    excess = 0;
+                    continue;
+                }
+            }
+
+            for &position in &turn {
+                self.apply_policy(self.policy.evaluate_prompt_truncation(
+                    &format!("messages[{}]", prompt_indexes[position]),
+                    json!(messages[position]),
+                    None,
+                    &format!(
+                        "Dropped turn {} to fit the prompt into {} tokens",
+                        prompt_indexes[position],
+                        budget
+                    ),
+                ))?;
+            }
+            excess = excess.saturating_sub(tokens);
+            removed.extend(turn);
+        }
+
+        if excess > 0 {
+            log::warn!(
+                "Prompt exceeds its budget of {} tokens by an estimated {} tokens after truncation",
+                budget,
+                excess
+            );
+        }
+
+        Ok(messages.into_iter()
+            .enumerate()
+            .filter(|(position, _)| !removed.contains(position))
+            .map(|(_, message)| message)
+            .collect())
+    }
+
+    /// Index in the prompt of each message; system messages map to the
+    /// prompt's in order, and the other messages are the prompt's last
+    /// turns
+    fn prompt_indexes(&self, messages: &[Message]) -> Vec<usize> {
+        let (prompt_system, prompt_turns): (Vec<usize>, Vec<usize>) = (0..self.prompt_messages.len())
+            .partition(|&index| self.prompt_messages[index].role == MessageRole::System);
+        let mut next_system = 0;
+        let mut next_turn = prompt_turns.len()
+            .saturating_sub(messages.iter().filter(|m| m.role != MessageRole::System).count());
+
+        messages.iter()
+            .enumerate()
+            .map(|(position, m)| {
+                let (indexes, next) = if m.role == MessageRole::System {
+                    (&prompt_system, &mut next_system)
+                } else {
+                    (&prompt_turns, &mut next_turn)
+                };
+                *next += 1;
+                indexes.get(*next - 1).copied().unwrap_or(position)
+            })
+            .collect()
+    }
+
+    /// Record a policy result and act on it
+    fn apply_policy(&self, policy_result: PolicyResult) -> Result<()> {
+        let PolicyResult { action, lossiness_item } = policy_result;
+        if let Some(lossiness_item) = lossiness_item {
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.add_item(lossiness_item);
+            }
+        }
+
+        match action {
+            StrictnessAction::Fail { error } => Err(error),
+            StrictnessAction::Warn { message } => {
+                log::warn!("{}", message);
+                Ok(())
+            }
+            StrictnessAction::Proceed | StrictnessAction::Coerce { .. } => Ok(()),
+        }
+    }
+}
+
+/// Truncation mode of the prompt, or the model's default
+pub fn truncation_mode(context: &TranslationContext) -> TruncationMode {
+    context.prompt_spec.preferences.as_ref()
+        .and_then(|preferences| preferences.prompt_truncation)
+        .or_else(|| {
+            context.model_spec.constraints.extensions.as_ref()
+                .and_then(|extensions| extensions.prompt_truncation.as_ref())
+                .and_then(|truncation| truncation.default_mode)
+        })
+        .unwrap_or(TruncationMode::Auto)
+}
+
+/// Positions of the non-system turns; an assistant message's tool calls and
+/// the tool results after it form one turn
+fn turns(messages: &[Message]) -> Vec<Vec<usize>> {
+    let mut turns: Vec<Vec<usize>> = Vec::new();
+    for (position, message) in messages.iter().enumerate() {
+        match message.role {
+            MessageRole::System => {}
+            MessageRole::Tool if turns.last().is_some_and(|turn| messages[turn[0]].tool_calls.is_some()) => {
+                turns.last_mut().unwrap().push(position);
+            }
+            _ => turns.push(vec![position]),
+        }
+    }
+    turns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::register_tokenizer;
+    use crate::types::{Limits, Preferences, StrictMode, ToolCall};
+    use crate::{
+        ArgsEncoding, ConstraintLimits, Constraints, EndpointConfig, Endpoints, EventSelector, InputModes,
+        JsonOutputConfig, LossinessItem, Mappings, ModelSpec, PromptSpec, ProviderInfo, ProviderSpec,
+        ResponseNormalization, StreamNormalization, SyncNormalization, ToolingConfig,
+    };
+    use std::collections::HashMap;
+
+    /// Counts one token per word, so budgets are easy to follow
+    struct Words;
+
+    impl Tokenizer for Words {
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.split_whitespace().count() as u32
+        }
+    }
+
+    fn create_test_context(
+        messages: Vec<Message>,
+        max_prompt_tokens: u32,
+        mode: TruncationMode,
+        strict_mode: StrictMode,
+    ) -> TranslationContext {
+        register_tokenizer("truncation-test", Arc::new(Words));
+
+        let prompt_spec = PromptSpec {
+            model_class: "Chat".to_string(),
+            messages,
+            tools: None,
+            tool_choice: None,
+            response_format: None,
+            sampling: None,
+            limits: Some(Limits {
+                max_output_tokens: None,
+                reasoning_tokens: None,
+                max_prompt_tokens: Some(max_prompt_tokens),
+            }),
+            media: None,
+            advanced: None,
+            strict_mode,
+            rag: None,
+            conversation: None,
+            preferences: Some(Preferences { prompt_truncation: Some(mode), ..Default::default() }),
+        };
+
+        let provider_spec = ProviderSpec {
+            spec_version: "1.0.0".to_string(),
+            provider: ProviderInfo {
+                name: "test-provider".to_string(),
+                base_url: "https://api.test.com".to_string(),
+                headers: HashMap::new(),
+            },
+            models: vec![],
+        };
+
+        let model_spec = ModelSpec {
+            id: "test-model".to_string(),
+            aliases: None,
+            family: "truncation-test".to_string(),
+            endpoints: Endpoints {
+                chat_completion: EndpointConfig {
+                    method: "POST".to_string(),
+                    path: "/chat".to_string(),
+                    protocol: "https".to_string(),
+                    query: None,
+                    headers: None,
+                },
+                streaming_chat_completion: EndpointConfig {
+                    method: "POST".to_string(),
+                    path: "/chat".to_string(),
+                    protocol: "https".to_string(),
+                    query: None,
+                    headers: None,
+                },
+            },
+            input_modes: InputModes {
+                messages: true,
+                single_text: false,
+                images: false,
+            },
+            tooling: ToolingConfig {
+                tools_supported: false,
+                parallel_tool_calls_default: false,
+                can_disable_parallel_tool_calls: false,
+                disable_switch: None,
+                rendering: None,
+            },
+            json_output: JsonOutputConfig {
+                native_param: false,
+                strategy: "system_prompt".to_string(),
+                instruction: None,
+                prefill: false,
+            },
+            capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
+            parameters: json!({}),
+            constraints: Constraints {
+                system_prompt_location: "first".to_string(),
+                forbid_unknown_top_level_fields: false,
+                mutually_exclusive: vec![],
+                resolution_preferences: vec![],
+                limits: ConstraintLimits {
+                    max_tool_schema_bytes: 100000,
+                    max_system_prompt_bytes: 10000,
+                    context_window: None,
+                },
+                extensions: None,
+            },
+            mappings: Mappings {
+                paths: HashMap::new(),
+                flags: HashMap::new(),
+            },
+            response_normalization: ResponseNormalization {
+                sync: SyncNormalization {
+                    content_path: "content".to_string(),
+                    finish_reason_path: "finish".to_string(),
+                    finish_reason_map: HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
+                },
+                stream: StreamNormalization {
+                    protocol: "sse".to_string(),
+                    event_selector: EventSelector {
+                        type_path: "type".to_string(),
+                        routes: vec![],
+                    },
+                    usage: None,
+                },
+            },
+            extensions: None,
+        };
+
+        TranslationContext::new(prompt_spec, provider_spec, model_spec, strict_mode)
+    }
+
+    /// Truncate the messages, returning them with the lossiness items
+    fn truncate(
+        context: &TranslationContext,
+        messages: &[Message],
+    ) -> Result<(Vec<Message>, Vec<LossinessItem>)> {
+        let policy = StrictnessPolicy::new(context.clone());
+        let tracker = Arc::new(Mutex::new(LossinessTracker::new(context.strict_mode)));
+        let truncated = PromptTruncator::new(context, &policy, &tracker).truncate(messages)?;
+        let items = Arc::try_unwrap(tracker).unwrap().into_inner().unwrap().build_report().items;
+        Ok((truncated, items))
+    }
+
+    fn text(message: &Message) -> &str {
+        match &message.content {
+            MessageContent::Text(text) => text,
+            MessageContent::Parts(_) => panic!("expected text content"),
+        }
+    }
+
+    fn paths(items: &[LossinessItem]) -> Vec<&str> {
+        items.iter().map(|item| item.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_turns() {
+        let call = ToolCall { name: "get_weather".to_string(), arguments: json!({"city": "Paris"}), id: Some("call_1".to_string()) };
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("Weather in Paris?"),
+            Message::assistant("").with_tool_calls(vec![call]),
+            Message::tool("call_1", "Sunny"),
+            Message::assistant("It is sunny."),
+            Message::user("Thanks"),
+        ];
+        assert_eq!(turns(&messages), vec![vec![1], vec![2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_truncate_within_budget() {
+        // 4 tokens of overhead per message plus one per word
+        let messages = vec![Message::system("Be brief."), Message::user("Weather in Paris?")];
+        let context = create_test_context(messages.clone(), 13, TruncationMode::Auto, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.len(), 2);
+        assert!(items.is_empty());
+
+        let context = create_test_context(messages.clone(), 1, TruncationMode::Off, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.len(), 2);
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_drops_oldest_turns() {
+        // 6 + 8 + 8 + 5 = 27 tokens, 7 over the budget
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("one two three four"),
+            Message::assistant("a b c d"),
+            Message::user("last"),
+        ];
+        let context = create_test_context(messages.clone(), 20, TruncationMode::Start, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.iter().map(text).collect::<Vec<_>>(), vec!["Be brief.", "a b c d", "last"]);
+        assert_eq!(paths(&items), vec!["messages[1]"]);
+        assert!(items[0].message.contains("Dropped turn 1"));
+    }
+
+    #[test]
+    fn test_truncate_trims_in_proportion() {
+        // 6 + 44 + 5 + 5 = 60 tokens; 10 of the 40 words cost 20 characters
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("w ".repeat(40)),
+            Message::assistant("ok"),
+            Message::user("last"),
+        ];
+        let context = create_test_context(messages.clone(), 50, TruncationMode::Start, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.len(), 4);
+        assert_eq!(text(&truncated[1]), "w ".repeat(30));
+        assert_eq!(paths(&items), vec!["messages[1].content"]);
+        assert!(items[0].message.contains("Trimmed 20 characters from the start of turn 1"));
+    }
+
+    #[test]
+    fn test_truncate_end_mode() {
+        // The newest turns go first and are trimmed from their end
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("first question"),
+            Message::user("w ".repeat(40)),
+        ];
+        let context = create_test_context(messages.clone(), 46, TruncationMode::End, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(text(&truncated[1]), "first question");
+        assert_eq!(text(&truncated[2]), "w ".repeat(30));
+        assert!(items[0].message.contains("from the end of turn 2"));
+
+        // The oldest turn is kept even when newer ones are dropped
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("w ".repeat(40)),
+            Message::assistant("ok"),
+            Message::user("last"),
+        ];
+        let context = create_test_context(messages.clone(), 50, TruncationMode::End, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.len(), 2);
+        assert_eq!(text(&truncated[1]), "w ".repeat(40));
+        assert_eq!(paths(&items), vec!["messages[3]", "messages[2]"]);
+    }
+
+    #[test]
+    fn test_truncate_auto_keeps_first_user_turn() {
+        // 6 + 6 + 8 + 8 + 5 + 5 = 38 tokens, 8 over the budget
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("first task"),
+            Message::assistant("a b c d"),
+            Message::user("e f g h"),
+            Message::assistant("ok"),
+            Message::user("last"),
+        ];
+        let context = create_test_context(messages.clone(), 30, TruncationMode::Auto, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(
+            truncated.iter().map(text).collect::<Vec<_>>(),
+            vec!["Be brief.", "first task", "e f g h", "ok", "last"]
+        );
+        assert_eq!(paths(&items), vec!["messages[2]"]);
+
+        // Strict mode fails instead
+        let context = create_test_context(messages.clone(), 30, TruncationMode::Auto, StrictMode::Strict);
+        assert!(truncate(&context, &messages).is_err());
+    }
+
+    #[test]
+    fn test_truncate_reports_prompt_indexes() {
+        // The provider already holds the first two turns, so the messages
+        // are the prompt's system message and its last three turns
+        let prompt_messages = vec![
+            Message::system("Be brief."),
+            Message::user("one"),
+            Message::assistant("two"),
+            Message::user("a b c d"),
+            Message::assistant("ok"),
+            Message::user("last"),
+        ];
+        let messages = vec![
+            prompt_messages[0].clone(),
+            prompt_messages[3].clone(),
+            prompt_messages[4].clone(),
+            prompt_messages[5].clone(),
+        ];
+        let context = create_test_context(prompt_messages, 18, TruncationMode::Start, StrictMode::Warn);
+        let (truncated, items) = truncate(&context, &messages).unwrap();
+        assert_eq!(truncated.iter().map(text).collect::<Vec<_>>(), vec!["Be brief.", "ok", "last"]);
+        assert_eq!(paths(&items), vec!["messages[3]"]);
+        assert!(items[0].message.contains("Dropped turn 3"));
+    }
+}
@@ -8,17 +8,58 @@
 
 use crate::Result;
 use super::{ValidationError, ValidationSeverity};
+use super::super::size_limits::{system_prompt_bytes, tool_schema_bytes};
 use super::super::TranslationContext;
 
 /// Validate provider-specific constraints
-pub fn validate_provider_constraints(context: &TranslationContext) -> Result<Vec<ValidationError>> {
+pub fn validate_provider_constraints(_context: &TranslationContext) -> Result<Vec<ValidationError>> {
     let errors = Vec::new();
     
-    // Check for unknown top-level fields if provider forbids them
-    if context.model_spec.constraints.forbid_unknown_top_level_fields {
-        // This would require introspection of the JSON structure
-        // For now, we'll add a placeholder that could be implemented
-        // when we have more detailed provider specifications
+    // Unknown top-level fields only exist once the provider request is
+    // assembled; translation drops them when the provider forbids them
+    
+    Ok(errors)
+}
+
+/// Validate the system prompt and tool definitions against the model's size
+/// limits; outside strict mode translation reduces them or warns
+pub fn validate_size_limits(context: &TranslationContext) -> Result<Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    let limits = &context.model_spec.constraints.limits;
+    let severity = if context.should_fail_on_error() {
+        ValidationSeverity::Error
+    } else {
+        ValidationSeverity::Warning
+    };
+    
+    let system_bytes = system_prompt_bytes(&context.prompt_spec.messages);
+    if system_bytes > limits.max_system_prompt_bytes as usize {
+        errors.push(ValidationError {
+            field_path: "messages".to_string(),
+            message: format!(
+                "System prompt exceeds the provider limit of {} bytes",
+                limits.max_system_prompt_bytes
+            ),
+            expected: Some(format!("≤ {} bytes", limits.max_system_prompt_bytes)),
+            actual: Some(format!("{} bytes", system_bytes)),
+            severity,
+        });
+    }
+    
+    if let Some(ref tools) = context.prompt_spec.tools {
+        let tool_bytes = tool_schema_bytes(tools);
+        if tool_bytes > limits.max_tool_schema_bytes as usize {
+            errors.push(ValidationError {
+                field_path: "tools".to_string(),
+                message: format!(
+                    "Tool definitions exceed the provider limit of {} bytes",
+                    limits.max_tool_schema_bytes
+                ),
+                expected: Some(format!("≤ {} bytes", limits.max_tool_schema_bytes)),
+                actual: Some(format!("{} bytes", tool_bytes)),
+                severity,
+            });
+        }
     }
     
     Ok(errors)
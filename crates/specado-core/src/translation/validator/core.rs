@@ -14,7 +14,7 @@ use super::field_validators::{
     validate_limits, validate_tools, validate_media, validate_response_format
 };
 use super::constraint_validators::{
-    validate_provider_constraints, validate_mutually_exclusive_fields
+    validate_provider_constraints, validate_size_limits, validate_mutually_exclusive_fields
 };
 
 /// Pre-validator for checking input compatibility before translation
@@ -77,6 +77,7 @@ impl<'a> PreValidator<'a> {
         
         // Run provider-specific validation
         errors.extend(validate_provider_constraints(self.context)?);
+        errors.extend(validate_size_limits(self.context)?);
         
         // Run mutually exclusive field validation
         errors.extend(validate_mutually_exclusive_fields(self.context)?);
@@ -7,8 +7,11 @@
 //! Copyright (c) 2025 Specado Team
 //! Licensed under the Apache-2.0 license
 
-use crate::{Result, MessageRole};
+use crate::{Result, Message, MessageRole};
+use crate::tokenizer::{count_prompt_tokens, tokenizer_for};
+use crate::types::TruncationMode;
 use super::{ValidationError, ValidationSeverity};
+use super::super::truncation::truncation_mode;
 use super::super::TranslationContext;
 
 /// Validate messages array
@@ -26,12 +29,16 @@ pub fn validate_messages(context: &TranslationContext) -> Result<Vec<ValidationE
         return Ok(errors);
     }
     
+    // Tool call ids requested by earlier assistant messages
+    let mut known_tool_call_ids = std::collections::HashSet::new();
+
     // Validate individual messages
     for (i, message) in context.prompt_spec.messages.iter().enumerate() {
         let field_prefix = format!("messages[{}]", i);
+        let has_tool_calls = message.tool_calls.as_ref().is_some_and(|calls| !calls.is_empty());
         
-        // Check message content is not empty
-        if message.content.trim().is_empty() {
+        // Check message content is not empty (assistant tool call turns may omit text)
+        if message.content.is_blank() && !has_tool_calls {
             errors.push(ValidationError {
                 field_path: format!("{}.content", field_prefix),
                 message: "Message content cannot be empty".to_string(),
@@ -43,15 +50,18 @@ pub fn validate_messages(context: &TranslationContext) -> Result<Vec<ValidationE
         
         // Check message content length against constraints
         let max_content_length = 1_000_000; // 1MB character limit
-        if message.content.len() > max_content_length {
+        let content_length = message.content.text().len();
+        if content_length > max_content_length {
             errors.push(ValidationError {
                 field_path: format!("{}.content", field_prefix),
                 message: format!("Message content exceeds maximum length of {} characters", max_content_length),
                 expected: Some(format!("≤ {} characters", max_content_length)),
-                actual: Some(format!("{} characters", message.content.len())),
+                actual: Some(format!("{} characters", content_length)),
                 severity: ValidationSeverity::Error,
             });
         }
+
+        errors.extend(validate_tool_turn(&field_prefix, message, &mut known_tool_call_ids));
     }
     
     // Validate system prompt requirements
@@ -60,6 +70,64 @@ pub fn validate_messages(context: &TranslationContext) -> Result<Vec<ValidationE
     Ok(errors)
 }
 
+/// Validate tool calls and tool results on a single message
+fn validate_tool_turn(
+    field_prefix: &str,
+    message: &Message,
+    known_tool_call_ids: &mut std::collections::HashSet<String>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if let Some(tool_calls) = &message.tool_calls {
+        if message.role != MessageRole::Assistant {
+            errors.push(ValidationError {
+                field_path: format!("{}.tool_calls", field_prefix),
+                message: "Only assistant messages can contain tool calls".to_string(),
+                expected: Some("assistant role".to_string()),
+                actual: Some(format!("{:?}", message.role).to_lowercase()),
+                severity: ValidationSeverity::Error,
+            });
+        }
+
+        for (j, call) in tool_calls.iter().enumerate() {
+            match &call.id {
+                Some(id) if !id.is_empty() => {
+                    known_tool_call_ids.insert(id.clone());
+                }
+                _ => errors.push(ValidationError {
+                    field_path: format!("{}.tool_calls[{}].id", field_prefix, j),
+                    message: "Tool calls in conversation history must have an id".to_string(),
+                    expected: Some("Non-empty tool call id".to_string()),
+                    actual: Some("missing".to_string()),
+                    severity: ValidationSeverity::Error,
+                }),
+            }
+        }
+    }
+
+    if message.role == MessageRole::Tool {
+        match message.tool_call_id.as_deref() {
+            None | Some("") => errors.push(ValidationError {
+                field_path: format!("{}.tool_call_id", field_prefix),
+                message: "Tool messages must reference the tool call they answer".to_string(),
+                expected: Some("Non-empty tool_call_id".to_string()),
+                actual: Some("missing".to_string()),
+                severity: ValidationSeverity::Error,
+            }),
+            Some(id) if !known_tool_call_ids.contains(id) => errors.push(ValidationError {
+                field_path: format!("{}.tool_call_id", field_prefix),
+                message: format!("Tool result '{}' does not match any earlier assistant tool call", id),
+                expected: Some("id of a preceding assistant tool call".to_string()),
+                actual: Some(id.to_string()),
+                severity: ValidationSeverity::Warning,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    errors
+}
+
 /// Validate system prompt requirements based on provider constraints
 fn validate_system_prompt_requirements(context: &TranslationContext) -> Result<Vec<ValidationError>> {
     let mut errors = Vec::new();
@@ -104,21 +172,6 @@ fn validate_system_prompt_requirements(context: &TranslationContext) -> Result<V
         _ => {}
     }
     
-    // Check system prompt size limits
-    let max_system_bytes = context.model_spec.constraints.limits.max_system_prompt_bytes;
-    for (index, message) in &system_messages {
-        let byte_count = message.content.len();
-        if byte_count > max_system_bytes as usize {
-            errors.push(ValidationError {
-                field_path: format!("messages[{}].content", index),
-                message: format!("System prompt exceeds maximum size of {} bytes", max_system_bytes),
-                expected: Some(format!("≤ {} bytes", max_system_bytes)),
-                actual: Some(format!("{} bytes", byte_count)),
-                severity: ValidationSeverity::Error,
-            });
-        }
-    }
-    
     Ok(errors)
 }
 
@@ -194,6 +247,38 @@ pub fn validate_sampling_params(context: &TranslationContext) -> Result<Vec<Vali
                 });
             }
         }
+        
+        if sampling.candidate_count == Some(0) {
+            errors.push(ValidationError {
+                field_path: "sampling.candidate_count".to_string(),
+                message: "Candidate count must be at least 1".to_string(),
+                expected: Some("candidate_count ≥ 1".to_string()),
+                actual: Some("0".to_string()),
+                severity: ValidationSeverity::Error,
+            });
+        }
+
+        if let Some(ref stop) = sampling.stop {
+            if stop.iter().any(String::is_empty) {
+                errors.push(ValidationError {
+                    field_path: "sampling.stop".to_string(),
+                    message: "Stop sequences must not be empty".to_string(),
+                    expected: Some("non-empty strings".to_string()),
+                    actual: Some(format!("{:?}", stop)),
+                    severity: ValidationSeverity::Error,
+                });
+            }
+        }
+
+        if sampling.top_logprobs.is_some() && sampling.logprobs != Some(true) {
+            errors.push(ValidationError {
+                field_path: "sampling.top_logprobs".to_string(),
+                message: "Top logprobs are only returned when logprobs is enabled".to_string(),
+                expected: Some("logprobs: true".to_string()),
+                actual: Some(format!("{:?}", sampling.logprobs)),
+                severity: ValidationSeverity::Warning,
+            });
+        }
     }
     
     Ok(errors)
@@ -264,6 +349,38 @@ pub fn validate_limits(context: &TranslationContext) -> Result<Vec<ValidationErr
         }
     }
     
+    // Check the prompt's size; unless truncation is off, translation drops
+    // turns to fit it
+    let tokens = count_prompt_tokens(&*tokenizer_for(&context.model_spec.family), &context.prompt_spec.messages);
+    let truncates = truncation_mode(context) != TruncationMode::Off;
+    let limits = context.prompt_spec.limits.as_ref();
+    
+    if let Some(max_prompt) = limits.and_then(|l| l.max_prompt_tokens).filter(|&max| max > 0 && tokens > max) {
+        errors.push(ValidationError {
+            field_path: "limits.max_prompt_tokens".to_string(),
+            message: format!("Prompt of about {} tokens exceeds max prompt tokens of {}", tokens, max_prompt),
+            expected: Some(format!("≤ {} tokens", max_prompt)),
+            actual: Some(tokens.to_string()),
+            severity: if truncates { ValidationSeverity::Info } else { ValidationSeverity::Error },
+        });
+    }
+    
+    if let Some(context_window) = context.model_spec.constraints.limits.context_window {
+        let max_output = limits.and_then(|l| l.max_output_tokens).unwrap_or(0);
+        if tokens.saturating_add(max_output) > context_window {
+            errors.push(ValidationError {
+                field_path: "limits.max_output_tokens".to_string(),
+                message: format!(
+                    "Prompt of about {} tokens plus {} output tokens exceeds the context window of {}",
+                    tokens, max_output, context_window
+                ),
+                expected: Some(format!("≤ {} tokens", context_window)),
+                actual: Some((tokens + max_output).to_string()),
+                severity: if truncates { ValidationSeverity::Info } else { ValidationSeverity::Warning },
+            });
+        }
+    }
+    
     Ok(errors)
 }
 
@@ -314,19 +431,6 @@ pub fn validate_tools(context: &TranslationContext) -> Result<Vec<ValidationErro
                     severity: ValidationSeverity::Warning,
                 });
             }
-            
-            // Check tool schema size
-            let schema_size = serde_json::to_string(&tool.json_schema)?.len();
-            let max_schema_bytes = context.model_spec.constraints.limits.max_tool_schema_bytes;
-            if schema_size > max_schema_bytes as usize {
-                errors.push(ValidationError {
-                    field_path: format!("{}.json_schema", field_prefix),
-                    message: format!("Tool schema exceeds maximum size of {} bytes", max_schema_bytes),
-                    expected: Some(format!("≤ {} bytes", max_schema_bytes)),
-                    actual: Some(format!("{} bytes", schema_size)),
-                    severity: ValidationSeverity::Error,
-                });
-            }
         }
         
         // Validate tool_choice if present
@@ -12,7 +12,7 @@ mod tests {
     use crate::{
         Error, StrictMode, Constraints, ConstraintLimits, EndpointConfig, Endpoints, 
         InputModes, JsonOutputConfig, Mappings, Message, MessageRole, ProviderInfo, 
-        ResponseNormalization, StreamNormalization, SyncNormalization, ToolingConfig, 
+        ResponseNormalization, StreamNormalization, SyncNormalization, ArgsEncoding, ToolingConfig, 
         EventSelector, ProviderSpec, ModelSpec, PromptSpec, Tool, Limits, MediaConfig, 
         SamplingParams,
     };
@@ -24,9 +24,12 @@ mod tests {
             model_class: "Chat".to_string(),
             messages: vec![Message {
                 role: MessageRole::User,
-                content: "Test".to_string(),
+                content: "Test".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             }],
             tools: None,
             tool_choice: None,
@@ -36,6 +39,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
 
         let provider_spec = ProviderSpec {
@@ -78,12 +84,17 @@ mod tests {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: true,
                 strategy: "native".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: serde_json::json!({}),
             constraints: Constraints {
                 system_prompt_location: "first".to_string(),
@@ -93,7 +104,9 @@ mod tests {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 100000,
                     max_system_prompt_bytes: 10000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: HashMap::new(),
@@ -104,6 +117,26 @@ mod tests {
                     content_path: "content".to_string(),
                     finish_reason_path: "finish".to_string(),
                     finish_reason_map: HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -111,8 +144,10 @@ mod tests {
                         type_path: "type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         };
 
         TranslationContext::new(prompt_spec, provider_spec, model_spec, strict_mode)
@@ -275,6 +310,11 @@ mod tests {
             top_k: None,
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         // Test strict mode
@@ -330,6 +370,11 @@ mod tests {
             top_k: None,
             frequency_penalty: Some(3.0), // Invalid frequency penalty
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         context.prompt_spec.limits = Some(Limits {
             max_output_tokens: Some(0), // Invalid zero value
@@ -421,15 +466,21 @@ mod tests {
         context.prompt_spec.messages = vec![
             Message {
                 role: MessageRole::User,
-                content: "Hello".to_string(),
+                content: "Hello".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::System,
-                content: "You are a helpful assistant.".to_string(),
+                content: "You are a helpful assistant.".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
         ];
         
@@ -447,15 +498,21 @@ mod tests {
         context_none.prompt_spec.messages = vec![
             Message {
                 role: MessageRole::User,
-                content: "Hello".to_string(),
+                content: "Hello".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::System,
-                content: "You are a helpful assistant.".to_string(),
+                content: "You are a helpful assistant.".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
         ];
         context_none.model_spec.constraints.system_prompt_location = "none".to_string();
@@ -492,4 +549,90 @@ mod tests {
         assert_eq!(token_error.actual.as_ref().unwrap(), "0");
         assert!(token_error.message.contains("greater than 0"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_validate_tool_turns() {
+        let mut context = create_test_context_with_mode(StrictMode::Strict);
+        context.prompt_spec.messages = vec![
+            Message::user("Weather in Paris?"),
+            Message::assistant("").with_tool_calls(vec![crate::ToolCall {
+                name: "get_weather".to_string(),
+                arguments: serde_json::json!({"city": "Paris"}),
+                id: Some("call_1".to_string()),
+            }]),
+            Message::tool("call_1", "sunny"),
+            Message::tool("call_2", "rainy"),
+            Message { tool_call_id: None, ..Message::tool("", "orphan") },
+        ];
+
+        let validator = PreValidator::new(&context);
+        let errors = validator.validate().unwrap();
+
+        // The assistant tool call turn may have empty text
+        assert!(!errors.iter().any(|e| e.field_path == "messages[1].content"));
+        assert!(!errors.iter().any(|e| e.field_path.starts_with("messages[2]")));
+
+        let unknown = errors.iter().find(|e| e.field_path == "messages[3].tool_call_id").unwrap();
+        assert_eq!(unknown.severity, ValidationSeverity::Warning);
+        let missing = errors.iter().find(|e| e.field_path == "messages[4].tool_call_id").unwrap();
+        assert_eq!(missing.severity, ValidationSeverity::Error);
+
+        // Tool calls are only valid on assistant messages
+        context.prompt_spec.messages[0].tool_calls = Some(vec![crate::ToolCall {
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({}),
+            id: None,
+        }]);
+        let errors = PreValidator::new(&context).validate().unwrap();
+        assert!(errors.iter().any(|e| e.field_path == "messages[0].tool_calls"));
+        assert!(errors.iter().any(|e| e.field_path == "messages[0].tool_calls[0].id"));
+    }
+
+    #[test]
+    fn test_validate_prompt_tokens() {
+        let mut context = create_test_context_with_mode(StrictMode::Strict);
+        context.prompt_spec.messages = vec![Message::user("word ".repeat(100))];
+        context.prompt_spec.limits = Some(Limits {
+            max_output_tokens: Some(1000),
+            reasoning_tokens: None,
+            max_prompt_tokens: Some(50),
+        });
+        context.model_spec.constraints.limits.context_window = Some(1000);
+
+        // Truncation fits the prompt during translation
+        let errors = PreValidator::new(&context).validate().unwrap();
+        let too_long = errors.iter().find(|e| e.field_path == "limits.max_prompt_tokens").unwrap();
+        assert_eq!(too_long.severity, ValidationSeverity::Info);
+        assert!(PreValidator::new(&context).validate_strict().is_ok());
+
+        context.prompt_spec.preferences = Some(crate::Preferences {
+            prompt_truncation: Some(crate::TruncationMode::Off),
+            ..Default::default()
+        });
+        let errors = PreValidator::new(&context).validate().unwrap();
+        let too_long = errors.iter().find(|e| e.field_path == "limits.max_prompt_tokens").unwrap();
+        assert_eq!(too_long.severity, ValidationSeverity::Error);
+        assert_eq!(too_long.actual.as_deref(), Some("129"));
+        let window = errors.iter().find(|e| e.message.contains("context window")).unwrap();
+        assert_eq!(window.severity, ValidationSeverity::Warning);
+    }
+
+    #[test]
+    fn test_validate_size_limits() {
+        let mut context = create_test_context_with_mode(StrictMode::Strict);
+        context.prompt_spec.messages = vec![Message::system("Be brief. ".repeat(10)), Message::user("Hello")];
+        context.model_spec.constraints.limits.max_system_prompt_bytes = 50;
+
+        let errors = PreValidator::new(&context).validate().unwrap();
+        let oversized = errors.iter().find(|e| e.message.starts_with("System prompt exceeds")).unwrap();
+        assert_eq!(oversized.severity, ValidationSeverity::Error);
+        assert_eq!(oversized.actual.as_deref(), Some("100 bytes"));
+
+        // Outside strict mode translation reduces or warns
+        context.strict_mode = StrictMode::Coerce;
+        let errors = PreValidator::with_mode(&context, ValidationMode::Strict).validate().unwrap();
+        let oversized = errors.iter().find(|e| e.message.starts_with("System prompt exceeds")).unwrap();
+        assert_eq!(oversized.severity, ValidationSeverity::Warning);
+        assert!(PreValidator::new(&context).validate_strict().is_ok());
+    }
+}
@@ -3,15 +3,20 @@
 //! This module defines the fundamental data structures used throughout
 //! the library for representing prompts, providers, and translation results.
 
+use futures_util::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 // Re-export error types for convenience
 pub use crate::error::{LossinessCode, Severity, StrictMode};
 
 // Import capability types for optional model capability metadata
 use crate::specs::Capabilities;
+use crate::response::TokenUsage;
+use crate::translation::transformer::TransformationRule;
 
 /// Represents a uniform prompt specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +56,18 @@ pub struct PromptSpec {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub advanced: Option<AdvancedParams>,
     
+    /// Retrieval-augmented generation documents and sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rag: Option<RagConfig>,
+    
+    /// Continuation of a conversation held by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ConversationConfig>,
+    
+    /// Translation preferences
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<Preferences>,
+    
     /// Strictness mode for translation
     #[serde(default = "default_strict_mode")]
     pub strict_mode: StrictMode,
@@ -76,6 +93,9 @@ impl Default for PromptSpec {
             limits: None,
             media: None,
             advanced: None,
+            rag: None,
+            conversation: None,
+            preferences: None,
             strict_mode: default_strict_mode(),
         }
     }
@@ -85,7 +105,7 @@ impl PromptSpec {
     /// Create a prompt with just a message - everything else uses defaults
     pub fn new(prompt: impl Into<String>) -> Self {
         Self {
-            messages: vec![Message::user(prompt)],
+            messages: vec![Message::user(MessageContent::Text(prompt.into()))],
             ..Default::default()
         }
     }
@@ -181,6 +201,11 @@ pub struct ModelSpec {
     #[serde(default)]
     pub capabilities: Option<Capabilities>,
     
+    /// Request message rendering template (OpenAI-style defaults if unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub message_rendering: Option<MessageRendering>,
+    
     /// Parameter mappings and constraints
     pub parameters: Value, // Flexible for provider-specific params
     
@@ -190,8 +215,16 @@ pub struct ModelSpec {
     /// Field mappings
     pub mappings: Mappings,
     
+    /// Declarative transformations applied to the provider request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transformations: Vec<TransformationRule>,
+    
     /// Response normalization rules
     pub response_normalization: ResponseNormalization,
+    
+    /// Model-specific extensions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<ModelExtensions>,
 }
 
 /// Message in a conversation
@@ -200,8 +233,8 @@ pub struct Message {
     /// Role of the message sender
     pub role: MessageRole,
     
-    /// Content of the message
-    pub content: String,
+    /// Content of the message, either plain text or ordered content parts
+    pub content: MessageContent,
     
     /// Optional name for the message
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -210,38 +243,232 @@ pub struct Message {
     /// Optional metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Value>,
+    
+    /// Tool calls requested by an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    
+    /// Tool call a tool message is answering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    
+    /// Reasoning blocks of a previous assistant turn, sent back so the
+    /// provider can verify them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<Vec<ReasoningBlock>>,
 }
 
 impl Message {
     /// Create a user message
-    pub fn user(content: impl Into<String>) -> Self {
+    pub fn user(content: impl Into<MessageContent>) -> Self {
         Self {
             role: MessageRole::User,
             content: content.into(),
             name: None,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }
     }
     
     /// Create an assistant message
-    pub fn assistant(content: impl Into<String>) -> Self {
+    pub fn assistant(content: impl Into<MessageContent>) -> Self {
         Self {
             role: MessageRole::Assistant,
             content: content.into(),
             name: None,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }
     }
     
     /// Create a system message
-    pub fn system(content: impl Into<String>) -> Self {
+    pub fn system(content: impl Into<MessageContent>) -> Self {
         Self {
             role: MessageRole::System,
             content: content.into(),
             name: None,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
+        }
+    }
+    
+    /// Create a tool message carrying the result of a tool call
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<MessageContent>) -> Self {
+        Self {
+            role: MessageRole::Tool,
+            content: content.into(),
+            name: None,
+            metadata: None,
+            tool_calls: None,
+            tool_call_id: Some(tool_call_id.into()),
+            reasoning: None,
+        }
+    }
+    
+    /// Builder method to attach tool calls to an assistant message
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.tool_calls = Some(tool_calls);
+        self
+    }
+}
+
+/// Message content: a plain string or an ordered list of content parts
+///
+/// Plain strings serialize unchanged, so existing prompts keep working.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum MessageContent {
+    /// Plain text content
+    Text(String),
+    /// Ordered multimodal content parts
+    Parts(Vec<ContentPart>),
+}
+
+impl MessageContent {
+    /// Concatenated text of the content, ignoring non-text parts
+    pub fn text(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            MessageContent::Text(text) => std::borrow::Cow::Borrowed(text),
+            MessageContent::Parts(parts) => std::borrow::Cow::Owned(
+                parts.iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+    }
+
+    /// Whether the content has no text and no media
+    pub fn is_blank(&self) -> bool {
+        match self {
+            MessageContent::Text(text) => text.trim().is_empty(),
+            MessageContent::Parts(parts) => parts.iter().all(|part| match part {
+                ContentPart::Text { text } => text.trim().is_empty(),
+                _ => false,
+            }),
+        }
+    }
+
+    /// Content as a list of parts
+    pub fn parts(&self) -> Vec<ContentPart> {
+        match self {
+            MessageContent::Text(text) => vec![ContentPart::Text { text: text.clone() }],
+            MessageContent::Parts(parts) => parts.clone(),
+        }
+    }
+}
+
+impl Default for MessageContent {
+    fn default() -> Self {
+        MessageContent::Text(String::new())
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ContentPart>> for MessageContent {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        MessageContent::Parts(parts)
+    }
+}
+
+impl PartialEq<str> for MessageContent {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, MessageContent::Text(text) if text == other)
+    }
+}
+
+impl PartialEq<&str> for MessageContent {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl std::fmt::Display for MessageContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+/// A single part of multimodal message content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Text
+    Text { text: String },
+    /// Image from a URL or inline base64 data
+    Image {
+        #[serde(flatten)]
+        source: MediaSource,
+        /// Provider detail hint (e.g. "low", "high")
+        #[serde(skip_serializing_if = "Option::is_none")]
+        detail: Option<String>,
+    },
+    /// Audio from a URL or inline base64 data
+    Audio {
+        #[serde(flatten)]
+        source: MediaSource,
+    },
+    /// Reference to a file previously uploaded to the provider
+    File {
+        file_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+    },
+}
+
+impl ContentPart {
+    /// Create a text part
+    pub fn text(text: impl Into<String>) -> Self {
+        ContentPart::Text { text: text.into() }
+    }
+
+    /// Create an image part from a URL
+    pub fn image_url(url: impl Into<String>) -> Self {
+        ContentPart::Image {
+            source: MediaSource::Url { url: url.into() },
+            detail: None,
         }
     }
+
+    /// Create an image part from base64 data
+    pub fn image_base64(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        ContentPart::Image {
+            source: MediaSource::Base64 { data: data.into(), mime_type: mime_type.into() },
+            detail: None,
+        }
+    }
+}
+
+/// Location of media content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum MediaSource {
+    /// Media available at a URL
+    Url { url: String },
+    /// Inline base64-encoded media
+    Base64 { data: String, mime_type: String },
 }
 
 /// Message role enumeration
@@ -251,6 +478,7 @@ pub enum MessageRole {
     System,
     User,
     Assistant,
+    Tool,
 }
 
 /// Tool definition
@@ -295,23 +523,75 @@ pub enum ResponseFormat {
     },
 }
 
+impl ResponseFormat {
+    /// JSON Schema the response must match, unwrapping OpenAI-style
+    /// `{"name": ..., "schema": ...}` definitions
+    pub fn schema(&self) -> Option<&Value> {
+        match self {
+            ResponseFormat::JsonSchema { json_schema, .. } => {
+                Some(json_schema.get("schema").unwrap_or(json_schema))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Sampling parameters for generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SamplingParams {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_sampling_value")]
     pub temperature: Option<f32>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_sampling_value")]
     pub top_p: Option<f32>,
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_k: Option<u32>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_sampling_value")]
     pub frequency_penalty: Option<f32>,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_sampling_value")]
     pub presence_penalty: Option<f32>,
+    
+    /// Number of candidate completions to generate (`n`)
+    #[serde(default, alias = "n", skip_serializing_if = "Option::is_none")]
+    pub candidate_count: Option<u32>,
+    
+    /// Sequences that end generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    
+    /// Return the log probability of each generated token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    
+    /// Number of most likely alternatives to return for each token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+    
+    /// Bias added to the logits of tokens, keyed by token ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<String, f32>>,
+}
+
+/// JSON number for a sampling value
+///
+/// The value is written in its shortest decimal form, so a temperature of
+/// `0.6` stays `0.6` instead of widening to `0.6000000238418579`.
+pub(crate) fn sampling_number(value: f32) -> Value {
+    value.to_string()
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
+
+fn serialize_sampling_value<S>(value: &Option<f32>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    value.map(sampling_number).serialize(serializer)
 }
 
 /// Advanced parameters for latest model features
@@ -400,6 +680,97 @@ pub struct MediaConfig {
     pub output_audio: Option<Value>,
 }
 
+/// Retrieval-augmented generation configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RagConfig {
+    /// Documents to ground the response in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<RagDocument>,
+    
+    /// Retrieval connectors, e.g. `web_search`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connectors: Vec<String>,
+    
+    /// Queries for the provider's retrieval
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_queries: Vec<String>,
+    
+    /// Ask for citations of the documents used
+    #[serde(default)]
+    pub citations_required: bool,
+    
+    /// Maximum number of results to retrieve
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<u32>,
+}
+
+/// Conversation continuation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationConfig {
+    /// Persistent conversation identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    
+    /// Message or response the new turn follows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_message_id: Option<String>,
+    
+    /// Send the earlier turns of `messages`; without it only the turns after
+    /// the last assistant message are sent
+    #[serde(default = "default_preserve_history")]
+    pub preserve_history: bool,
+    
+    /// Message to branch the conversation from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_from: Option<String>,
+}
+
+fn default_preserve_history() -> bool {
+    true
+}
+
+/// Translation preferences
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Preferences {
+    /// How to fit a prompt that exceeds the token budget; defaults to the
+    /// model's `default_mode`, then `AUTO`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_truncation: Option<TruncationMode>,
+
+    /// Whether the model may call several tools in one turn; defaults to
+    /// the model's `parallel_tool_calls_default`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+}
+
+/// Prompt truncation mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TruncationMode {
+    /// Send the prompt whole
+    Off,
+    /// Drop the oldest turns, keeping the first user turn
+    Auto,
+    /// Drop the oldest turns in order
+    AutoPreserveOrder,
+    /// Provider mode cutting the start of the prompt, like `AutoPreserveOrder`
+    Start,
+    /// Provider mode cutting the end of the prompt: the newest turns are
+    /// dropped first and the oldest turn is kept
+    End,
+}
+
+/// A document the response is grounded in
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RagDocument {
+    pub id: String,
+    
+    pub content: String,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
 /// API endpoints configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Endpoints {
@@ -429,6 +800,156 @@ pub struct InputModes {
     pub images: bool,
 }
 
+/// Native content block format of a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentBlockFormat {
+    /// OpenAI-style `text`/`image_url`/`input_audio`/`file` parts
+    #[default]
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Anthropic-style `text`/`image`/`document` blocks
+    Anthropic,
+}
+
+/// How plain-text message content is rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentMode {
+    /// Plain text as a string, content parts as a block array
+    #[default]
+    Auto,
+    /// Always a block array
+    Blocks,
+}
+
+/// Declarative message rendering template
+///
+/// Describes how uniform messages become the provider's request shape so a
+/// new provider can be added from its spec alone. Templates are JSON values
+/// in which `"{{name}}"` placeholders are substituted; an object key whose
+/// value is a placeholder without a value is omitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageRendering {
+    /// Block format for default block templates (OpenAI if unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ContentBlockFormat>,
+
+    /// Request field receiving the conversation
+    pub messages_field: String,
+
+    /// Field holding each message's role
+    pub role_field: String,
+
+    /// Field holding each message's content
+    pub content_field: String,
+
+    /// Role renames, e.g. `{"assistant": "model"}`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub role_map: HashMap<String, String>,
+
+    /// Request field receiving the system prompt when it is a top-level field
+    pub system_field: String,
+
+    /// Template for the top-level system value (`{{text}}`); a plain string if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_template: Option<Value>,
+
+    /// Merge consecutive turns with the same role into one message
+    pub merge_consecutive_roles: bool,
+
+    /// How plain-text content is rendered
+    pub content_mode: ContentMode,
+
+    /// Content block templates; format defaults are used if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<ContentBlockTemplates>,
+
+    /// Request field receiving the prompt for single-text models
+    pub prompt_field: String,
+
+    /// Template for one turn of a single-text prompt (`{{role}}`, `{{text}}`)
+    pub turn_template: String,
+
+    /// Separator between turns of a single-text prompt
+    pub turn_separator: String,
+}
+
+impl Default for MessageRendering {
+    fn default() -> Self {
+        Self {
+            format: None,
+            messages_field: "messages".to_string(),
+            role_field: "role".to_string(),
+            content_field: "content".to_string(),
+            role_map: HashMap::new(),
+            system_field: "system".to_string(),
+            system_template: None,
+            merge_consecutive_roles: false,
+            content_mode: ContentMode::Auto,
+            blocks: None,
+            prompt_field: "prompt".to_string(),
+            turn_template: "{{role}}: {{text}}".to_string(),
+            turn_separator: "\n\n".to_string(),
+        }
+    }
+}
+
+/// Content block templates by part type
+///
+/// A part type without a template is not supported by the provider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentBlockTemplates {
+    /// Text block (`{{text}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<Value>,
+
+    /// Image by URL (`{{url}}`, `{{detail}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<Value>,
+
+    /// Inline image (`{{data}}`, `{{mime_type}}`, `{{data_url}}`, `{{detail}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_base64: Option<Value>,
+
+    /// Audio by URL (`{{url}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_url: Option<Value>,
+
+    /// Inline audio (`{{data}}`, `{{mime_type}}`, `{{format}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_base64: Option<Value>,
+
+    /// Uploaded file reference (`{{file_id}}`, `{{filename}}`, `{{mime_type}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<Value>,
+
+    /// Reasoning echoed back on an assistant turn (`{{text}}`, `{{signature}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<Value>,
+
+    /// Redacted or encrypted reasoning echoed back (`{{data}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redacted_thinking: Option<Value>,
+
+    /// Assistant tool call (`{{id}}`, `{{name}}`, `{{arguments}}` as an
+    /// object, `{{arguments_json}}` as a JSON-encoded string)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call: Option<Value>,
+
+    /// Message field receiving the rendered tool calls; they are appended to
+    /// the content blocks if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls_field: Option<String>,
+
+    /// Whole tool result message (`{{tool_call_id}}`, `{{content}}`); its
+    /// `role` and `content` keys are renamed like any other message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_result: Option<Value>,
+}
+
 /// Tool support configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolingConfig {
@@ -438,6 +959,52 @@ pub struct ToolingConfig {
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_switch: Option<Value>,
+
+    /// How tools and tool choice are rendered; OpenAI-style defaults if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendering: Option<ToolRendering>,
+}
+
+/// Declarative tool rendering templates
+///
+/// Templates follow the placeholder rules of [`MessageRendering`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolRendering {
+    /// Block format for default templates (OpenAI if unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ContentBlockFormat>,
+
+    /// Tool definition (`{{name}}`, `{{description}}`, `{{schema}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<Value>,
+
+    /// Tool choice templates; format defaults are used if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoiceTemplates>,
+
+    /// JSON Schema keywords the provider rejects in tool parameters
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unsupported_schema_keywords: Vec<String>,
+}
+
+/// Tool choice templates by mode
+///
+/// A mode without a template is not supported by the provider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolChoiceTemplates {
+    /// The model decides whether to call a tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto: Option<Value>,
+
+    /// The model must call a tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<Value>,
+
+    /// The model must call the named tool (`{{name}}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specific: Option<Value>,
 }
 
 /// JSON output configuration
@@ -445,6 +1012,15 @@ pub struct ToolingConfig {
 pub struct JsonOutputConfig {
     pub native_param: bool,
     pub strategy: String,
+
+    /// Instruction added to the system prompt when JSON mode is emulated;
+    /// `{{schema}}` is replaced by the requested JSON Schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+
+    /// Prefill the assistant turn with `{` when JSON mode is emulated
+    #[serde(default)]
+    pub prefill: bool,
 }
 
 /// Provider constraints
@@ -455,6 +1031,8 @@ pub struct Constraints {
     pub mutually_exclusive: Vec<Vec<String>>,
     pub resolution_preferences: Vec<String>,
     pub limits: ConstraintLimits,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<ConstraintExtensions>,
 }
 
 /// Constraint limits
@@ -462,6 +1040,29 @@ pub struct Constraints {
 pub struct ConstraintLimits {
     pub max_tool_schema_bytes: u32,
     pub max_system_prompt_bytes: u32,
+    /// Tokens the model reads and writes in one request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+}
+
+/// Additional constraint configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConstraintExtensions {
+    /// Prompt truncation defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_truncation: Option<PromptTruncation>,
+    
+    /// Extensions the translator does not interpret
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
+
+/// Prompt truncation defaults of a model
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PromptTruncation {
+    /// Mode used when the prompt sets no preference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_mode: Option<TruncationMode>,
 }
 
 /// Field mappings configuration
@@ -471,6 +1072,59 @@ pub struct Mappings {
     pub flags: HashMap<String, Value>,
 }
 
+/// Model-specific extensions
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ModelExtensions {
+    /// Request parameters for retrieval-augmented generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rag_config: Option<RagConfiguration>,
+    
+    /// Request parameters for server-side conversation state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_management: Option<ConversationManagement>,
+    
+    /// Extensions the translator does not interpret
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
+
+/// Request parameters a model accepts for server-side conversation state
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConversationManagement {
+    /// Parameter receiving the conversation ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id_param: Option<String>,
+    
+    /// Parameter receiving the ID of the message or response to continue
+    /// from, e.g. `previous_response_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_message_param: Option<String>,
+    
+    /// Whether the parent message can be an earlier message than the last
+    #[serde(default)]
+    pub supports_branching: bool,
+}
+
+/// Request parameters a model accepts for retrieval-augmented generation
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RagConfiguration {
+    /// Parameter receiving the connectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connector_param: Option<String>,
+    
+    /// Parameter receiving the documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_param: Option<String>,
+    
+    /// Parameter receiving the search queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_queries_param: Option<String>,
+    
+    /// Provider identifier of the `web_search` connector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search_connector: Option<String>,
+}
+
 /// Response normalization configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseNormalization {
@@ -484,6 +1138,101 @@ pub struct SyncNormalization {
     pub content_path: String,
     pub finish_reason_path: String,
     pub finish_reason_map: HashMap<String, String>,
+    
+    /// Path to the provider usage object (defaults to `$.usage`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_path: Option<String>,
+    
+    /// Token count paths within the usage object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsagePaths>,
+    
+    /// Path selecting the tool calls, either an array or each call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls_path: Option<String>,
+    
+    /// Path to the tool name within a call (defaults to `$.name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name_path: Option<String>,
+    
+    /// Path to the tool arguments within a call (defaults to `$.arguments`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_args_path: Option<String>,
+    
+    /// Path to the tool call identifier within a call (defaults to `$.id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_id_path: Option<String>,
+    
+    /// How the provider encodes tool arguments
+    #[serde(default)]
+    pub args_encoding: ArgsEncoding,
+    
+    /// Path selecting the reasoning blocks, or the reasoning text itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_path: Option<String>,
+    
+    /// Path to the text within a reasoning block (defaults to `$.text`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_text_path: Option<String>,
+    
+    /// Path to the signature within a reasoning block (defaults to `$.signature`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_signature_path: Option<String>,
+    
+    /// Path to redacted or encrypted content within a reasoning block
+    /// (defaults to `$.data`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_redacted_path: Option<String>,
+    
+    /// Path to the array of choices; content, finish reason and tool call
+    /// paths under its first element are read from every choice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices_path: Option<String>,
+    
+    /// Path selecting the per-token log probabilities, either an array or
+    /// each entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs_path: Option<String>,
+    
+    /// Path to the token within an entry (defaults to `$.token`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprob_token_path: Option<String>,
+    
+    /// Path to the log probability within an entry (defaults to `$.logprob`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprob_value_path: Option<String>,
+    
+    /// Path to the alternatives within an entry (defaults to `$.top_logprobs`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_logprobs_path: Option<String>,
+    
+    /// Path selecting the citations, either an array or each citation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citations_path: Option<String>,
+    
+    /// Path to the cited documents within a citation, as IDs or as indexes
+    /// into the request's documents (defaults to `$.document_id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation_document_path: Option<String>,
+    
+    /// Path to the cited text within a citation (defaults to `$.text`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation_text_path: Option<String>,
+    
+    /// Path to the response identifier (defaults to `$.id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_id_path: Option<String>,
+}
+
+/// Encoding of tool call arguments in a provider response
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgsEncoding {
+    /// A JSON document serialized into a string (OpenAI)
+    #[default]
+    JsonString,
+    /// A JSON object (Anthropic)
+    Object,
 }
 
 /// Stream response normalization
@@ -491,6 +1240,50 @@ pub struct SyncNormalization {
 pub struct StreamNormalization {
     pub protocol: String,
     pub event_selector: EventSelector,
+    
+    /// Token count paths within the usage objects selected by routes'
+    /// `usage_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsagePaths>,
+}
+
+/// JSONPaths to token counts within a provider usage object
+///
+/// Paths left out are not reported; a missing total is the sum of input
+/// and output tokens.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsagePaths {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens_path: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens_path: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_tokens_path: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens_path: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens_path: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens_path: Option<String>,
+}
+
+impl UsagePaths {
+    /// Paths for the conventional usage keys, used when a spec declares none
+    pub fn conventional() -> Self {
+        Self {
+            input_tokens_path: Some("$.input_tokens".to_string()),
+            output_tokens_path: Some("$.output_tokens".to_string()),
+            total_tokens_path: Some("$.total_tokens".to_string()),
+            reasoning_tokens_path: Some("$.output_tokens_details.reasoning_tokens".to_string()),
+            cache_read_tokens_path: Some("$.cache_read_input_tokens".to_string()),
+            cache_write_tokens_path: Some("$.cache_creation_input_tokens".to_string()),
+        }
+    }
 }
 
 /// Event selection rules
@@ -501,11 +1294,18 @@ pub struct EventSelector {
 }
 
 /// Single event route
+///
+/// A route matches when the value selected by `type_path` equals `when`, or,
+/// when that value is an object, when it has a non-null `when` key.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventRoute {
     pub when: String,
     pub emit: String,
     
+    /// Overrides the selector's `type_path` for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_path: Option<String>,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text_path: Option<String>,
     
@@ -514,6 +1314,40 @@ pub struct EventRoute {
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub args_path: Option<String>,
+    
+    /// Path to the tool call identifier, or to the response identifier on
+    /// `start` routes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_path: Option<String>,
+    
+    /// Path to the tool call index when several calls are streamed, or to
+    /// the content block index of reasoning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_path: Option<String>,
+    
+    /// Path to the index of the choice the event belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choice_path: Option<String>,
+    
+    /// Path to a reasoning block's signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_path: Option<String>,
+    
+    /// Path to a reasoning block's redacted or encrypted content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redacted_path: Option<String>,
+    
+    /// Path to the provider finish reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason_path: Option<String>,
+    
+    /// Path to the provider usage object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_path: Option<String>,
+    
+    /// Route-specific extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Value>,
 }
 
 /// Result of a translation operation
@@ -581,6 +1415,10 @@ pub struct TranslationMetadata {
     pub timestamp: String,
     pub duration_ms: Option<u64>,
     pub strict_mode: StrictMode,
+    /// Whether JSON mode is emulated through the system prompt, so the
+    /// response's JSON document has to be extracted from its content
+    #[serde(default)]
+    pub json_mode: bool,
 }
 
 /// Normalized response format
@@ -594,6 +1432,145 @@ pub struct UniformResponse {
     pub tool_calls: Option<Vec<ToolCall>>,
     
     pub raw_metadata: Value,
+    
+    /// Token usage extracted with the model's usage paths
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    
+    /// Content checked against the requested JSON Schema, when validation
+    /// was asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<StructuredOutput>,
+    
+    /// Reasoning extracted with the model's reasoning paths
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningOutput>,
+    
+    /// Every candidate, the first included, when more than one was returned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<Candidate>,
+    
+    /// Per-token log probabilities, when the provider returned them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    
+    /// Documents the response cites
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+    
+    /// Provider identifier of the response; pass it as the conversation's
+    /// `parent_message_id` to continue from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_id: Option<String>,
+    
+    /// What normalizing the provider response could not represent, when
+    /// reported by the caller that ran the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lossiness: Option<LossinessReport>,
+}
+
+/// A reference from the response to one of the request's documents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Citation {
+    pub document_id: String,
+    
+    /// Text of the response or document the citation covers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// One of several candidate completions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Candidate {
+    /// Position of the candidate in the response
+    pub index: usize,
+    
+    pub content: String,
+    
+    pub finish_reason: FinishReason,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
+/// Log probability of a generated token
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenLogprob {
+    pub token: String,
+    
+    pub logprob: f64,
+    
+    /// Most likely alternatives at this position
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// An alternative token and its log probability
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopLogprob {
+    pub token: String,
+    
+    pub logprob: f64,
+}
+
+/// Reasoning a model produced before its answer
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReasoningOutput {
+    /// Reasoning text or summary of all blocks
+    pub text: String,
+    
+    /// Blocks to send back with the assistant turn on follow-up requests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ReasoningBlock>,
+    
+    /// Reasoning tokens reported in the usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+}
+
+/// A single provider reasoning block
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReasoningBlock {
+    /// Reasoning text; absent for redacted blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    
+    /// Signature the provider checks when the block is sent back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    
+    /// Redacted or encrypted reasoning content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted: Option<String>,
+}
+
+/// Response content validated against a requested JSON Schema
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StructuredOutput {
+    /// Parsed content, if it is valid JSON
+    pub parsed: Option<Value>,
+    
+    /// Schema violations; empty when the content matches the schema
+    #[serde(default)]
+    pub errors: Vec<StructuredOutputError>,
+}
+
+impl StructuredOutput {
+    /// Whether the content parsed and matched the schema
+    pub fn is_valid(&self) -> bool {
+        self.parsed.is_some() && self.errors.is_empty()
+    }
+}
+
+/// A single structured output violation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StructuredOutputError {
+    /// Location in the parsed content, e.g. `$.items[0]`
+    pub path: String,
+    pub message: String,
 }
 
 /// Finish reason for a response
@@ -607,8 +1584,8 @@ pub enum FinishReason {
     Other,
 }
 
-/// Tool call in a response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Tool call requested by the model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ToolCall {
     pub name: String,
     pub arguments: Value,
@@ -617,10 +1594,88 @@ pub struct ToolCall {
     pub id: Option<String>,
 }
 
+/// Uniform event produced while streaming a response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+///
+/// `choice` is the candidate an event belongs to when several were requested;
+/// it is 0 otherwise.
+pub enum StreamEvent {
+    /// The provider's identifier for the response
+    ResponseStart { id: String },
+    /// Incremental text content
+    TextDelta {
+        #[serde(default)]
+        choice: usize,
+        text: String,
+    },
+    /// Incremental reasoning text of the reasoning block at `index`
+    ReasoningDelta { index: usize, text: String },
+    /// Signature of the reasoning block at `index`
+    ReasoningSignature { index: usize, signature: String },
+    /// Redacted or encrypted content of the reasoning block at `index`
+    ReasoningRedacted { index: usize, data: String },
+    /// A tool call has started
+    ToolCallStart {
+        #[serde(default)]
+        choice: usize,
+        index: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        name: String,
+    },
+    /// A fragment of a tool call's JSON arguments
+    ToolCallArgsDelta {
+        #[serde(default)]
+        choice: usize,
+        index: usize,
+        delta: String,
+    },
+    /// A tool call is complete
+    ToolCallEnd {
+        #[serde(default)]
+        choice: usize,
+        index: usize,
+    },
+    /// The response, or one of its candidates, finished
+    Finish {
+        #[serde(default)]
+        choice: usize,
+        finish_reason: FinishReason,
+    },
+    /// Token usage reported by the provider
+    Usage { usage: Value },
+}
+
 /// Stream handle for streaming operations
+///
+/// Yields normalized [`StreamEvent`]s as the provider sends them.
 pub struct StreamHandle {
-    // Implementation details will be added when implementing streaming
-    pub(crate) _private: (),
+    pub(crate) inner: Pin<Box<dyn Stream<Item = crate::Result<StreamEvent>> + Send>>,
+}
+
+impl StreamHandle {
+    /// Wrap a stream of normalized events
+    pub(crate) fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = crate::Result<StreamEvent>> + Send + 'static,
+    {
+        Self { inner: Box::pin(stream) }
+    }
+}
+
+impl Stream for StreamHandle {
+    type Item = crate::Result<StreamEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for StreamHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamHandle").finish_non_exhaustive()
+    }
 }
 
 #[cfg(test)]
@@ -637,6 +1692,37 @@ mod tests {
         assert_eq!(deserialized, MessageRole::System);
     }
 
+    #[test]
+    fn test_message_content_deserialization() {
+        // String content keeps deserializing as plain text
+        let msg: Message = serde_json::from_str(r#"{"role": "user", "content": "Hi"}"#).unwrap();
+        assert_eq!(msg.content, "Hi");
+        assert_eq!(serde_json::to_value(&msg).unwrap()["content"], "Hi");
+
+        let msg: Message = serde_json::from_value(serde_json::json!({
+            "role": "user",
+            "content": [
+                {"type": "text", "text": "Compare"},
+                {"type": "image", "url": "https://example.com/a.png", "detail": "low"},
+                {"type": "image", "data": "aGVsbG8=", "mime_type": "image/png"},
+                {"type": "audio", "data": "UklGRg==", "mime_type": "audio/wav"},
+                {"type": "file", "file_id": "file-123"}
+            ]
+        })).unwrap();
+
+        let MessageContent::Parts(parts) = &msg.content else {
+            panic!("Expected content parts");
+        };
+        assert_eq!(parts.len(), 5);
+        assert_eq!(parts[1], ContentPart::Image {
+            source: MediaSource::Url { url: "https://example.com/a.png".to_string() },
+            detail: Some("low".to_string()),
+        });
+        assert_eq!(parts[2], ContentPart::image_base64("aGVsbG8=", "image/png"));
+        assert_eq!(msg.content.text(), "Compare");
+        assert!(!msg.content.is_blank());
+    }
+
     #[test]
     fn test_prompt_spec_minimal() {
         let spec = PromptSpec {
@@ -644,9 +1730,12 @@ mod tests {
             messages: vec![
                 Message {
                     role: MessageRole::User,
-                    content: "Hello".to_string(),
+                    content: "Hello".into(),
                     name: None,
                     metadata: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    reasoning: None,
                 }
             ],
             tools: None,
@@ -657,6 +1746,9 @@ mod tests {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         let json = serde_json::to_string(&spec).unwrap();
@@ -8,6 +8,13 @@ use specado_core::{translate, StrictMode, LossinessCode, Severity};
 use specado_core::types::*;
 use serde_json::Value;
 
+/// Turns prompt truncation dropped, reported as lossiness
+fn dropped_turns(result: &TranslationResult) -> usize {
+    result.lossiness.items.iter()
+        .filter(|item| item.code == LossinessCode::Drop && item.path.starts_with("messages[") && item.path.ends_with(']'))
+        .count()
+}
+
 // Strategy functions for property testing
 
 /// Strategy for generating message roles
@@ -28,9 +35,12 @@ fn message_strategy() -> impl Strategy<Value = Message> {
     ).prop_map(|(role, content, name)| {
         Message {
             role,
-            content,
+            content: content.into(),
             name,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }
     })
 }
@@ -50,6 +60,11 @@ fn sampling_params_strategy() -> impl Strategy<Value = SamplingParams> {
             top_k,
             frequency_penalty,
             presence_penalty,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         }
     })
 }
@@ -118,6 +133,9 @@ fn prompt_spec_strategy() -> impl Strategy<Value = PromptSpec> {
             media: None,
             advanced: None,
             strict_mode,
+            rag: None,
+            conversation: None,
+            preferences: None,
         }
     })
 }
@@ -161,12 +179,17 @@ fn minimal_provider_spec() -> ProviderSpec {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: false,
                 strategy: "system_prompt".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: Value::Object(Default::default()),
             constraints: Constraints {
                 system_prompt_location: "first_message".to_string(),
@@ -176,7 +199,9 @@ fn minimal_provider_spec() -> ProviderSpec {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 65536,
                     max_system_prompt_bytes: 100000,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: Default::default(),
@@ -187,6 +212,26 @@ fn minimal_provider_spec() -> ProviderSpec {
                     content_path: "$.content".to_string(),
                     finish_reason_path: "$.finish_reason".to_string(),
                     finish_reason_map: Default::default(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -194,8 +239,10 @@ fn minimal_provider_spec() -> ProviderSpec {
                         type_path: "$.type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         }],
     }
 }
@@ -248,6 +295,11 @@ proptest! {
             top_k: None,
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let provider_spec = minimal_provider_spec();
@@ -287,6 +339,11 @@ proptest! {
             top_k: None,
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let provider_spec = minimal_provider_spec();
@@ -341,6 +398,11 @@ proptest! {
             top_k: top_k_val,
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let provider_spec = minimal_provider_spec();
@@ -370,6 +432,11 @@ proptest! {
             top_k: None,
             frequency_penalty: Some(raw_freq),
             presence_penalty: Some(raw_pres),
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let provider_spec = minimal_provider_spec();
@@ -441,6 +508,9 @@ proptest! {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         let provider_spec = minimal_provider_spec();
@@ -456,7 +526,7 @@ proptest! {
                             // Find this content in original messages
                             if original_idx < messages.len() {
                                 let orig_content = &messages[original_idx].content;
-                                if content.as_str() == Some(orig_content) {
+                                if content.as_str() == Some(orig_content.text().as_ref()) {
                                     original_idx += 1;
                                 }
                             }
@@ -535,6 +605,9 @@ proptest! {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         let provider_spec = minimal_provider_spec();
@@ -653,8 +726,9 @@ proptest! {
                 if let Some(messages) = result.provider_request_json.get("messages") {
                     if let Some(arr) = messages.as_array() {
                         // Output messages should be at least as many as input
-                        // (system prompts might be added)
-                        assert!(arr.len() >= prompt_spec.messages.len());
+                        // (system prompts might be added), less the turns
+                        // dropped to fit the prompt budget
+                        assert!(arr.len() + dropped_turns(&result) >= prompt_spec.messages.len());
                     }
                 }
             }
@@ -738,6 +812,11 @@ proptest! {
             top_k: None,
             frequency_penalty: None,
             presence_penalty: None,
+            candidate_count: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            logit_bias: None,
         });
         
         let provider_spec = minimal_provider_spec();
@@ -796,6 +875,9 @@ proptest! {
             media: None,
             advanced: None,
             strict_mode: StrictMode::Warn,
+            rag: None,
+            conversation: None,
+            preferences: None,
         };
         
         let provider_spec = minimal_provider_spec();
@@ -804,14 +886,22 @@ proptest! {
         if let Ok(result) = translate(&prompt_spec, &provider_spec, model_id, StrictMode::Warn) {
             if let Some(output_messages) = result.provider_request_json.get("messages") {
                 if let Some(arr) = output_messages.as_array() {
+                    // System messages are relocated to the front for "first_message" providers
+                    let mut expected = messages.clone();
+                    if provider_spec.models[0].constraints.system_prompt_location == "first_message"
+                        && messages.first().is_some_and(|m| m.role != MessageRole::System) {
+                        expected.sort_by_key(|m| m.role != MessageRole::System);
+                    }
+                    
                     // Check that roles are preserved
-                    for (i, msg) in messages.iter().enumerate() {
+                    for (i, msg) in expected.iter().enumerate() {
                         if i < arr.len() {
                             if let Some(role) = arr[i].get("role") {
                                 let expected_role = match msg.role {
                                     MessageRole::System => "system",
                                     MessageRole::User => "user",
                                     MessageRole::Assistant => "assistant",
+                                    MessageRole::Tool => "tool",
                                 };
                                 assert_eq!(role.as_str(), Some(expected_role));
                             }
@@ -836,7 +926,7 @@ proptest! {
             // 1. Message count should be preserved or increased (system prompts added)
             if let Some(messages) = result.provider_request_json.get("messages") {
                 if let Some(arr) = messages.as_array() {
-                    assert!(arr.len() >= prompt_spec.messages.len(),
+                    assert!(arr.len() + dropped_turns(&result) >= prompt_spec.messages.len(),
                            "Message count should not decrease");
                 }
             }
@@ -937,7 +1027,7 @@ proptest! {
         
         // Add a large system prompt
         if let Some(msg) = prompt_spec.messages.iter_mut().find(|m| m.role == MessageRole::System) {
-            msg.content = "x".repeat(600); // Exceeds limit
+            msg.content = "x".repeat(600).into(); // Exceeds limit
         }
         
         let model_id = "test-model";
@@ -39,12 +39,17 @@ fn create_openai_model_spec() -> ModelSpec {
             parallel_tool_calls_default: true,
             can_disable_parallel_tool_calls: true,
             disable_switch: Some(json!({"path": "$.parallel_tool_calls", "value": false})),
+            rendering: None,
         },
         json_output: JsonOutputConfig {
             native_param: true,
             strategy: "json_schema".to_string(),
+            instruction: None,
+            prefill: false,
         },
         capabilities: None,
+        transformations: Vec::new(),
+        message_rendering: None,
         parameters: json!({}),
         constraints: Constraints {
             system_prompt_location: "message_role".to_string(),
@@ -54,7 +59,9 @@ fn create_openai_model_spec() -> ModelSpec {
             limits: ConstraintLimits {
                 max_tool_schema_bytes: 200000,
                 max_system_prompt_bytes: 32000,
+                context_window: None,
             },
+            extensions: None,
         },
         mappings: Mappings {
             paths: HashMap::new(),
@@ -70,6 +77,26 @@ fn create_openai_model_spec() -> ModelSpec {
                     ("tool_calls".to_string(), "tool_call".to_string()),
                     ("function_call".to_string(), "tool_call".to_string()),
                 ]),
+                usage_path: None,
+                usage: None,
+                tool_calls_path: Some("$.choices[0].message.tool_calls".to_string()),
+                tool_name_path: Some("$.function.name".to_string()),
+                tool_args_path: Some("$.function.arguments".to_string()),
+                tool_id_path: Some("$.id".to_string()),
+                args_encoding: ArgsEncoding::JsonString,
+                reasoning_path: None,
+                reasoning_text_path: None,
+                reasoning_signature_path: None,
+                reasoning_redacted_path: None,
+                choices_path: None,
+                logprobs_path: None,
+                logprob_token_path: None,
+                logprob_value_path: None,
+                top_logprobs_path: None,
+                citations_path: None,
+                citation_document_path: None,
+                citation_text_path: None,
+                response_id_path: None,
             },
             stream: StreamNormalization {
                 protocol: "sse".to_string(),
@@ -77,8 +104,10 @@ fn create_openai_model_spec() -> ModelSpec {
                     type_path: "$.object".to_string(),
                     routes: vec![],
                 },
+                usage: None,
             },
         },
+        extensions: None,
     }
 }
 
@@ -114,12 +143,17 @@ fn create_anthropic_model_spec() -> ModelSpec {
             parallel_tool_calls_default: true,
             can_disable_parallel_tool_calls: true,
             disable_switch: Some(json!({"path": "$.tool_choice.disable_parallel_tool_use", "value": true})),
+            rendering: None,
         },
         json_output: JsonOutputConfig {
             native_param: false,
             strategy: "tools".to_string(),
+            instruction: None,
+            prefill: false,
         },
         capabilities: None,
+        transformations: Vec::new(),
+        message_rendering: None,
         parameters: json!({}),
         constraints: Constraints {
             system_prompt_location: "top_level".to_string(),
@@ -131,7 +165,9 @@ fn create_anthropic_model_spec() -> ModelSpec {
             limits: ConstraintLimits {
                 max_tool_schema_bytes: 180000,
                 max_system_prompt_bytes: 30000,
+                context_window: None,
             },
+            extensions: None,
         },
         mappings: Mappings {
             paths: HashMap::new(),
@@ -139,7 +175,7 @@ fn create_anthropic_model_spec() -> ModelSpec {
         },
         response_normalization: ResponseNormalization {
             sync: SyncNormalization {
-                content_path: "$.content[-1].text".to_string(),
+                content_path: "$.content[?(@.type == 'text')].text".to_string(),
                 finish_reason_path: "$.stop_reason".to_string(),
                 finish_reason_map: HashMap::from([
                     ("end_turn".to_string(), "stop".to_string()),
@@ -147,6 +183,26 @@ fn create_anthropic_model_spec() -> ModelSpec {
                     ("tool_use".to_string(), "tool_call".to_string()),
                     ("stop_sequence".to_string(), "stop".to_string()),
                 ]),
+                usage_path: None,
+                usage: None,
+                tool_calls_path: Some("$.content[?(@.type == 'tool_use')]".to_string()),
+                tool_name_path: Some("$.name".to_string()),
+                tool_args_path: Some("$.input".to_string()),
+                tool_id_path: Some("$.id".to_string()),
+                args_encoding: ArgsEncoding::Object,
+                reasoning_path: None,
+                reasoning_text_path: None,
+                reasoning_signature_path: None,
+                reasoning_redacted_path: None,
+                choices_path: None,
+                logprobs_path: None,
+                logprob_token_path: None,
+                logprob_value_path: None,
+                top_logprobs_path: None,
+                citations_path: None,
+                citation_document_path: None,
+                citation_text_path: None,
+                response_id_path: None,
             },
             stream: StreamNormalization {
                 protocol: "sse".to_string(),
@@ -154,8 +210,10 @@ fn create_anthropic_model_spec() -> ModelSpec {
                     type_path: "$.type".to_string(),
                     routes: vec![],
                 },
+                usage: None,
             },
         },
+        extensions: None,
     }
 }
 
@@ -183,7 +241,7 @@ fn test_openai_simple_response() {
         }
     });
     
-    let result = normalize_response(&response, &model_spec, "gpt-5").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "gpt-5").unwrap();
     
     assert_eq!(result.model, "gpt-5");
     assert_eq!(result.content, "The capital of France is Paris.");
@@ -212,7 +270,7 @@ fn test_anthropic_simple_response() {
         }
     });
     
-    let result = normalize_response(&response, &model_spec, "claude-opus-4-1").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "claude-opus-4-1").unwrap();
     
     assert_eq!(result.model, "claude-opus-4-1");
     assert_eq!(result.content, "The capital of France is Paris.");
@@ -252,7 +310,7 @@ fn test_openai_with_tools() {
         }
     });
     
-    let result = normalize_response(&response, &model_spec, "gpt-5").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "gpt-5").unwrap();
     
     assert_eq!(result.model, "gpt-5");
     assert_eq!(result.content, ""); // No content when using tools
@@ -297,7 +355,7 @@ fn test_anthropic_with_tools() {
         }
     });
     
-    let result = normalize_response(&response, &model_spec, "claude-opus-4-1").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "claude-opus-4-1").unwrap();
     
     assert_eq!(result.model, "claude-opus-4-1");
     assert_eq!(result.content, "I'll check the weather for you.");
@@ -334,7 +392,7 @@ fn test_length_limit_finish_reason() {
         }
     });
     
-    let result = normalize_response(&response, &model_spec, "gpt-5").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "gpt-5").unwrap();
     
     assert_eq!(result.finish_reason, FinishReason::Length);
     assert_eq!(result.content, "This is a partial response that was cut off due to");
@@ -355,7 +413,7 @@ fn test_missing_fields_graceful_handling() {
         }]
     });
     
-    let result = normalize_response(&response, &model_spec, "gpt-5").unwrap();
+    let (result, _) = normalize_response(&response, &model_spec, "gpt-5").unwrap();
     
     assert_eq!(result.model, "gpt-5");
     assert_eq!(result.content, ""); // Empty content when missing
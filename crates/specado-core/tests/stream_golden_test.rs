@@ -0,0 +1,110 @@
+//! Golden tests comparing streaming and sync response normalization
+//!
+//! Each recorded stream in `tests/fixtures/streams` has a matching sync
+//! response for the same output; accumulating the stream must produce the
+//! same UniformResponse as normalizing the sync body.
+
+use futures_util::stream;
+use serde_json::Value;
+use specado_core::http::{normalize_response, normalize_stream};
+use specado_core::types::*;
+use specado_core::{ResponseExt, StreamAccumulator};
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/streams").join(name)
+}
+
+/// Load a model from the shipped provider specs
+fn load_model(provider_file: &str, model_id: &str) -> ModelSpec {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../providers").join(provider_file);
+    let spec: ProviderSpec = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    spec.models.into_iter().find(|m| m.id == model_id).unwrap()
+}
+
+/// Accumulate a recorded stream, delivered in small chunks
+async fn accumulate_fixture(model: &ModelSpec, name: &str) -> UniformResponse {
+    let bytes = std::fs::read(fixture_path(name)).unwrap();
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        bytes.chunks(23).map(|c| Ok(c.to_vec())).collect();
+
+    let handle = normalize_stream(stream::iter(chunks), model).unwrap();
+    StreamAccumulator::accumulate_for_model(handle, model).await.unwrap()
+}
+
+fn normalize_fixture(model: &ModelSpec, name: &str) -> UniformResponse {
+    let body: Value = serde_json::from_str(&std::fs::read_to_string(fixture_path(name)).unwrap()).unwrap();
+    normalize_response(&body, model, &model.id).unwrap().0
+}
+
+fn assert_same_response(streamed: &UniformResponse, sync: &UniformResponse) {
+    assert_eq!(streamed.model, sync.model);
+    assert_eq!(streamed.content, sync.content);
+    assert_eq!(streamed.finish_reason, sync.finish_reason);
+
+    let streamed_tools = streamed.tool_calls.clone().unwrap_or_default();
+    let sync_tools = sync.tool_calls.clone().unwrap_or_default();
+    assert_eq!(streamed_tools.len(), sync_tools.len());
+    for (s, y) in streamed_tools.iter().zip(&sync_tools) {
+        assert_eq!(s.name, y.name);
+        assert_eq!(s.id, y.id);
+        assert_eq!(s.arguments, y.arguments);
+    }
+
+    let (s, y) = (streamed.usage(), sync.usage());
+    assert_eq!(s.input_tokens, y.input_tokens);
+    assert_eq!(s.output_tokens, y.output_tokens);
+    assert_eq!(s.total_tokens, y.total_tokens);
+    assert_eq!(streamed.usage, sync.usage);
+
+    assert_eq!(streamed.reasoning, sync.reasoning);
+    assert_eq!(streamed.response_id, sync.response_id);
+    assert_eq!(streamed.candidates, sync.candidates);
+}
+
+#[tokio::test]
+async fn test_openai_text_stream_matches_sync() {
+    let model = load_model("openai/gpt-5.json", "gpt-5");
+    let streamed = accumulate_fixture(&model, "openai_chat.sse").await;
+    let sync = normalize_fixture(&model, "openai_chat.json");
+
+    assert_eq!(streamed.content, "The weather in Paris is sunny.");
+    assert_eq!(streamed.usage().total_tokens, 21);
+    assert_same_response(&streamed, &sync);
+}
+
+#[tokio::test]
+async fn test_openai_tool_stream_matches_sync() {
+    let model = load_model("openai/gpt-5.json", "gpt-5");
+    let streamed = accumulate_fixture(&model, "openai_tools.sse").await;
+    let sync = normalize_fixture(&model, "openai_tools.json");
+
+    assert_eq!(streamed.finish_reason, FinishReason::ToolCall);
+    assert_same_response(&streamed, &sync);
+}
+
+#[tokio::test]
+async fn test_anthropic_stream_matches_sync() {
+    let model = load_model("anthropic/claude-opus-4.1.json", "claude-opus-4-1-20250805");
+    let streamed = accumulate_fixture(&model, "anthropic_messages.sse").await;
+    let sync = normalize_fixture(&model, "anthropic_messages.json");
+
+    assert_eq!(streamed.content, "Let me check the weather.");
+    assert_eq!(streamed.usage().output_tokens, 32);
+    assert_same_response(&streamed, &sync);
+}
+
+#[tokio::test]
+async fn test_anthropic_thinking_stream_matches_sync() {
+    let model = load_model("anthropic/claude-opus-4.1.json", "claude-opus-4-1-20250805");
+    let streamed = accumulate_fixture(&model, "anthropic_thinking.sse").await;
+    let sync = normalize_fixture(&model, "anthropic_thinking.json");
+
+    let reasoning = streamed.reasoning.as_ref().unwrap();
+    assert_eq!(reasoning.text, "The user wants 17 * 3. That is 51.");
+    assert_eq!(reasoning.blocks.len(), 2);
+    assert_eq!(reasoning.blocks[0].signature.as_deref(), Some("EqQBCgIYAhIM1gbcDa9GJwZA2b3h"));
+    assert_eq!(reasoning.blocks[1].redacted.as_deref(), Some("EmwKAhgBEgy3va3pzix/LafPsn4a"));
+    assert_eq!(streamed.response_id.as_deref(), Some("msg_2"));
+    assert_same_response(&streamed, &sync);
+}
@@ -2,10 +2,11 @@
 
 use serde_json::json;
 use specado_core::types::{
-    ConstraintLimits, Constraints, EndpointConfig, Endpoints, EventSelector,
-    InputModes, JsonOutputConfig, Limits, Mappings, Message, MessageRole, ModelSpec, 
-    PromptSpec, ProviderInfo, ProviderSpec, ResponseFormat, ResponseNormalization, 
-    SamplingParams, StreamNormalization, SyncNormalization, Tool, ToolChoice, ToolingConfig,
+    ArgsEncoding, ConstraintLimits, Constraints, ContentBlockFormat, EndpointConfig, Endpoints,
+    EventSelector, InputModes, JsonOutputConfig, Limits, Mappings, Message, MessageRendering,
+    MessageRole, ModelSpec, PromptSpec, ProviderInfo, ProviderSpec, ResponseFormat,
+    ResponseNormalization, SamplingParams, StreamNormalization, SyncNormalization, Tool,
+    ToolChoice, ToolRendering, ToolingConfig,
 };
 use specado_core::StrictMode;
 
@@ -15,9 +16,12 @@ pub fn minimal_prompt() -> PromptSpec {
         model_class: "Chat".to_string(),
         messages: vec![Message {
             role: MessageRole::User,
-            content: "Test message".to_string(),
+            content: "Test message".into(),
             name: None,
             metadata: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }],
         tools: None,
         tool_choice: None,
@@ -27,6 +31,9 @@ pub fn minimal_prompt() -> PromptSpec {
         media: None,
         advanced: None,
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     }
 }
 
@@ -37,15 +44,21 @@ pub fn chat_prompt(system: &str, user: &str) -> PromptSpec {
         messages: vec![
             Message {
                 role: MessageRole::System,
-                content: system.to_string(),
+                content: system.into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::User,
-                content: user.to_string(),
+                content: user.into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
         ],
         tools: None,
@@ -56,6 +69,9 @@ pub fn chat_prompt(system: &str, user: &str) -> PromptSpec {
         media: None,
         advanced: None,
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     }
 }
 
@@ -85,6 +101,11 @@ pub fn prompt_with_sampling() -> PromptSpec {
         top_k: Some(40),
         frequency_penalty: Some(0.5),
         presence_penalty: Some(0.2),
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
     prompt
 }
@@ -152,12 +173,17 @@ pub fn openai_gpt5_model() -> ModelSpec {
             parallel_tool_calls_default: true,
             can_disable_parallel_tool_calls: true,
             disable_switch: Some(json!({"parallel_tool_calls": false})),
+            rendering: None,
         },
         json_output: JsonOutputConfig {
             native_param: true,
             strategy: "response_format".to_string(),
+            instruction: None,
+            prefill: false,
         },
         capabilities: None,
+        transformations: Vec::new(),
+        message_rendering: None,
         parameters: json!({
             "temperature": {
                 "type": "number",
@@ -176,6 +202,11 @@ pub fn openai_gpt5_model() -> ModelSpec {
                 "minimum": 1,
                 "maximum": 100
             },
+            "max_tokens": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 128000
+            },
             "frequency_penalty": {
                 "type": "number",
                 "minimum": -2.0,
@@ -197,7 +228,9 @@ pub fn openai_gpt5_model() -> ModelSpec {
             limits: ConstraintLimits {
                 max_tool_schema_bytes: 16384,
                 max_system_prompt_bytes: 32768,
+                context_window: None,
             },
+            extensions: None,
         },
         mappings: Mappings {
             paths: {
@@ -239,6 +272,26 @@ pub fn openai_gpt5_model() -> ModelSpec {
                 content_path: "$.choices[0].message.content".to_string(),
                 finish_reason_path: "$.choices[0].finish_reason".to_string(),
                 finish_reason_map: std::collections::HashMap::new(),
+                usage_path: None,
+                usage: None,
+                tool_calls_path: None,
+                tool_name_path: None,
+                tool_args_path: None,
+                tool_id_path: None,
+                args_encoding: ArgsEncoding::default(),
+                reasoning_path: None,
+                reasoning_text_path: None,
+                reasoning_signature_path: None,
+                reasoning_redacted_path: None,
+                choices_path: None,
+                logprobs_path: None,
+                logprob_token_path: None,
+                logprob_value_path: None,
+                top_logprobs_path: None,
+                citations_path: None,
+                citation_document_path: None,
+                citation_text_path: None,
+                response_id_path: None,
             },
             stream: StreamNormalization {
                 protocol: "sse".to_string(),
@@ -246,8 +299,10 @@ pub fn openai_gpt5_model() -> ModelSpec {
                     type_path: "$.choices[0].delta".to_string(),
                     routes: vec![],
                 },
+                usage: None,
             },
         },
+        extensions: None,
     }
 }
 
@@ -304,29 +359,59 @@ pub fn anthropic_claude_model() -> ModelSpec {
             parallel_tool_calls_default: true,
             can_disable_parallel_tool_calls: false,
             disable_switch: None,
+            rendering: Some(ToolRendering {
+                format: Some(ContentBlockFormat::Anthropic),
+                ..Default::default()
+            }),
         },
         json_output: JsonOutputConfig {
             native_param: false,
             strategy: "system_prompt".to_string(),
+            instruction: None,
+            prefill: false,
         },
         capabilities: None,
+        transformations: Vec::new(),
+        message_rendering: Some(MessageRendering {
+            format: Some(ContentBlockFormat::Anthropic),
+            ..Default::default()
+        }),
         parameters: json!({
             "temperature": {
                 "type": "number",
                 "minimum": 0.0,
                 "maximum": 1.0,
                 "default": 1.0
+            },
+            "top_p": {
+                "type": "number",
+                "minimum": 0.0,
+                "maximum": 1.0
+            },
+            "top_k": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 500
+            },
+            "max_tokens": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 200000,
+                "default": 4096,
+                "required": true
             }
         }),
         constraints: Constraints {
-            system_prompt_location: "first_message".to_string(),
+            system_prompt_location: "top_level_field".to_string(),
             forbid_unknown_top_level_fields: true,
             mutually_exclusive: vec![],
             resolution_preferences: vec![],
             limits: ConstraintLimits {
                 max_tool_schema_bytes: 16384,
                 max_system_prompt_bytes: 100000,
+                context_window: None,
             },
+            extensions: None,
         },
         mappings: Mappings {
             paths: {
@@ -344,6 +429,26 @@ pub fn anthropic_claude_model() -> ModelSpec {
                 content_path: "$.content[0].text".to_string(),
                 finish_reason_path: "$.stop_reason".to_string(),
                 finish_reason_map: std::collections::HashMap::new(),
+                usage_path: None,
+                usage: None,
+                tool_calls_path: None,
+                tool_name_path: None,
+                tool_args_path: None,
+                tool_id_path: None,
+                args_encoding: ArgsEncoding::default(),
+                reasoning_path: None,
+                reasoning_text_path: None,
+                reasoning_signature_path: None,
+                reasoning_redacted_path: None,
+                choices_path: None,
+                logprobs_path: None,
+                logprob_token_path: None,
+                logprob_value_path: None,
+                top_logprobs_path: None,
+                citations_path: None,
+                citation_document_path: None,
+                citation_text_path: None,
+                response_id_path: None,
             },
             stream: StreamNormalization {
                 protocol: "sse".to_string(),
@@ -351,8 +456,10 @@ pub fn anthropic_claude_model() -> ModelSpec {
                     type_path: "$.type".to_string(),
                     routes: vec![],
                 },
+                usage: None,
             },
         },
+        extensions: None,
     }
 }
 
@@ -395,12 +502,17 @@ pub fn limited_provider() -> ProviderSpec {
                 parallel_tool_calls_default: false,
                 can_disable_parallel_tool_calls: false,
                 disable_switch: None,
+                rendering: None,
             },
             json_output: JsonOutputConfig {
                 native_param: false,
                 strategy: "none".to_string(),
+                instruction: None,
+                prefill: false,
             },
             capabilities: None,
+            transformations: Vec::new(),
+            message_rendering: None,
             parameters: json!({}),
             constraints: Constraints {
                 system_prompt_location: "first_message".to_string(),
@@ -410,7 +522,9 @@ pub fn limited_provider() -> ProviderSpec {
                 limits: ConstraintLimits {
                     max_tool_schema_bytes: 8192,
                     max_system_prompt_bytes: 16384,
+                    context_window: None,
                 },
+                extensions: None,
             },
             mappings: Mappings {
                 paths: std::collections::HashMap::new(),
@@ -421,6 +535,26 @@ pub fn limited_provider() -> ProviderSpec {
                     content_path: "$.content".to_string(),
                     finish_reason_path: "$.finish_reason".to_string(),
                     finish_reason_map: std::collections::HashMap::new(),
+                    usage_path: None,
+                    usage: None,
+                    tool_calls_path: None,
+                    tool_name_path: None,
+                    tool_args_path: None,
+                    tool_id_path: None,
+                    args_encoding: ArgsEncoding::default(),
+                    reasoning_path: None,
+                    reasoning_text_path: None,
+                    reasoning_signature_path: None,
+                    reasoning_redacted_path: None,
+                    choices_path: None,
+                    logprobs_path: None,
+                    logprob_token_path: None,
+                    logprob_value_path: None,
+                    top_logprobs_path: None,
+                    citations_path: None,
+                    citation_document_path: None,
+                    citation_text_path: None,
+                    response_id_path: None,
                 },
                 stream: StreamNormalization {
                     protocol: "sse".to_string(),
@@ -428,8 +562,10 @@ pub fn limited_provider() -> ProviderSpec {
                         type_path: "$.type".to_string(),
                         routes: vec![],
                     },
+                    usage: None,
                 },
             },
+            extensions: None,
         }],
     }
 }
@@ -441,27 +577,39 @@ pub fn multi_turn_prompt() -> PromptSpec {
         messages: vec![
             Message {
                 role: MessageRole::System,
-                content: "You are a helpful assistant.".to_string(),
+                content: "You are a helpful assistant.".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::User,
-                content: "What is the capital of France?".to_string(),
+                content: "What is the capital of France?".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::Assistant,
-                content: "The capital of France is Paris.".to_string(),
+                content: "The capital of France is Paris.".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
             Message {
                 role: MessageRole::User,
-                content: "What is its population?".to_string(),
+                content: "What is its population?".into(),
                 name: None,
                 metadata: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning: None,
             },
         ],
         tools: None,
@@ -472,6 +620,9 @@ pub fn multi_turn_prompt() -> PromptSpec {
         media: None,
         advanced: None,
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     }
 }
 
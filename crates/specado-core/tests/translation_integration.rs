@@ -9,7 +9,7 @@
 mod test_support;
 
 use serde_json::{json, Value};
-use specado_core::{translate, StrictMode};
+use specado_core::{convert, translate, StrictMode};
 use specado_core::error::LossinessCode;
 use specado_core::types::{
     PromptSpec, 
@@ -196,6 +196,11 @@ fn test_temperature_clamping() {
         top_k: None,
         frequency_penalty: None,
         presence_penalty: None,
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
 
     let provider = test_support::openai_provider();
@@ -241,6 +246,9 @@ fn test_empty_messages() {
         media: None,
         advanced: None,
         strict_mode: StrictMode::Warn,
+        rag: None,
+        conversation: None,
+        preferences: None,
     };
 
     let provider = test_support::openai_provider();
@@ -282,6 +290,11 @@ fn test_comprehensive_lossiness_tracking() {
         top_k: None,
         frequency_penalty: None,
         presence_penalty: None,
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
 
     let provider = test_support::limited_provider();
@@ -371,7 +384,7 @@ fn test_media_translation() {
     
     // Add media to the message (if supported by types)
     // Note: This test assumes media is handled through message content
-    prompt.messages[0].content = "What's in this image? [image would be here]".to_string();
+    prompt.messages[0].content = "What's in this image? [image would be here]".into();
     
     let provider = test_support::openai_provider();
     let result = translate(&prompt, &provider, "gpt-5", StrictMode::Warn)
@@ -411,6 +424,11 @@ fn test_cross_provider_temperature_scaling() {
         top_k: None,
         frequency_penalty: None,
         presence_penalty: None,
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
 
     // Test with OpenAI (0-2 range)
@@ -431,6 +449,93 @@ fn test_cross_provider_temperature_scaling() {
     assert!(anthropic_request.contains_key("temperature"));
 }
 
+/// Test converting a native request straight from one provider to another
+#[test]
+fn test_convert_openai_request_to_anthropic() {
+    let openai_provider = test_support::openai_provider();
+    let anthropic_provider = test_support::anthropic_provider();
+    let request = json!({
+        "model": "gpt-5",
+        "messages": [
+            {"role": "system", "content": "You are a helpful assistant."},
+            {"role": "user", "content": "What is 2+2?"}
+        ],
+        "max_tokens": 256,
+        "user": "user-42"
+    });
+
+    let result = convert(
+        &request,
+        (&openai_provider, "gpt-5"),
+        (&anthropic_provider, "claude-opus-4.1"),
+        StrictMode::Warn,
+    )
+    .expect("Conversion should succeed");
+
+    let converted = result.provider_request_json.as_object().unwrap();
+    assert_eq!(converted["model"], "claude-opus-4.1");
+    assert_eq!(converted["system"], "You are a helpful assistant.");
+    assert_eq!(converted["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(converted["max_tokens"], 256);
+
+    // The source-side drop is reported alongside the target hop's items
+    let user_item = result.lossiness.items.iter()
+        .find(|item| item.path == "user")
+        .expect("Dropped source field should be reported");
+    assert_eq!(user_item.code, LossinessCode::Drop);
+    assert_eq!(result.lossiness.summary.total_items, result.lossiness.items.len());
+
+    // Strict mode refuses to lose the source field
+    assert!(convert(
+        &request,
+        (&openai_provider, "gpt-5"),
+        (&anthropic_provider, "claude-opus-4.1"),
+        StrictMode::Strict,
+    )
+    .is_err());
+}
+
+/// Test converting an Anthropic request with thinking blocks to OpenAI
+#[test]
+fn test_convert_anthropic_thinking_to_openai() {
+    let openai_provider = test_support::openai_provider();
+    let anthropic_provider = test_support::anthropic_provider();
+    let request = json!({
+        "model": "claude-opus-4.1",
+        "messages": [
+            {"role": "user", "content": "What is 2+2?"},
+            {"role": "assistant", "content": [
+                {"type": "thinking", "thinking": "Adding the numbers.", "signature": "sig-1"},
+                {"type": "redacted_thinking", "data": "opaque"},
+                {"type": "text", "text": "4"}
+            ]},
+            {"role": "user", "content": "And 3+3?"}
+        ],
+        "max_tokens": 256,
+        "temperature": 0.6
+    });
+
+    let result = convert(
+        &request,
+        (&anthropic_provider, "claude-opus-4.1"),
+        (&openai_provider, "gpt-5"),
+        StrictMode::Warn,
+    )
+    .expect("Conversion should succeed");
+
+    // Sampling values keep their decimal form
+    let converted = &result.provider_request_json;
+    assert_eq!(serde_json::to_string(&converted["temperature"]).unwrap(), "0.6");
+    assert_eq!(converted["messages"][1]["content"], "4");
+
+    // Each discarded reasoning block is reported as a drop
+    let dropped: Vec<&str> = result.lossiness.items.iter()
+        .filter(|item| item.code == LossinessCode::Drop && item.path.contains(".reasoning["))
+        .map(|item| item.path.as_str())
+        .collect();
+    assert_eq!(dropped, ["messages[1].reasoning[0]", "messages[1].reasoning[1]"]);
+}
+
 /// Test max output tokens capping
 #[test]
 fn test_max_output_tokens_capping() {
@@ -472,6 +577,11 @@ fn test_strict_mode_multiple_failures() {
         top_k: None,
         frequency_penalty: None,
         presence_penalty: None,
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
 
     let provider = test_support::limited_provider();
@@ -662,6 +772,11 @@ fn test_complex_multi_feature_request() {
         top_k: Some(50),
         frequency_penalty: Some(0.3),
         presence_penalty: Some(0.1),
+        candidate_count: None,
+        stop: None,
+        logprobs: None,
+        top_logprobs: None,
+        logit_bias: None,
     });
     prompt.limits = Some(Limits {
         max_output_tokens: Some(2000),
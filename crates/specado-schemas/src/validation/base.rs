@@ -345,6 +345,44 @@ impl ValidationHelpers {
             ))
         }
     }
+
+    /// Validate a JSON instance against a JSON Schema, collecting every violation
+    ///
+    /// Error paths use the `$.field[0]` form of the instance location.
+    pub fn validate_against_schema(instance: &Value, schema: &Value) -> Result<(), ValidationErrors> {
+        let validator = jsonschema::validator_for(schema).map_err(|e| ValidationErrors {
+            errors: vec![ValidationError::new("$", format!("Invalid JSON Schema: {}", e))],
+        })?;
+
+        let mut errors = ValidationErrors::new();
+        for error in validator.iter_errors(instance) {
+            errors.add(ValidationError::with_violations(
+                pointer_to_path(error.instance_path.as_str()),
+                error.to_string(),
+                vec![ValidationError::create_violation(
+                    "json_schema",
+                    error.schema_path.as_str(),
+                    error.instance.to_string(),
+                )],
+            ));
+        }
+        errors.into_result()
+    }
+}
+
+/// Convert a JSON pointer (`/items/0/name`) into a `$.items[0].name` path
+fn pointer_to_path(pointer: &str) -> String {
+    let mut path = String::from("$");
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            path.push('.');
+            path.push_str(&segment);
+        }
+    }
+    path
 }
 
 #[cfg(test)]
@@ -384,6 +422,27 @@ mod tests {
         assert!(ValidationHelpers::validate_env_var_reference("${env:lowercase}", &context).is_err());
     }
 
+    #[test]
+    fn test_validate_against_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["items"],
+            "properties": {
+                "items": {"type": "array", "items": {"type": "string"}}
+            }
+        });
+
+        assert!(ValidationHelpers::validate_against_schema(&serde_json::json!({"items": ["a"]}), &schema).is_ok());
+
+        let errors = ValidationHelpers::validate_against_schema(&serde_json::json!({"items": ["a", 2]}), &schema)
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.errors[0].path, "$.items[1]");
+
+        let invalid_schema = serde_json::json!({"type": "not-a-type"});
+        assert!(ValidationHelpers::validate_against_schema(&serde_json::json!({}), &invalid_schema).is_err());
+    }
+
     #[test]
     fn test_validate_url_scheme() {
         let context = ValidationContext::new(ValidationMode::Strict);
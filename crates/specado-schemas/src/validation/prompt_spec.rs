@@ -125,6 +125,33 @@ impl PromptSpecValidator {
             }
         }
 
+        // Rule: tool messages must answer a tool call, and only assistants may make tool calls
+        if let Some(messages) = spec.get("messages").and_then(|m| m.as_array()) {
+            for (idx, message) in messages.iter().enumerate() {
+                let role = message.get("role").and_then(|r| r.as_str());
+                let message_ctx = ctx.child("messages").child_index(idx);
+                
+                if role == Some("tool") {
+                    let has_id = message.get("tool_call_id")
+                        .and_then(|id| id.as_str())
+                        .is_some_and(|id| !id.is_empty());
+                    if !has_id {
+                        errors.push(ValidationError::new(
+                            message_ctx.child("tool_call_id").path.clone(),
+                            "tool messages require a tool_call_id",
+                        ));
+                    }
+                }
+                
+                if message.get("tool_calls").is_some_and(|calls| !calls.is_null()) && role != Some("assistant") {
+                    errors.push(ValidationError::new(
+                        message_ctx.child("tool_calls").path.clone(),
+                        format!("tool_calls are only valid on assistant messages, found role {:?}", role),
+                    ));
+                }
+            }
+        }
+
         // Rule: reasoning_tokens only valid when model_class is "ReasoningChat"
         if let Some(limits) = spec.get("limits") {
             if limits.get("reasoning_tokens").is_some() {
@@ -326,6 +353,32 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("tool_choice requires tools"));
     }
 
+    #[test]
+    fn test_tool_messages() {
+        let validator = PromptSpecValidator::new().unwrap();
+        let mut spec = json!({
+            "model_class": "Chat",
+            "messages": [
+                {"role": "user", "content": "Weather in Paris?"},
+                {"role": "assistant", "content": "", "tool_calls": [
+                    {"id": "call_1", "name": "get_weather", "arguments": {"city": "Paris"}}
+                ]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "Sunny"}
+            ],
+            "strict_mode": "Standard"
+        });
+        assert!(validator.validate(&spec).is_ok());
+        
+        spec["messages"][2].as_object_mut().unwrap().remove("tool_call_id");
+        let result = validator.validate(&spec);
+        assert!(result.unwrap_err().to_string().contains("tool_call_id"));
+        
+        spec["messages"][2]["tool_call_id"] = json!("call_1");
+        spec["messages"][0]["tool_calls"] = json!([]);
+        let result = validator.validate(&spec);
+        assert!(result.unwrap_err().to_string().contains("only valid on assistant messages"));
+    }
+
     #[test]
     fn test_reasoning_tokens_model_class() {
         let validator = PromptSpecValidator::new().unwrap();
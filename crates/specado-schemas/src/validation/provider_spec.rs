@@ -108,6 +108,15 @@ impl ProviderSpecValidator {
         &self.schema
     }
 
+    /// Stream protocols allowed by the schema's `protocol` enum
+    pub fn stream_protocols(&self) -> Vec<&str> {
+        self.schema
+            .pointer("/$defs/Model/properties/response_normalization/properties/stream/properties/protocol/enum")
+            .and_then(|e| e.as_array())
+            .map(|protocols| protocols.iter().filter_map(|p| p.as_str()).collect())
+            .unwrap_or_default()
+    }
+
     /// Validate custom rules for ProviderSpec
     fn validate_custom_rules(&self, spec: &Value, ctx: &ValidationContext) -> Vec<ValidationError> {
         let mut errors = Vec::new();
@@ -186,6 +195,20 @@ impl ProviderSpecValidator {
                             }
                         }
                     }
+                    // Check stream protocol has a decoder
+                    if let Some(protocol) = norm.get("stream").and_then(|s| s.get("protocol")).and_then(|p| p.as_str()) {
+                        let supported = self.stream_protocols();
+                        if !supported.contains(&protocol) {
+                            errors.push(ValidationError::new(
+                                format!("$.models[{}].response_normalization.stream.protocol", idx),
+                                format!(
+                                    "Unsupported stream protocol '{}', expected one of: {}",
+                                    protocol,
+                                    supported.join(", ")
+                                ),
+                            ));
+                        }
+                    }
                     // Check stream event selector paths
                     if let Some(stream) = norm.get("stream") {
                         if let Some(event_selector) = stream.get("event_selector") {
@@ -223,6 +246,48 @@ impl ProviderSpecValidator {
             }
         }
 
+        // Rule: declared transformation rules must be well formed and have unique ids
+        if let Some(models) = spec.get("models").and_then(|m| m.as_array()) {
+            for (idx, model) in models.iter().enumerate() {
+                if let Some(rules) = model.get("transformations").and_then(|t| t.as_array()) {
+                    let mut seen_ids = std::collections::HashSet::new();
+                    for (rule_idx, rule) in rules.iter().enumerate() {
+                        let rule_path = format!("$.models[{}].transformations[{}]", idx, rule_idx);
+                        match rule.get("id").and_then(|v| v.as_str()) {
+                            Some(id) if !id.is_empty() => {
+                                if !seen_ids.insert(id) {
+                                    errors.push(ValidationError::new(
+                                        format!("{}.id", rule_path),
+                                        format!("Duplicate transformation rule id '{}'", id),
+                                    ));
+                                }
+                            }
+                            _ => errors.push(ValidationError::new(
+                                format!("{}.id", rule_path),
+                                "Transformation rule id cannot be empty".to_string(),
+                            )),
+                        }
+                        match rule.get("source_path").and_then(|v| v.as_str()) {
+                            Some(path) if path.starts_with('$') => {}
+                            _ => errors.push(ValidationError::new(
+                                format!("{}.source_path", rule_path),
+                                "source_path must be a JSONPath starting with '$'".to_string(),
+                            )),
+                        }
+                        if let Some(direction) = rule.get("direction").and_then(|v| v.as_str()) {
+                            if !TRANSFORMATION_DIRECTIONS.contains(&direction) {
+                                errors.push(ValidationError::new(
+                                    format!("{}.direction", rule_path),
+                                    format!("Unknown transformation direction '{}'", direction),
+                                ));
+                            }
+                        }
+                        validate_transformation(&rule_path, rule, &mut errors);
+                    }
+                }
+            }
+        }
+
         // Rule: tooling.tool_choice_modes must include "auto" if tools supported
         if let Some(capabilities) = spec.get("capabilities") {
             if capabilities.get("supports_tools").and_then(|v| v.as_bool()) == Some(true) {
@@ -295,6 +360,131 @@ impl ProviderSpecValidator {
     }
 }
 
+/// Transformation types that can be declared in a spec
+const TRANSFORMATION_TYPES: &[&str] = &[
+    "type_conversion",
+    "enum_mapping",
+    "unit_conversion",
+    "field_rename",
+    "default_value",
+    "conditional",
+];
+
+/// Directions a transformation rule can run in
+const TRANSFORMATION_DIRECTIONS: &[&str] = &["forward", "reverse", "bidirectional"];
+
+/// Check a transformation's type and the fields that type requires
+fn validate_transformation(path: &str, transformation: &Value, errors: &mut Vec<ValidationError>) {
+    let transformation_type = match transformation.get("type").and_then(|t| t.as_str()) {
+        Some(t) => t,
+        None => {
+            errors.push(ValidationError::new(
+                format!("{}.type", path),
+                "Transformation type is required".to_string(),
+            ));
+            return;
+        }
+    };
+
+    if !TRANSFORMATION_TYPES.contains(&transformation_type) {
+        let message = if transformation_type == "custom" {
+            "Custom transformations can only be registered in code".to_string()
+        } else {
+            format!(
+                "Unknown transformation type '{}', expected one of: {}",
+                transformation_type,
+                TRANSFORMATION_TYPES.join(", ")
+            )
+        };
+        errors.push(ValidationError::new(format!("{}.type", path), message));
+        return;
+    }
+
+    let required: &[&str] = match transformation_type {
+        "type_conversion" => &["from", "to"],
+        "enum_mapping" => &["mappings"],
+        "unit_conversion" => &["formula"],
+        "default_value" => &["value"],
+        "conditional" => &["condition", "if_true"],
+        _ => &[],
+    };
+    for field in required {
+        if transformation.get(*field).is_none() {
+            errors.push(ValidationError::new(
+                format!("{}.{}", path, field),
+                format!("{} transformations require '{}'", transformation_type, field),
+            ));
+        }
+    }
+
+    match transformation_type {
+        "field_rename" => {
+            let has_name = transformation.get("new_name").and_then(|n| n.as_str()).is_some_and(|n| !n.is_empty());
+            if !has_name && transformation.get("target_path").is_none() {
+                errors.push(ValidationError::new(
+                    format!("{}.new_name", path),
+                    "field_rename transformations require 'new_name' or 'target_path'".to_string(),
+                ));
+            }
+        }
+        "conditional" => {
+            if let Some(condition) = transformation.get("condition") {
+                validate_condition(&format!("{}.condition", path), condition, errors);
+            }
+            for branch in ["if_true", "if_false"] {
+                if let Some(inner) = transformation.get(branch) {
+                    validate_transformation(&format!("{}.{}", path, branch), inner, errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a transformation condition and its nested conditions
+fn validate_condition(path: &str, condition: &Value, errors: &mut Vec<ValidationError>) {
+    let Some((kind, body)) = condition.as_object().filter(|c| c.len() == 1).and_then(|c| c.iter().next()) else {
+        errors.push(ValidationError::new(
+            path.to_string(),
+            "Condition must have exactly one of: equals, exists, matches, and, or, not".to_string(),
+        ));
+        return;
+    };
+
+    match kind.as_str() {
+        "equals" | "exists" | "matches" => {
+            if body.get("path").and_then(|p| p.as_str()).is_none_or(|p| !p.starts_with('$')) {
+                errors.push(ValidationError::new(
+                    format!("{}.{}.path", path, kind),
+                    "Condition path must be a JSONPath starting with '$'".to_string(),
+                ));
+            }
+            if kind == "matches" && body.get("pattern").and_then(|p| p.as_str()).is_none() {
+                errors.push(ValidationError::new(
+                    format!("{}.matches.pattern", path),
+                    "matches conditions require a 'pattern'".to_string(),
+                ));
+            }
+        }
+        "and" | "or" => match body.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    validate_condition(&format!("{}.{}[{}]", path, kind, i), item, errors);
+                }
+            }
+            None => errors.push(ValidationError::new(
+                format!("{}.{}", path, kind),
+                format!("'{}' conditions require an array", kind),
+            )),
+        },
+        "not" => validate_condition(&format!("{}.not", path), body, errors),
+        other => errors.push(ValidationError::new(
+            path.to_string(),
+            format!("Unknown condition '{}'", other),
+        )),
+    }
+}
+
 impl SchemaValidator for ProviderSpecValidator {
     type Input = Value;
 
@@ -542,6 +732,74 @@ mod tests {
         assert!(validator.validate(&spec).is_ok());
     }
 
+    #[test]
+    fn test_stream_protocols() {
+        let validator = ProviderSpecValidator::new().unwrap();
+        let mut spec = create_basic_provider_spec();
+        
+        spec["models"] = json!([{
+            "id": "test-model",
+            "response_normalization": {
+                "stream": {
+                    "protocol": "websocket",
+                    "event_selector": { "type_path": "$.type", "routes": [] }
+                }
+            }
+        }]);
+        
+        let result = validator.validate(&spec);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Unsupported stream protocol"));
+        
+        assert_eq!(validator.stream_protocols(), ["sse", "ndjson"]);
+        for protocol in validator.stream_protocols() {
+            spec["models"][0]["response_normalization"]["stream"]["protocol"] = json!(protocol);
+            assert!(validator.validate(&spec).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_transformation_rules() {
+        let validator = ProviderSpecValidator::new().unwrap();
+        let mut spec = create_basic_provider_spec();
+
+        spec["models"] = json!([{
+            "id": "test-model",
+            "transformations": [
+                {
+                    "id": "temperature_scale",
+                    "source_path": "$.temperature",
+                    "type": "unit_conversion",
+                    "from_unit": "openai",
+                    "to_unit": "anthropic",
+                    "formula": { "linear": { "scale": 0.5 } },
+                    "optional": true
+                },
+                {
+                    "id": "reasoning_effort",
+                    "source_path": "$.reasoning_effort",
+                    "type": "conditional",
+                    "condition": { "and": [{ "exists": { "path": "$.reasoning_effort" } }] },
+                    "if_true": { "type": "enum_mapping", "mappings": { "max": "high" } }
+                }
+            ]
+        }]);
+        assert!(validator.validate(&spec).is_ok());
+
+        let cases = [
+            ("/id", json!("temperature_scale"), "Duplicate transformation rule id"),
+            ("/if_true", json!({ "type": "enum_mapping" }), "enum_mapping transformations require 'mappings'"),
+            ("/condition", json!({ "exists": { "path": "effort" } }), "Condition path must be a JSONPath"),
+            ("/type", json!("custom"), "Custom transformations can only be registered in code"),
+        ];
+        for (pointer, value, expected) in cases {
+            let mut invalid = spec.clone();
+            *invalid.pointer_mut(&format!("/models/0/transformations/1{}", pointer)).unwrap() = value;
+            let message = validator.validate(&invalid).unwrap_err().to_string();
+            assert!(message.contains(expected), "{}: {}", pointer, message);
+        }
+    }
+
     #[test]
     fn test_validation_modes() {
         let validator = ProviderSpecValidator::new().unwrap();